@@ -0,0 +1,233 @@
+// Benchmarks de las extrínsecas cuyo peso depende de cuántos kitties toca y de cada cierre que
+// hace `on_initialize` (subastas, alquileres y regalos).
+//
+// Los resultados van a `weights.rs`. Solo se compila con la feature `runtime-benchmarks`.
+
+use super::*;
+use frame::deps::frame_benchmarking::v2::*;
+use frame::arithmetic::Bounded;
+use frame::arithmetic::One;
+use frame::arithmetic::Zero;
+
+/// Da a `who` saldo de sobra para depósitos y stake.
+fn fund<T: Config>(who: &T::AccountId) {
+    T::NativeBalance::set_balance(who, BalanceOf::<T>::max_value() / 8u32.into());
+}
+
+/// Sube el límite de `who` hasta `MaxKittiesOwned` con stake de capacidad, si el runtime lo permite.
+fn stake_to_the_cap<T: Config>(who: &T::AccountId) {
+    let per_tier = T::StakePerCapacityTier::get();
+    let kitties_per_tier = T::KittiesPerCapacityTier::get().max(1);
+    let missing = T::MaxKittiesOwned::get().saturating_sub(T::BaseKittiesPerAccount::get());
+    if per_tier.is_zero() || missing == 0 {
+        return;
+    }
+    let tiers = missing.div_ceil(kitties_per_tier);
+    Pallet::<T>::do_stake_for_capacity(who.clone(), per_tier.saturating_mul(tiers.into()))
+        .expect("la cuenta tiene saldo para el stake");
+}
+
+/// Crea `n` kitties de `who`, cada uno a la venta, que es el caso más caro de transferir.
+fn listed_kitties<T: Config>(who: &T::AccountId, n: u32) -> Vec<[u8; 32]> {
+    let price = T::NativeBalance::minimum_balance();
+    (0..n)
+        .map(|_| {
+            let kitty_id = Pallet::<T>::gen_dna(who);
+            Pallet::<T>::mint(who.clone(), kitty_id).expect("la cuenta tiene sitio");
+            Pallet::<T>::do_set_price(who.clone(), kitty_id, Some(price), None).expect("el dueño puede anunciarlo");
+            kitty_id
+        })
+        .collect()
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    // Un mint por destinatario, cada uno a una cuenta distinta.
+    #[benchmark]
+    fn force_mint_batch(n: Linear<1, { T::MaxAirdropRecipients::get() }>) -> Result<(), BenchmarkError> {
+        let origin = T::AirdropOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+        let recipients: Vec<T::AccountId> = (0..n).map(|i| account("recipient", i, 0)).collect();
+        recipients.iter().for_each(fund::<T>);
+        let recipients = BoundedVec::try_from(recipients).expect("`n` no pasa de `MaxAirdropRecipients`");
+
+        #[extrinsic_call]
+        _(origin as T::RuntimeOrigin, recipients);
+
+        assert_eq!(CountForKitties::<T>::get(), n);
+        Ok(())
+    }
+
+    // Quien llama tiene `n` kitties, todos a la venta.
+    #[benchmark]
+    fn transfer_all(n: Linear<1, { T::MaxKittiesOwned::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let to: T::AccountId = account("to", 0, 0);
+        fund::<T>(&caller);
+        fund::<T>(&to);
+        stake_to_the_cap::<T>(&caller);
+        stake_to_the_cap::<T>(&to);
+        listed_kitties::<T>(&caller, n);
+
+        #[extrinsic_call]
+        _(frame_system::RawOrigin::Signed(caller.clone()), to.clone());
+
+        assert_eq!(KittiesOwned::<T>::decode_len(&to), Some(n as usize));
+        assert!(KittiesOwned::<T>::get(&caller).is_empty());
+    }
+
+    // Como `transfer_all`, pero con la lista de `n` kitties en la llamada.
+    #[benchmark]
+    fn transfer_many(n: Linear<1, { T::MaxBatchTransfer::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        let to: T::AccountId = account("to", 0, 0);
+        fund::<T>(&caller);
+        fund::<T>(&to);
+        stake_to_the_cap::<T>(&caller);
+        stake_to_the_cap::<T>(&to);
+        let kitty_ids = listed_kitties::<T>(&caller, n);
+        let kitty_ids = BoundedVec::try_from(kitty_ids).expect("`n` no pasa de `MaxBatchTransfer`");
+
+        #[extrinsic_call]
+        _(frame_system::RawOrigin::Signed(caller.clone()), to.clone(), kitty_ids);
+
+        assert_eq!(KittiesOwned::<T>::decode_len(&to), Some(n as usize));
+    }
+
+    // Peor caso: antes de los `n` anuncios que compra salta otros `n` más baratos, reservados a
+    // otra cuenta, que son los que más lecturas cuestan.
+    #[benchmark]
+    fn sweep_floor(n: Linear<1, { T::MaxSweep::get() }>) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let seller: T::AccountId = account("seller", 0, 0);
+        let reserved: T::AccountId = account("reserved", 0, 0);
+        fund::<T>(&caller);
+        fund::<T>(&seller);
+        stake_to_the_cap::<T>(&seller);
+        let floor = T::NativeBalance::minimum_balance();
+        let price = floor.saturating_mul(2u32.into());
+        for i in 0..n.saturating_mul(2) {
+            let kitty_id = Pallet::<T>::gen_dna(&seller);
+            Pallet::<T>::mint(seller.clone(), kitty_id)?;
+            let (price, buyer) = if i < n { (floor, Some(reserved.clone())) } else { (price, None) };
+            Pallet::<T>::do_set_price(seller.clone(), kitty_id, Some(price), buyer)?;
+        }
+
+        #[extrinsic_call]
+        _(frame_system::RawOrigin::Signed(caller.clone()), n, price);
+
+        assert_eq!(KittiesOwned::<T>::decode_len(&caller), Some(n as usize));
+        Ok(())
+    }
+
+    // Cada kitty del lote sale de su anuncio suelto.
+    #[benchmark]
+    fn create_bundle(n: Linear<1, { T::MaxBundleSize::get() }>) {
+        let caller: T::AccountId = whitelisted_caller();
+        fund::<T>(&caller);
+        stake_to_the_cap::<T>(&caller);
+        let kitty_ids = listed_kitties::<T>(&caller, n);
+        let kitty_ids = BoundedVec::try_from(kitty_ids).expect("`n` no pasa de `MaxBundleSize`");
+        let bundle_id = NextBundleId::<T>::get();
+
+        #[extrinsic_call]
+        _(frame_system::RawOrigin::Signed(caller.clone()), kitty_ids, T::NativeBalance::minimum_balance());
+
+        assert_eq!(Bundles::<T>::get(bundle_id).map(|bundle| bundle.kitty_ids.len()), Some(n as usize));
+    }
+
+    // Un lote de `n` kitties; el pago se reparte entre todos ellos.
+    #[benchmark]
+    fn buy_bundle(n: Linear<1, { T::MaxBundleSize::get() }>) -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let seller: T::AccountId = account("seller", 0, 0);
+        fund::<T>(&caller);
+        fund::<T>(&seller);
+        stake_to_the_cap::<T>(&caller);
+        stake_to_the_cap::<T>(&seller);
+        let kitty_ids = listed_kitties::<T>(&seller, n);
+        let kitty_ids = BoundedVec::try_from(kitty_ids).expect("`n` no pasa de `MaxBundleSize`");
+        let price = T::NativeBalance::minimum_balance().saturating_mul(n.into());
+        let bundle_id = NextBundleId::<T>::get();
+        Pallet::<T>::do_create_bundle(seller, kitty_ids, price)?;
+
+        #[extrinsic_call]
+        _(frame_system::RawOrigin::Signed(caller.clone()), bundle_id, price);
+
+        assert_eq!(KittiesOwned::<T>::decode_len(&caller), Some(n as usize));
+        Ok(())
+    }
+
+    // Una subasta con puja, de un kitty que no creó el vendedor: entrega el kitty y reparte el
+    // pago entre vendedor, comisión y regalía. Es lo que hace `on_initialize` por cada subasta.
+    #[benchmark]
+    fn settle_auction() -> Result<(), BenchmarkError> {
+        let caller: T::AccountId = whitelisted_caller();
+        let minter: T::AccountId = account("minter", 0, 0);
+        let seller: T::AccountId = account("seller", 0, 0);
+        let bidder: T::AccountId = account("bidder", 0, 0);
+        [&caller, &minter, &seller, &bidder].into_iter().for_each(fund::<T>);
+        let kitty_id = Pallet::<T>::gen_dna(&minter);
+        Pallet::<T>::mint(minter.clone(), kitty_id)?;
+        Pallet::<T>::do_transfer(minter, seller.clone(), kitty_id)?;
+        let min_bid = T::NativeBalance::minimum_balance();
+        let auction_id = NextAuctionId::<T>::get();
+        Pallet::<T>::do_start_auction(seller, kitty_id, min_bid, One::one(), None)?;
+        Pallet::<T>::do_bid(bidder.clone(), auction_id, min_bid)?;
+        // La puja puede haber alargado la subasta.
+        let end = Auctions::<T>::get(auction_id).ok_or(BenchmarkError::Weightless)?.end;
+        frame_system::Pallet::<T>::set_block_number(end);
+
+        #[extrinsic_call]
+        _(frame_system::RawOrigin::Signed(caller), auction_id);
+
+        assert_eq!(Kitties::<T>::get(kitty_id).map(|kitty| kitty.owner), Some(bidder));
+        Ok(())
+    }
+
+    // Un alquiler con el pago pendiente que el inquilino ya no puede cubrir: el dueño cobra de la
+    // garantía. Es lo que hace `on_initialize` por cada alquiler que vence.
+    #[benchmark]
+    fn close_rental() -> Result<(), BenchmarkError> {
+        let owner: T::AccountId = account("owner", 0, 0);
+        let renter: T::AccountId = account("renter", 0, 0);
+        fund::<T>(&owner);
+        fund::<T>(&renter);
+        let kitty_id = Pallet::<T>::gen_dna(&owner);
+        Pallet::<T>::mint(owner.clone(), kitty_id)?;
+        let price = T::NativeBalance::minimum_balance();
+        Pallet::<T>::do_list_for_rent(owner.clone(), kitty_id, price, One::one(), price)?;
+        Pallet::<T>::do_rent(renter.clone(), kitty_id, One::one())?;
+        T::NativeBalance::set_balance(&renter, T::NativeBalance::minimum_balance());
+
+        #[block]
+        {
+            Pallet::<T>::close_rental(kitty_id);
+        }
+
+        assert!(Rentals::<T>::get(kitty_id).is_none());
+        Ok(())
+    }
+
+    // Un regalo sin reclamar. Es lo que hace `on_initialize` por cada regalo que caduca.
+    #[benchmark]
+    fn reclaim_gift() -> Result<(), BenchmarkError> {
+        let from: T::AccountId = account("from", 0, 0);
+        let to: T::AccountId = account("to", 0, 0);
+        fund::<T>(&from);
+        let kitty_id = Pallet::<T>::gen_dna(&from);
+        Pallet::<T>::mint(from.clone(), kitty_id)?;
+        Pallet::<T>::do_send_gift(from, to, kitty_id)?;
+
+        #[block]
+        {
+            Pallet::<T>::reclaim_gift(kitty_id);
+        }
+
+        assert!(Gifts::<T>::get(kitty_id).is_none());
+        Ok(())
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::tests::new_test_ext(), crate::tests::TestRuntime);
+}
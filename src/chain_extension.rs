@@ -0,0 +1,79 @@
+// Lado del pallet de una chain extension para contratos ink!.
+//
+// Los contratos llaman a la extensión con un `func_id` y una entrada codificada en SCALE; el
+// runtime (su `ChainExtension` de `pallet-contracts`) lee ambos del entorno, cobra el peso y
+// delega aquí con la cuenta del contrato como `caller`. Cada función pasa por la misma
+// extrínseca que usaría una cuenta firmada, así que respeta tasas, cuotas y bloqueos; las pausas,
+// que para las cuentas aplica el filtro del runtime, se comprueban aquí antes de despacharla.
+// Solo se compila con la feature `chain-extension`.
+
+use super::*;
+use codec::Decode;
+use codec::Encode;
+use frame::prelude::*;
+use frame::traits::UnfilteredDispatchable;
+
+/// Funciones que la extensión expone a los contratos.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum KittiesFunction {
+    Mint,     // () -> [u8; 32]: crea un kitty para el contrato y devuelve su id
+    Transfer, // (AccountId, [u8; 32]) -> (): transfiere un kitty del contrato
+    SetPrice, // ([u8; 32], Option<Balance>) -> (): pone a la venta o retira un kitty del contrato
+    Buy,      // ([u8; 32], Balance) -> (): compra un kitty a un precio máximo
+    PriceOf,  // [u8; 32] -> Option<Balance>: precio de venta de un kitty
+}
+
+impl KittiesFunction {
+    /// Función con identificador `func_id`, si existe.
+    pub fn from_id(func_id: u16) -> Option<Self> {
+        match func_id {
+            1 => Some(KittiesFunction::Mint),
+            2 => Some(KittiesFunction::Transfer),
+            3 => Some(KittiesFunction::SetPrice),
+            4 => Some(KittiesFunction::Buy),
+            5 => Some(KittiesFunction::PriceOf),
+            _ => None,
+        }
+    }
+}
+
+/// Ejecuta la función `func_id` en nombre de `caller` con la entrada `input` y devuelve la
+/// salida codificada.
+pub fn call<T: Config>(func_id: u16, caller: T::AccountId, mut input: &[u8]) -> Result<Vec<u8>, DispatchError> {
+    let function = KittiesFunction::from_id(func_id).ok_or(DispatchError::Other("UnknownFunction"))?;
+    let call = match function {
+        KittiesFunction::Mint => Call::<T>::create_kitty {},
+        KittiesFunction::Transfer => {
+            let (to, kitty_id) = decode::<(T::AccountId, [u8; 32])>(&mut input)?;
+            Call::<T>::transfer { to, kitty_id }
+        },
+        KittiesFunction::SetPrice => {
+            let (kitty_id, new_price) = decode::<([u8; 32], Option<BalanceOf<T>>)>(&mut input)?;
+            Call::<T>::set_price { kitty_id, new_price, whitelisted_buyer: None }
+        },
+        KittiesFunction::Buy => {
+            let (kitty_id, max_price) = decode::<([u8; 32], BalanceOf<T>)>(&mut input)?;
+            Call::<T>::buy_kitty { kitty_id, max_price }
+        },
+        KittiesFunction::PriceOf => {
+            let kitty_id = decode::<[u8; 32]>(&mut input)?;
+            return Ok(Pallet::<T>::price_of(kitty_id).encode());
+        },
+    };
+    Pallet::<T>::ensure_call_not_paused(&call)?;
+    call.dispatch_bypass_filter(frame_system::RawOrigin::Signed(caller.clone()).into())
+        .map_err(|error| error.error)?;
+    if function == KittiesFunction::Mint {
+        // `mint` añade el kitty nuevo al final de la lista del dueño.
+        let kitty_id = KittiesOwned::<T>::get(&caller).last().copied().ok_or(Error::<T>::NoKitty)?;
+        return Ok(kitty_id.encode());
+    }
+    Ok(Vec::new())
+}
+
+/// Decodifica la entrada entera de una función.
+fn decode<I: Decode>(input: &mut &[u8]) -> Result<I, DispatchError> {
+    let decoded = I::decode(input).map_err(|_| DispatchError::Other("InvalidInput"))?;
+    ensure!(input.is_empty(), DispatchError::Other("InvalidInput"));
+    Ok(decoded)
+}
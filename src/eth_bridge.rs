@@ -0,0 +1,93 @@
+// Mensajes del puente con Ethereum.
+//
+// Se codifican como `abi.encode` de Solidity para que el contrato ERC-721 del otro lado los lea
+// sin adaptadores: cada campo ocupa una palabra de 32 bytes, los enteros van en big-endian y las
+// direcciones alineadas a la derecha. El id del kitty es directamente el `tokenId` (`uint256`)
+// del ERC-721, y el ADN viaja como su hash keccak-256 para que el contrato pueda comprobarlo.
+
+use alloc::vec::Vec;
+use frame::hashing::Hash;
+use frame::hashing::Keccak256;
+
+// Tamaño de una palabra ABI.
+const WORD: usize = 32;
+
+/// Tamaño de un mensaje codificado: cuatro palabras ABI.
+pub const MESSAGE_LEN: usize = 4 * WORD;
+
+/// Kitty que sale hacia Ethereum:
+/// `abi.encode(uint64 nonce, uint256 tokenId, address to, bytes32 dnaHash)`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ExportMessage {
+    pub nonce: u64,           // Número de secuencia del canal de salida
+    pub token_id: [u8; 32],   // Id del kitty, que es el `tokenId` del ERC-721
+    pub to: [u8; 20],         // Dirección de Ethereum que recibe el token
+    pub dna_hash: [u8; 32],   // keccak-256 del ADN del kitty
+}
+
+impl ExportMessage {
+    /// Codificación ABI del mensaje.
+    pub fn abi_encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(MESSAGE_LEN);
+        out.extend_from_slice(&uint64_word(self.nonce));
+        out.extend_from_slice(&self.token_id);
+        out.extend_from_slice(&[0u8; WORD - 20]);
+        out.extend_from_slice(&self.to);
+        out.extend_from_slice(&self.dna_hash);
+        out
+    }
+
+    /// Compromiso que el puente firma y el contrato verifica: keccak-256 del mensaje codificado.
+    pub fn commitment(&self) -> [u8; 32] {
+        Keccak256::hash(&self.abi_encode()).into()
+    }
+}
+
+/// Kitty que vuelve de Ethereum:
+/// `abi.encode(uint64 nonce, uint256 tokenId, bytes32 recipient, bytes32 dnaHash)`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ImportMessage {
+    pub nonce: u64,           // Número de secuencia del canal de entrada
+    pub token_id: [u8; 32],   // Id del kitty
+    pub recipient: [u8; 32],  // Cuenta de esta cadena que recibe el kitty, codificada en SCALE
+    pub dna_hash: [u8; 32],   // keccak-256 del ADN que tenía el token
+}
+
+impl ImportMessage {
+    /// Codificación ABI del mensaje.
+    pub fn abi_encode(&self) -> [u8; MESSAGE_LEN] {
+        let mut out = [0u8; MESSAGE_LEN];
+        out[..WORD].copy_from_slice(&uint64_word(self.nonce));
+        out[WORD..2 * WORD].copy_from_slice(&self.token_id);
+        out[2 * WORD..3 * WORD].copy_from_slice(&self.recipient);
+        out[3 * WORD..].copy_from_slice(&self.dna_hash);
+        out
+    }
+
+    /// Decodifica un mensaje; el nonce no puede pasar de 64 bits.
+    pub fn abi_decode(input: &[u8; MESSAGE_LEN]) -> Option<Self> {
+        let word = |i: usize| -> [u8; 32] { input[i * WORD..(i + 1) * WORD].try_into().expect("una palabra") };
+        let nonce_word = word(0);
+        if nonce_word[..WORD - 8].iter().any(|byte| *byte != 0) {
+            return None;
+        }
+        Some(ImportMessage {
+            nonce: u64::from_be_bytes(nonce_word[WORD - 8..].try_into().expect("ocho bytes")),
+            token_id: word(1),
+            recipient: word(2),
+            dna_hash: word(3),
+        })
+    }
+}
+
+/// keccak-256 del ADN, tal como lo calcula el contrato.
+pub fn dna_hash(dna: &[u8]) -> [u8; 32] {
+    Keccak256::hash(dna).into()
+}
+
+/// `uint64` como palabra ABI.
+fn uint64_word(value: u64) -> [u8; 32] {
+    let mut word = [0u8; WORD];
+    word[WORD - 8..].copy_from_slice(&value.to_be_bytes());
+    word
+}
@@ -1,8 +1,27 @@
 use super::*; 
+use frame::arithmetic::One;
+use frame::arithmetic::Permill;
+use frame::arithmetic::SaturatedConversion;
+use frame::arithmetic::Saturating;
+use frame::arithmetic::Zero;
+use frame::deps::frame_support::storage::with_storage_layer;
 use frame::prelude::*; 
-use frame::primitives::BlakeTwo256; 
+use frame::hashing::BlakeTwo256; 
+use frame::traits::tokens::DepositConsequence;
+use frame::traits::tokens::Fortitude;
+use frame::traits::tokens::Precision;
 use frame::traits::tokens::Preservation; 
+use frame::traits::tokens::Provenance;
+use frame::traits::tokens::Restriction;
+use frame::traits::tokens::nonfungibles_v2;
+use frame::traits::fungibles::Inspect as _;
+use frame::traits::fungibles::Mutate as _;
+use frame::traits::fungibles::Create as _;
+use frame::traits::AccountIdConversion;
+use frame::traits::Contains;
 use frame::traits::Hash; 
+use alloc::string::String;
+use core::fmt::Write;
 
 
 impl<T: Config> Pallet<T> {
@@ -12,7 +31,12 @@ impl<T: Config> Pallet<T> {
     // -------------------------------------------------------------------------
     // Genera y devuelve un ADN único de 32 bytes para un nuevo kitty.
 	// Se usa información del bloque actual y el contador de kitties para garantizar unicidad.
-    pub fn gen_dna() -> [u8; 32] {
+    // Además se mezclan la cuenta que crea el kitty, su nonce y la aleatoriedad del runtime,
+    // para que nadie pueda precalcular el ADN resultante y elegir cuándo hacer el mint.
+    pub fn gen_dna(who: &T::AccountId) -> [u8; 32] {
+        // Semilla aleatoria del runtime; el `subject` la separa de otros usos de la misma fuente.
+        let (random_seed, _) = T::Randomness::random(b"kitties/dna");
+
         // Crea una "semilla" única combinando varios valores del sistema.
         // Esto evita que dos kitties generados en el mismo bloque tengan el mismo ADN.
         let unique_payload = (
@@ -20,138 +44,4337 @@ impl<T: Config> Pallet<T> {
             frame_system::Pallet::<T>::block_number(),   // Número del bloque actual
             frame_system::Pallet::<T>::extrinsic_index(),// Índice de la transacción dentro del bloque
             CountForKitties::<T>::get(),                 // Cantidad actual de kitties creados
+            who,                                         // Cuenta que crea el kitty
+            frame_system::Pallet::<T>::account_nonce(who), // Nonce de esa cuenta
+            random_seed,                                 // Aleatoriedad de la fuente configurada
         );
 
         // Aplica el hash Blake2-256 sobre el payload y convierte el resultado en [u8; 32].
         BlakeTwo256::hash_of(&unique_payload).into()
     }
 
+    /// Crea un kitty con ADN aleatorio para cada cuenta de `recipients`.
+    pub fn do_force_mint_batch(recipients: Vec<T::AccountId>) -> DispatchResult {
+        let count = recipients.len() as u32;
+        for who in recipients {
+            // `gen_dna` mezcla `CountForKitties`, así que repetir destinatario da otro ADN.
+            let dna = Self::gen_dna(&who);
+            Self::mint(who, dna)?;
+        }
+        Self::deposit_event(Event::<T>::BatchMinted { recipients: count });
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: do_commit_create() / do_reveal_create()
+    // -------------------------------------------------------------------------
+    // El ADN de `gen_dna` sale de datos que ya están en la cadena, así que un bot puede simular
+    // el mint y enviarlo solo cuando el resultado es raro. En dos fases el ADN depende del hash
+    // de un bloque que no existe al comprometerse y de una sal que solo conoce quien mintea.
+
+    /// Guarda el compromiso de `who`. Uno que ya no se puede revelar se sustituye.
+    pub fn do_commit_create(who: T::AccountId, commitment: [u8; 32]) -> DispatchResult {
+        if let Some(pending) = MintCommits::<T>::get(&who) {
+            ensure!(Self::commit_expired(&pending), Error::<T>::CommitPending);
+        }
+
+        let now = frame_system::Pallet::<T>::block_number();
+        let target = now.saturating_add(T::RevealDelay::get());
+        MintCommits::<T>::insert(&who, MintCommit { commitment, target });
+
+        Self::deposit_event(Event::<T>::MintCommitted { who, target });
+        Ok(())
+    }
+
+    /// Comprueba `salt` contra el compromiso de `who` y crea su kitty.
+    pub fn do_reveal_create(who: T::AccountId, salt: [u8; 32]) -> DispatchResult {
+        let pending = MintCommits::<T>::get(&who).ok_or(Error::<T>::NoMintCommit)?;
+        ensure!(BlakeTwo256::hash(&salt).0 == pending.commitment, Error::<T>::BadReveal);
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(now > pending.target, Error::<T>::RevealTooEarly);
+        ensure!(!Self::commit_expired(&pending), Error::<T>::CommitExpired);
+
+        MintCommits::<T>::remove(&who);
+        let block_hash = frame_system::Pallet::<T>::block_hash(pending.target);
+        let dna: [u8; 32] = BlakeTwo256::hash_of(&(salt, block_hash, &who)).into();
+        Self::mint(who, dna)
+    }
+
+    /// Un compromiso caduca cuando `frame_system` ya no guarda el hash de su bloque objetivo.
+    fn commit_expired(pending: &MintCommit<BlockNumberFor<T>>) -> bool {
+        let now = frame_system::Pallet::<T>::block_number();
+        now > pending.target &&
+            frame_system::Pallet::<T>::block_hash(pending.target) == Default::default()
+    }
+
+    // -------------------------------------------------------------------------
+    //  Función: expand_dna()
+    // -------------------------------------------------------------------------
+    /// Ajusta una semilla de 32 bytes al genoma de `DnaLength` bytes del runtime: se recorta si
+    /// el genoma es más corto y se extiende con hashes encadenados si es más largo.
+    /// Con `DnaLength = 32` el genoma es la misma semilla.
+    pub fn expand_dna(seed: &[u8; 32]) -> KittyDna<T> {
+        let length = T::DnaLength::get() as usize;
+        let mut dna: Vec<u8> = Vec::with_capacity(length);
+        dna.extend_from_slice(&seed[..length.min(32)]);
+        let mut block: [u8; 32] = *seed;
+        while dna.len() < length {
+            block = BlakeTwo256::hash(&block).into();
+            let take = (length - dna.len()).min(32);
+            dna.extend_from_slice(&block[..take]);
+        }
+        // Nunca supera `DnaLength`, así que la conversión no puede fallar.
+        BoundedVec::truncate_from(dna)
+    }
+
     // -------------------------------------------------------------------------
     //  Función: mint()
     // -------------------------------------------------------------------------
     /// Crea un nuevo kitty y lo asigna al propietario indicado.
     /// Lanza errores si ya existe un kitty con ese ADN o si el propietario tiene demasiados.
+    /// `dna` es la semilla de 32 bytes que identifica al kitty; el genoma se deriva de ella.
+    /// Si hay una temporada en curso con cupo, el kitty sale como edición limitada.
     pub fn mint(owner: T::AccountId, dna: [u8; 32]) -> DispatchResult {
+        let mut genome = Self::expand_dna(&dna);
+        let season = Self::open_season();
+        if let Some((_, season)) = &season {
+            Self::mark_season(&mut genome, season);
+        }
+        Self::mint_with_dna(owner, dna, genome, 0, None)?;
+
+        if let Some((season_id, mut season)) = season {
+            season.minted.saturating_inc();
+            Kitties::<T>::mutate(dna, |maybe_kitty| {
+                if let Some(kitty) = maybe_kitty {
+                    kitty.special_edition = Some(season_id);
+                }
+            });
+            Self::deposit_event(Event::<T>::SpecialEditionMinted {
+                kitty_id: dna,
+                season_id,
+                number: season.minted,
+            });
+            Seasons::<T>::insert(season_id, season);
+        }
+        Ok(())
+    }
+
+    /// Igual que `mint()`, pero con un genoma ya calculado (p. ej. mezclado de sus progenitores),
+    /// la generación que le corresponde y, si nació por cría, sus progenitores.
+    pub fn mint_with_dna(
+        owner: T::AccountId,
+        kitty_id: [u8; 32],
+        dna: KittyDna<T>,
+        generation: u32,
+        parents: Option<([u8; 32], [u8; 32])>,
+    ) -> DispatchResult {
         // Crea la estructura del kitty con su ADN y dueño.
-        let kitty = Kitty { dna, owner: owner.clone(), price: None };
+        let rarity = Self::rarity_score(&dna);
+        let attributes = Self::attributes(&dna);
+        let kitty = Kitty {
+            dna,
+            owner: owner.clone(),
+            price: None,
+            cooldown_until: Zero::zero(),
+            generation,
+            parents,
+            rarity,
+            special_edition: None,
+        };
 
         // Asegura que no exista otro kitty con el mismo ADN.
-        ensure!(!Kitties::<T>::contains_key(dna), Error::<T>::DuplicateKitty);
+        ensure!(!Kitties::<T>::contains_key(kitty_id), Error::<T>::DuplicateKitty);
 
         // Incrementa el contador global de kitties, validando overflow.
         let current_count: u32 = CountForKitties::<T>::get();
         let new_count = current_count.checked_add(1).ok_or(Error::<T>::TooManyKitties)?;
 
-        // Añade el nuevo kitty al vector de kitties del propietario.
-        KittiesOwned::<T>::try_append(&owner, dna).map_err(|_| Error::<T>::TooManyOwned)?;
+        // Respeta el tope de suministro fijado por gobernanza, si lo hay.
+        if let Some(cap) = MaxTotalKitties::<T>::get() {
+            ensure!(new_count <= cap, Error::<T>::SupplyCapReached);
+        }
+
+        // Retiene el depósito de almacenamiento a quien lo crea, que queda como su creador.
+        Self::hold_kitty_deposit(&owner, kitty_id)?;
+        KittyMinters::<T>::insert(kitty_id, &owner);
+
+        // Añade el nuevo kitty al vector de kitties del propietario, dentro de su límite.
+        Self::ensure_can_receive(&owner)?;
+        KittiesOwned::<T>::try_append(&owner, kitty_id).map_err(|_| Error::<T>::TooManyOwned)?;
+        Self::on_kitties_received(&owner);
 
         // Inserta el kitty en el mapa global de kitties.
-        Kitties::<T>::insert(dna, kitty);
+        Kitties::<T>::insert(kitty_id, kitty);
 
-        // Actualiza el contador total de kitties.
+        // Actualiza el contador total de kitties y el de su nivel de rareza.
         CountForKitties::<T>::set(new_count);
+        Self::track_rarity(None, Some(rarity));
+        Self::index_traits(&kitty_id, &attributes, true);
 
-        // Emite un evento indicando la creación.
-        Self::deposit_event(Event::<T>::Created { owner });
+        // Emite un evento indicando la creación y avisa al runtime.
+        Self::index_history(kitty_id, KittyHistoryRecord::Minted { owner: owner.clone() });
+        T::OnKittyMinted::on_kitty_minted(&owner, kitty_id);
+        Self::deposit_event(Event::<T>::Created { owner, kitty_id, generation });
 
         Ok(())
     }
 
     // -------------------------------------------------------------------------
-    //  Función: do_transfer()
+    //  Funciones: depósito de almacenamiento
     // -------------------------------------------------------------------------
-    /// Transfiere un kitty de un usuario a otro, verificando propiedad, límites y validez.
-    pub fn do_transfer(from: T::AccountId, to: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
-        // No se puede transferir un kitty a uno mismo.
-        ensure!(from != to, Error::<T>::TransferToSelf);
 
-        // Obtiene el kitty de almacenamiento, si no existe lanza error.
-        let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+    /// Retiene `KittyDeposit` a `who` por `kitty_id` y apunta quién lo pagó.
+    fn hold_kitty_deposit(who: &T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let deposit = T::KittyDeposit::get();
+        if deposit.is_zero() {
+            return Ok(());
+        }
+        T::NativeBalance::hold(&HoldReason::KittyDeposit.into(), who, deposit)?;
+        KittyDeposits::<T>::insert(kitty_id, (who.clone(), deposit));
+        Ok(())
+    }
 
-        // Verifica que quien realiza la operación sea el dueño actual.
-        ensure!(kitty.owner == from, Error::<T>::NotOwner);
+    /// Devuelve el depósito de `kitty_id` a quien lo pagó. Los kitties creados antes de que
+    /// existiera el depósito no tienen nada que devolver.
+    fn release_kitty_deposit(kitty_id: [u8; 32]) {
+        if let Some((depositor, deposit)) = KittyDeposits::<T>::take(kitty_id) {
+            // Con `BestEffort` no puede fallar: como mucho libera lo que quede retenido.
+            let _ = T::NativeBalance::release(
+                &HoldReason::KittyDeposit.into(),
+                &depositor,
+                deposit,
+                Precision::BestEffort,
+            );
+        }
+    }
 
-        // Actualiza el dueño y elimina el precio (ya no está en venta).
-        kitty.owner = to.clone();
-        kitty.price = None;
+    /// Retiene `ListingDeposit` a `who` por anunciar `kitty_id`, si no lo tenía ya retenido
+    /// (cambiar el precio de un anuncio no cobra otro depósito).
+    fn hold_listing_deposit(who: &T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let deposit = T::ListingDeposit::get();
+        if deposit.is_zero() || ListingDeposits::<T>::contains_key(kitty_id) {
+            return Ok(());
+        }
+        T::NativeBalance::hold(&HoldReason::ListingDeposit.into(), who, deposit)?;
+        ListingDeposits::<T>::insert(kitty_id, (who.clone(), deposit));
+        Ok(())
+    }
+
+    /// Devuelve el depósito del anuncio de `kitty_id`, si lo hay, a quien lo pagó.
+    fn release_listing_deposit(kitty_id: [u8; 32]) {
+        if let Some((depositor, deposit)) = ListingDeposits::<T>::take(kitty_id) {
+            let _ = T::NativeBalance::release(
+                &HoldReason::ListingDeposit.into(),
+                &depositor,
+                deposit,
+                Precision::BestEffort,
+            );
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    //  Función: inherit_dna()
+    // -------------------------------------------------------------------------
+    // El genoma se divide en genes de `GENE_LEN` bytes. En cada gen el primer byte es el alelo
+    // dominante (el que se expresa) y los siguientes son recesivos. Para formar la cría:
+    //   1. En cada progenitor, cada alelo puede intercambiarse con el siguiente (25%), así que
+    //      un recesivo puede pasar a dominante.
+    //   2. Cada posición del gen de la cría se toma de uno de los dos progenitores (50%).
+    //   3. Cada byte resultante muta con probabilidad `mutation_chance`: se invierten algunos de
+    //      sus bits al azar, así que el valor siempre cambia.
+    // Toda la aleatoriedad sale de `seed`, así que el resultado es reproducible.
+
+    /// Bytes que forman un gen: un alelo dominante y `GENE_LEN - 1` recesivos.
+    pub const GENE_LEN: usize = 4;
+
+    /// Genoma de la cría de `parent_1` y `parent_2`. Función pura: con la misma semilla y
+    /// probabilidad de mutación, cualquier interfaz o test obtiene el mismo resultado.
+    pub fn inherit_dna(
+        parent_1: &KittyDna<T>,
+        parent_2: &KittyDna<T>,
+        seed: &[u8; 32],
+        mutation_chance: Permill,
+    ) -> KittyDna<T> {
+        Self::inherit_dna_with_mutations(parent_1, parent_2, seed, mutation_chance).0
+    }
+
+    /// Igual que `inherit_dna()`, pero devuelve además los índices de los genes que mutaron
+    /// (índice de gen, no de byte), sin repetidos y en orden.
+    pub fn inherit_dna_with_mutations(
+        parent_1: &KittyDna<T>,
+        parent_2: &KittyDna<T>,
+        seed: &[u8; 32],
+        mutation_chance: Permill,
+    ) -> (KittyDna<T>, Vec<u8>) {
+        let mut entropy = GeneEntropy::new(seed);
+        let mut genes: Vec<u8> = Vec::with_capacity(parent_1.len());
+
+        for (gene_1, gene_2) in parent_1.chunks(Self::GENE_LEN).zip(parent_2.chunks(Self::GENE_LEN)) {
+            let gene_1 = Self::shuffle_alleles(gene_1, &mut entropy);
+            let gene_2 = Self::shuffle_alleles(gene_2, &mut entropy);
+            for (&allele_1, &allele_2) in gene_1.iter().zip(gene_2.iter()) {
+                genes.push(if entropy.byte() & 1 == 0 { allele_1 } else { allele_2 });
+            }
+        }
 
-        // Obtiene el listado de kitties del receptor y añade el nuevo.
-        let mut to_owned = KittiesOwned::<T>::get(&to);
-        to_owned.try_push(kitty_id).map_err(|_| Error::<T>::TooManyOwned)?;
+        let mut mutated: Vec<u8> = Vec::new();
+        for (position, allele) in genes.iter_mut().enumerate() {
+            if entropy.chance(mutation_chance) {
+                *allele ^= entropy.byte().max(1);
+                let gene: u8 = (position / Self::GENE_LEN).saturated_into();
+                if mutated.last() != Some(&gene) {
+                    mutated.push(gene);
+                }
+            }
+        }
+
+        // Nunca supera la longitud de los progenitores, así que la conversión no puede fallar.
+        (BoundedVec::truncate_from(genes), mutated)
+    }
+
+    /// Copia de `gene` en la que cada alelo se intercambia con el siguiente con un 25%.
+    fn shuffle_alleles(gene: &[u8], entropy: &mut GeneEntropy) -> Vec<u8> {
+        let mut alleles = gene.to_vec();
+        for i in 1..alleles.len() {
+            if entropy.byte() < 64 {
+                alleles.swap(i - 1, i);
+            }
+        }
+        alleles
+    }
+
+    // -------------------------------------------------------------------------
+    //  Función: attributes()
+    // -------------------------------------------------------------------------
+    /// Traduce un genoma a sus rasgos visibles. Cada rasgo sale del alelo dominante de su gen
+    /// (ver `KittyAttributes`); si el genoma es demasiado corto para un gen, se toma como 0.
+    /// Función pura: es la misma para cualquier runtime y la que deben usar los front-ends.
+    pub fn attributes(dna: &[u8]) -> KittyAttributes {
+        let allele = |gene: usize| dna.get(gene * Self::GENE_LEN).copied().unwrap_or(0);
+        KittyAttributes {
+            gender: Self::gender_of(dna),
+            fur: match allele(1) % 4 {
+                0 => Fur::Short,
+                1 => Fur::Long,
+                2 => Fur::Curly,
+                _ => Fur::Hairless,
+            },
+            eye_color: match allele(2) % 6 {
+                0 => EyeColor::Amber,
+                1 => EyeColor::Blue,
+                2 => EyeColor::Green,
+                3 => EyeColor::Brown,
+                4 => EyeColor::Hazel,
+                _ => EyeColor::OddEyed,
+            },
+            pattern: match allele(3) % 4 {
+                0 => Pattern::Solid,
+                1 => Pattern::Tabby,
+                2 => Pattern::Spotted,
+                _ => Pattern::Calico,
+            },
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    //  Función: image_seed()
+    // -------------------------------------------------------------------------
+    /// Semilla y paleta del dibujo de un genoma. La semilla es el Blake2-256 del genoma y los
+    /// colores del pelaje, del dibujo y del fondo son sus primeros bytes; los ojos usan el color
+    /// fijo de su `EyeColor` y un kitty sin pelo tiene siempre el mismo tono de piel. Función
+    /// pura, como `attributes`: se calcula bajo demanda y no depende del runtime.
+    pub fn image_seed(dna: &[u8]) -> KittyImageSeed {
+        let seed: [u8; 32] = BlakeTwo256::hash(dna).into();
+        let color = |at: usize| [seed[at], seed[at + 1], seed[at + 2]];
+        let attributes = Self::attributes(dna);
+        const AMBER: [u8; 3] = [0xff, 0xbf, 0x00];
+        const BLUE: [u8; 3] = [0x3b, 0x82, 0xf6];
+        KittyImageSeed {
+            seed,
+            attributes,
+            body_color: match attributes.fur {
+                Fur::Hairless => [0xf4, 0xc2, 0xc2],
+                _ => color(0),
+            },
+            accent_color: color(3),
+            eye_colors: match attributes.eye_color {
+                EyeColor::Amber => [AMBER; 2],
+                EyeColor::Blue => [BLUE; 2],
+                EyeColor::Green => [[0x22, 0xc5, 0x5e]; 2],
+                EyeColor::Brown => [[0x8b, 0x5a, 0x2b]; 2],
+                EyeColor::Hazel => [[0x8e, 0x76, 0x18]; 2],
+                EyeColor::OddEyed => [BLUE, AMBER],
+            },
+            background: color(6),
+        }
+    }
+
+    /// `image_seed` del genoma de `kitty_id`, si existe. Es lo que devuelve
+    /// `runtime_api::KittiesApi::image_seed`.
+    pub fn kitty_image_seed(kitty_id: [u8; 32]) -> Option<KittyImageSeed> {
+        Kitties::<T>::get(kitty_id).map(|kitty| Self::image_seed(&kitty.dna))
+    }
+
+    /// Añade (`insert = true`) o quita `kitty_id` del índice `KittiesByTrait` para cada rasgo
+    /// de `attributes`. Hay que llamarla siempre que un kitty nace, muere o cambia de genoma.
+    pub(crate) fn index_traits(kitty_id: &[u8; 32], attributes: &KittyAttributes, insert: bool) {
+        for kitty_trait in attributes.traits() {
+            if insert {
+                KittiesByTrait::<T>::insert(kitty_trait, kitty_id, ());
+            } else {
+                KittiesByTrait::<T>::remove(kitty_trait, kitty_id);
+            }
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: rareza
+    // -------------------------------------------------------------------------
+    // Solo cuentan los alelos dominantes (el primer byte de cada gen), que son los que se
+    // expresan. Un alelo con `k` ceros finales aparece con probabilidad 2^-k, así que cada uno
+    // aporta sus ceros finales (0..=8) y el total se escala a 0..=1000 para que no dependa de la
+    // longitud del genoma.
+
+    /// Niveles de rareza: del 0 (común) al `RARITY_TIERS - 1` (el más raro).
+    pub const RARITY_TIERS: u8 = 5;
+
+    /// Rareza de un genoma, de 0 a 1000. Función pura.
+    pub fn rarity_score(dna: &[u8]) -> u16 {
+        let genes = dna.len().div_ceil(Self::GENE_LEN);
+        if genes == 0 {
+            return 0;
+        }
+        let points: usize =
+            dna.chunks(Self::GENE_LEN).map(|gene| gene[0].trailing_zeros() as usize).sum();
+        (points * 1000 / (genes * 8)) as u16
+    }
+
+    /// Nivel de rareza de una puntuación: tramos de 200 puntos, con 1000 en el último nivel.
+    pub fn rarity_tier(score: u16) -> u8 {
+        ((score / 200) as u8).min(Self::RARITY_TIERS - 1)
+    }
+
+    /// Mueve un kitty entre niveles de `RarityTierCounts` (`None` al crearlo o destruirlo).
+    fn track_rarity(old: Option<u16>, new: Option<u16>) {
+        if let Some(old) = old {
+            RarityTierCounts::<T>::mutate(Self::rarity_tier(old), |count| count.saturating_dec());
+        }
+        if let Some(new) = new {
+            RarityTierCounts::<T>::mutate(Self::rarity_tier(new), |count| count.saturating_inc());
+        }
+    }
+
+    /// Guarda el tope de suministro (`None` = sin tope).
+    pub fn do_set_max_total_kitties(cap: Option<u32>) -> DispatchResult {
+        MaxTotalKitties::<T>::set(cap);
+        Self::deposit_event(Event::<T>::MaxTotalKittiesSet { cap });
+        Ok(())
+    }
+
+    /// Cambia la fase de mint.
+    pub fn do_set_mint_phase(phase: MintPhase) -> DispatchResult {
+        CurrentMintPhase::<T>::put(phase);
+        Self::deposit_event(Event::<T>::MintPhaseChanged { phase });
+        Ok(())
+    }
 
-        // Elimina el kitty del listado del remitente.
-        let mut from_owned = KittiesOwned::<T>::get(&from);
-        if let Some(ind) = from_owned.iter().position(|&id| id == kitty_id) {
-            from_owned.swap_remove(ind); // Remueve el elemento rápidamente (sin mantener orden).
+    /// Añade o quita `who` de la lista de la fase `Whitelist`.
+    pub fn do_set_mint_allowlist(who: T::AccountId, allowed: bool) -> DispatchResult {
+        if allowed {
+            MintAllowlist::<T>::insert(&who, ());
         } else {
-            return Err(Error::<T>::NoKitty.into());
+            MintAllowlist::<T>::remove(&who);
         }
+        Self::deposit_event(Event::<T>::MintAllowlistUpdated { who, allowed });
+        Ok(())
+    }
 
-        // Actualiza almacenamiento: nuevo dueño y estado del kitty.
-        Kitties::<T>::insert(kitty_id, kitty);
-        KittiesOwned::<T>::insert(&to, to_owned);
-        KittiesOwned::<T>::insert(&from, from_owned);
+    /// Falla si la fase actual no deja crear kitties a `who`.
+    pub fn ensure_mint_phase_allows(who: &T::AccountId) -> DispatchResult {
+        if CurrentMintPhase::<T>::get() == MintPhase::Whitelist {
+            ensure!(MintAllowlist::<T>::contains_key(who), Error::<T>::NotOnMintAllowlist);
+        }
+        Ok(())
+    }
 
-        // Emite evento de transferencia.
-        Self::deposit_event(Event::<T>::Transferred { from, to, kitty_id });
+    // -------------------------------------------------------------------------
+    //  Funciones: pausa de emergencia
+    // -------------------------------------------------------------------------
+
+    /// Activa o desactiva la pausa de emergencia.
+    pub fn do_set_paused(paused: bool) -> DispatchResult {
+        Paused::<T>::put(paused);
+        Self::deposit_event(Event::<T>::PauseChanged { paused });
+        Ok(())
+    }
+
+    /// Pausa o reanuda las llamadas de `class`.
+    pub fn do_set_call_class_paused(class: CallClass, paused: bool) -> DispatchResult {
+        if paused {
+            PausedCalls::<T>::insert(class, ());
+        } else {
+            PausedCalls::<T>::remove(class);
+        }
+        Self::deposit_event(Event::<T>::CallClassPauseChanged { class, paused });
+        Ok(())
+    }
+
+    /// Falla si el pallet entero o las llamadas de `class` están en pausa.
+    pub fn ensure_not_paused(class: CallClass) -> DispatchResult {
+        ensure!(!Paused::<T>::get(), Error::<T>::PalletPaused);
+        ensure!(!PausedCalls::<T>::contains_key(class), Error::<T>::CallClassPaused);
+        Ok(())
+    }
+
+    /// Clase de `call`, o `None` si no se puede pausar: los cierres y disputas, las de gobernanza
+    /// y las que llegan de otras cadenas. Es la única lista de qué pausa cada clase. No hay
+    /// comodín, así que una extrínseca nueva no compila hasta que se clasifica aquí.
+    pub fn call_class(call: &Call<T>) -> Option<CallClass> {
+        match call {
+            Call::create_kitty { .. }
+            | Call::commit_create { .. }
+            | Call::reveal_create { .. }
+            | Call::fuse_kitties { .. }
+            | Call::reroll_dna { .. }
+            | Call::buy_voucher { .. }
+            | Call::redeem_voucher { .. }
+            | Call::buy_mint_pass { .. } => Some(CallClass::Minting),
+            Call::breed_kitty { .. }
+            | Call::set_breeding_price { .. }
+            | Call::breed_with { .. }
+            | Call::claim_birth { .. }
+            | Call::grant_breeding_loan { .. }
+            | Call::revoke_breeding_loan { .. }
+            | Call::offer_remote_breeding { .. }
+            | Call::cancel_remote_breeding { .. } => Some(CallClass::Breeding),
+            Call::transfer { .. }
+            | Call::transfer_with_remark { .. }
+            | Call::approve { .. }
+            | Call::revoke_approval { .. }
+            | Call::clear_expired_approval { .. }
+            | Call::clear_expired_operator { .. }
+            | Call::set_approval_for_all { .. }
+            | Call::transfer_from { .. }
+            | Call::offer_transfer { .. }
+            | Call::accept_transfer { .. }
+            | Call::cancel_transfer { .. }
+            | Call::deposit_to_escrow { .. }
+            | Call::withdraw_from_escrow { .. }
+            | Call::send_kitty_to_parachain { .. }
+            | Call::export_kitty { .. }
+            | Call::claim_parked_import { .. }
+            | Call::wrap_kitty { .. }
+            | Call::unwrap_kitty { .. }
+            | Call::enable_co_ownership { .. }
+            | Call::propose_action { .. }
+            | Call::approve_action { .. }
+            | Call::execute_action { .. }
+            | Call::send_gift { .. }
+            | Call::claim_gift { .. }
+            | Call::transfer_many { .. }
+            | Call::transfer_all { .. } => Some(CallClass::Transfers),
+            Call::list_for_rent { .. }
+            | Call::unlist_for_rent { .. }
+            | Call::rent { .. }
+            | Call::end_rental { .. }
+            | Call::set_price { .. }
+            | Call::buy_kitty { .. }
+            | Call::sweep_floor { .. }
+            | Call::create_bundle { .. }
+            | Call::cancel_bundle { .. }
+            | Call::buy_bundle { .. }
+            | Call::propose_swap { .. }
+            | Call::accept_swap { .. }
+            | Call::cancel_swap { .. }
+            | Call::make_offer { .. }
+            | Call::accept_offer { .. }
+            | Call::cancel_offer { .. }
+            | Call::start_auction { .. }
+            | Call::bid { .. }
+            | Call::set_price_in_asset { .. }
+            | Call::buy_kitty_with_asset { .. }
+            | Call::start_raffle { .. }
+            | Call::buy_ticket { .. }
+            | Call::buy_kitty_escrowed { .. } => Some(CallClass::Trading),
+            Call::burn_kitty { .. }
+            | Call::lock_kitty { .. }
+            | Call::unlock_kitty { .. }
+            | Call::set_name { .. }
+            | Call::pay_premium { .. }
+            | Call::file_claim { .. }
+            | Call::stake_for_capacity { .. }
+            | Call::unstake_capacity { .. } => Some(CallClass::Other),
+            // Cierres y disputas: sus plazos corren igual en pausa (`on_initialize` sigue
+            // liquidando subastas), así que pausarlos dejaría fondos o derechos de disputa
+            // atrapados.
+            Call::settle_auction { .. }
+            | Call::close_raffle { .. }
+            | Call::raise_dispute { .. }
+            | Call::settle_escrowed_sale { .. } => None,
+            Call::force_mint_batch { .. }
+            | Call::create_kitty_with_seed { .. }
+            | Call::schedule_season { .. }
+            | Call::set_max_total_kitties { .. }
+            | Call::set_mint_phase { .. }
+            | Call::set_mint_allowlist { .. }
+            | Call::force_transfer { .. }
+            | Call::force_burn { .. }
+            | Call::set_paused { .. }
+            | Call::set_call_class_paused { .. }
+            | Call::set_blacklisted { .. }
+            | Call::import_kitty { .. }
+            | Call::receive_kitty { .. }
+            | Call::resolve_claim { .. }
+            | Call::rule_dispute { .. }
+            | Call::breed_with_remote { .. }
+            | Call::set_collector_pass { .. }
+            | Call::post_appraisal { .. } => None,
+            Call::__Ignore(..) => None,
+        }
+    }
 
+    /// Falla si el pallet entero o la clase de `call` están en pausa. Lo aplica `PauseFilter` a
+    /// todas las extrínsecas; quien llame al pallet sin pasar por el filtro del runtime (p. ej.
+    /// la chain extension) lo comprueba aquí.
+    pub fn ensure_call_not_paused(call: &Call<T>) -> DispatchResult {
+        match Self::call_class(call) {
+            Some(class) => Self::ensure_not_paused(class),
+            None => Ok(()),
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: lista negra
+    // -------------------------------------------------------------------------
+
+    /// Añade o quita `who` de la lista negra.
+    pub fn do_set_blacklisted(who: T::AccountId, blacklisted: bool) -> DispatchResult {
+        if blacklisted {
+            Blacklist::<T>::insert(&who, ());
+        } else {
+            Blacklist::<T>::remove(&who);
+        }
+        Self::deposit_event(Event::<T>::BlacklistUpdated { who, blacklisted });
+        Ok(())
+    }
+
+    /// Falla si `who` está en la lista negra.
+    pub fn ensure_not_blacklisted(who: &T::AccountId) -> DispatchResult {
+        ensure!(!Blacklist::<T>::contains_key(who), Error::<T>::AccountBlacklisted);
+        Ok(())
+    }
+
+    /// Falla si `who` no puede operar en el mercado: está en la lista negra o
+    /// `Config::MarketParticipants` no lo admite. Cada compra la comprueba para el comprador y
+    /// para el vendedor, porque un anuncio puede ser anterior a la prohibición.
+    pub fn ensure_can_trade(who: &T::AccountId) -> DispatchResult {
+        Self::ensure_not_blacklisted(who)?;
+        ensure!(T::MarketParticipants::contains(who), Error::<T>::NotMarketParticipant);
         Ok(())
     }
 
     // -------------------------------------------------------------------------
-    //  Función: do_set_price()
+    //  Funciones: temporadas de edición limitada
     // -------------------------------------------------------------------------
-    // Permite al dueño establecer o quitar un precio de venta para su kitty.
-    pub fn do_set_price(
-        caller: T::AccountId,             // Quien realiza la llamada
-        kitty_id: [u8; 32],               // ID del kitty
-        new_price: Option<BalanceOf<T>>,  // Precio opcional (None = no venta)
+
+    /// Valida y guarda una temporada nueva. Solo puede haber una programada o en curso.
+    pub fn do_schedule_season(
+        start: BlockNumberFor<T>,
+        end: BlockNumberFor<T>,
+        gene_prefix: [u8; 3],
+        supply_cap: u32,
     ) -> DispatchResult {
-        // Verifica que el kitty exista.
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(start <= end && end >= now && supply_cap > 0, Error::<T>::InvalidSeason);
+        if let Some(current) = CurrentSeason::<T>::get().and_then(Seasons::<T>::get) {
+            ensure!(current.end < now, Error::<T>::SeasonScheduled);
+        }
+
+        let season_id = NextSeasonId::<T>::get();
+        NextSeasonId::<T>::put(season_id.wrapping_add(1));
+        Seasons::<T>::insert(season_id, Season { start, end, gene_prefix, supply_cap, minted: 0 });
+        CurrentSeason::<T>::put(season_id);
+
+        Self::deposit_event(Event::<T>::SeasonScheduled { season_id, start, end, supply_cap });
+        Ok(())
+    }
+
+    /// La temporada en curso, si todavía le queda cupo.
+    fn open_season() -> Option<(u16, Season<BlockNumberFor<T>>)> {
+        let season_id = CurrentSeason::<T>::get()?;
+        let season = Seasons::<T>::get(season_id)?;
+        let now = frame_system::Pallet::<T>::block_number();
+        (season.start <= now && now <= season.end && season.minted < season.supply_cap)
+            .then_some((season_id, season))
+    }
+
+    /// Escribe la marca de `season` en los alelos recesivos del gen 0: no cambia el sexo ni
+    /// ningún rasgo visible, así que las ediciones limitadas pueden criar con cualquiera.
+    fn mark_season(dna: &mut KittyDna<T>, season: &Season<BlockNumberFor<T>>) {
+        for (byte, marker) in dna.iter_mut().skip(1).zip(season.gene_prefix) {
+            *byte = marker;
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    //  Función: import_snapshot_kitty()
+    // -------------------------------------------------------------------------
+    /// Importa un kitty exportado de otra cadena (usado por la configuración de génesis).
+    /// Reutiliza `mint()`, así que rechaza ADN duplicado o dueños sin capacidad.
+    pub fn import_snapshot_kitty(entry: KittySnapshotOf<T>) -> DispatchResult {
+        let (owner, dna, price) = entry;
+
+        // Crea el kitty validando unicidad del ADN y el límite del dueño.
+        Self::mint(owner, dna)?;
+
+        // Conserva el precio de venta que tenía en el origen.
+        if price.is_some() {
+            Kitties::<T>::mutate(dna, |maybe_kitty| {
+                if let Some(kitty) = maybe_kitty {
+                    kitty.price = price;
+                }
+            });
+            Self::track_listing(dna, None, price);
+            Self::set_status(dna, KittyStatus::Listed)?;
+        }
+
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Función: burn()
+    // -------------------------------------------------------------------------
+    /// Destruye `kitty_id` de `owner` y limpia todos los índices que apuntan a él. Un kitty en
+    /// rifa o con una venta con escrow pendiente no se puede destruir. La póliza se pierde sin
+    /// pérdida que reclamar, porque la quema es del propio dueño; los reclamos se conservan como
+    /// registro. Emite `Burned`.
+    pub(crate) fn burn(owner: &T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        Self::destroy_kitty(owner, kitty_id)?;
+
+        Self::deposit_event(Event::<T>::Burned { owner: owner.clone(), kitty_id });
+        Ok(())
+    }
+
+    /// Destrucción decidida por `Config::ForceOrigin`: limpia lo mismo que `burn` sin la firma
+    /// del dueño. Si el kitty estaba asegurado, la pérdida queda registrada para `file_claim`.
+    /// Emite `ForceBurned`.
+    pub fn do_force_burn(kitty_id: [u8; 32]) -> DispatchResult {
+        let owner = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?.owner;
+        let policy = InsurancePolicies::<T>::get(kitty_id);
+        Self::destroy_kitty(&owner, kitty_id)?;
+        Self::record_insured_loss(kitty_id, policy);
+
+        Self::deposit_event(Event::<T>::ForceBurned { owner, kitty_id });
+        Ok(())
+    }
+
+    /// Borra `kitty_id` de `owner` con todos sus índices. No emite eventos; cada llamador emite
+    /// el suyo.
+    fn destroy_kitty(owner: &T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
         let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(&kitty.owner == owner, Error::<T>::NotOwner);
+        Self::ensure_movable(kitty_id)?;
 
-        // Solo el dueño puede establecer el precio.
-        ensure!(kitty.owner == caller, Error::<T>::NotOwner);
+        Self::remove_owned(owner, kitty_id)?;
 
-        // Actualiza el precio en la estructura.
-        kitty.price = new_price;
+        Kitties::<T>::remove(kitty_id);
+        CountForKitties::<T>::mutate(|count| count.saturating_dec());
+        Self::track_rarity(Some(kitty.rarity), None);
+        Self::delist(kitty_id, &mut kitty);
+        Self::index_traits(&kitty_id, &Self::attributes(&kitty.dna), false);
 
-        // Guarda los cambios en almacenamiento.
+        if let Some(name) = KittyNames::<T>::take(kitty_id) {
+            KittyByName::<T>::remove(name);
+        }
+        BreedingLoans::<T>::remove(kitty_id);
+        InsurancePolicies::<T>::remove(kitty_id);
+        RemoteBreedingOffers::<T>::remove(kitty_id);
+        StudListings::<T>::remove(kitty_id);
+        PendingTransfers::<T>::remove(kitty_id);
+        KittyApprovals::<T>::remove(kitty_id);
+        EscrowVault::<T>::remove(kitty_id);
+        KittiesAbroad::<T>::remove(kitty_id);
+        KittiesOnEthereum::<T>::remove(kitty_id);
+        ParkedImports::<T>::remove(kitty_id);
+        let _ = CoOwnerActions::<T>::clear_prefix(kitty_id, u32::MAX, None);
+        RentalListings::<T>::remove(kitty_id);
+        if let Some(rental) = Rentals::<T>::take(kitty_id) {
+            Self::release_rental_collateral(&rental);
+        }
+        RentalEndRequests::<T>::remove(kitty_id);
+        KittyStatuses::<T>::remove(kitty_id);
+        let _ = ItemAttributes::<T>::clear_prefix(kitty_id, u32::MAX, None);
+        if let Some((para_id, foreign_id)) = KittyProvenance::<T>::take(kitty_id) {
+            ForeignKitties::<T>::remove(para_id, foreign_id);
+        }
+        WrappedKitties::<T>::remove(kitty_id);
+        CollectorPasses::<T>::remove(kitty_id);
+        Appraisals::<T>::remove(AppraisalTarget::Kitty(kitty_id));
+        RerollCount::<T>::remove(kitty_id);
+        PriceHistory::<T>::remove(kitty_id);
+        Self::release_kitty_deposit(kitty_id);
+        KittyMinters::<T>::remove(kitty_id);
+
+        T::OnKittyBurned::on_kitty_burned(owner, kitty_id);
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Función: do_fuse()
+    // -------------------------------------------------------------------------
+    /// Destruye `kitty_a` y `kitty_b` de `owner` y crea uno nuevo con la fusión de sus genomas
+    /// y la generación mayor de los dos. El identificador del nuevo kitty se deriva de los dos
+    /// fusionados, así que también es determinista.
+    pub fn do_fuse(owner: T::AccountId, kitty_a: [u8; 32], kitty_b: [u8; 32]) -> DispatchResult {
+        ensure!(kitty_a != kitty_b, Error::<T>::SameKitty);
+        let a = Kitties::<T>::get(kitty_a).ok_or(Error::<T>::NoKitty)?;
+        let b = Kitties::<T>::get(kitty_b).ok_or(Error::<T>::NoKitty)?;
+
+        Self::burn(&owner, kitty_a)?;
+        Self::burn(&owner, kitty_b)?;
+
+        let fused: [u8; 32] = BlakeTwo256::hash_of(&(b"kitties/fuse", kitty_a, kitty_b)).into();
+        let dna = Self::fuse_dna(&a.dna, &b.dna);
+        let generation = a.generation.max(b.generation);
+        Self::mint_with_dna(owner.clone(), fused, dna, generation, Some((kitty_a, kitty_b)))?;
+
+        Self::deposit_event(Event::<T>::KittiesFused { owner, kitty_a, kitty_b, fused });
+
+        Ok(())
+    }
+
+    /// Fusión determinista de dos genomas: en cada posición se queda el byte mayor.
+    pub fn fuse_dna(dna_a: &KittyDna<T>, dna_b: &KittyDna<T>) -> KittyDna<T> {
+        let genes: Vec<u8> = dna_a.iter().zip(dna_b.iter()).map(|(&a, &b)| a.max(b)).collect();
+        // Nunca supera la longitud de los originales, así que la conversión no puede fallar.
+        BoundedVec::truncate_from(genes)
+    }
+
+    // -------------------------------------------------------------------------
+    //  Función: do_reroll_dna()
+    // -------------------------------------------------------------------------
+    /// Sustituye el genoma de `kitty_id` por uno aleatorio nuevo. El identificador no cambia.
+    /// La tasa va al bote del pallet y se duplica con cada regeneración del mismo kitty, también
+    /// si cambia de dueño. No se permite con el kitty en rifa o en una venta con escrow.
+    pub fn do_reroll_dna(owner: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.owner == owner, Error::<T>::NotOwner);
+        Self::ensure_movable(kitty_id)?;
+
+        let rerolls = RerollCount::<T>::get(kitty_id);
+        let fee = Self::reroll_fee(rerolls);
+        if !fee.is_zero() {
+            T::NativeBalance::transfer(&owner, &Self::pot_account(), fee, Preservation::Preserve)?;
+        }
+
+        // El número de regeneración entra en la semilla: dos seguidas en el mismo bloque (p. ej.
+        // en un lote) no repiten genoma.
+        let seed: [u8; 32] =
+            BlakeTwo256::hash_of(&(Self::gen_dna(&owner), kitty_id, rerolls)).into();
+        let mut new_dna = Self::expand_dna(&seed);
+        // Las ediciones limitadas conservan la marca de su temporada.
+        if let Some(season) = kitty.special_edition.and_then(Seasons::<T>::get) {
+            Self::mark_season(&mut new_dna, &season);
+        }
+        let old_dna = core::mem::replace(&mut kitty.dna, new_dna.clone());
+        let rarity = Self::rarity_score(&new_dna);
+        Self::track_rarity(Some(kitty.rarity), Some(rarity));
+        kitty.rarity = rarity;
+        Self::index_traits(&kitty_id, &Self::attributes(&old_dna), false);
+        Self::index_traits(&kitty_id, &Self::attributes(&new_dna), true);
         Kitties::<T>::insert(kitty_id, kitty);
+        RerollCount::<T>::insert(kitty_id, rerolls.saturating_add(1));
 
-        // Emite evento de cambio de precio.
-        Self::deposit_event(Event::<T>::PriceSet { owner: caller, kitty_id, new_price });
+        Self::deposit_event(Event::<T>::Rerolled { kitty_id, old_dna, new_dna, fee });
 
         Ok(())
     }
 
+    /// Precio de la regeneración número `rerolls + 1`: `RerollBaseFee * 2^rerolls`.
+    pub fn reroll_fee(rerolls: u32) -> BalanceOf<T> {
+        let multiplier: BalanceOf<T> = 2u128.saturating_pow(rerolls).saturated_into();
+        T::RerollBaseFee::get().saturating_mul(multiplier)
+    }
+
     // -------------------------------------------------------------------------
-    //  Función: do_buy_kitty()
+    //  Función: do_transfer()
     // -------------------------------------------------------------------------
-    // Permite a un comprador adquirir un kitty en venta si paga el precio correcto.
-    pub fn do_buy_kitty(
-        buyer: T::AccountId,        // Comprador
-        kitty_id: [u8; 32],         // ID del kitty a comprar
-        price: BalanceOf<T>,        // Precio máximo dispuesto a pagar
+    /// Transfiere un kitty de un usuario a otro, verificando propiedad, límites y validez.
+    pub fn do_transfer(from: T::AccountId, to: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        Self::do_transfer_with_remark(from, to, kitty_id, None)
+    }
+
+    /// Igual que `do_transfer`, pero publica `remark` en el evento `Transferred`.
+    pub fn do_transfer_with_remark(
+        from: T::AccountId,
+        to: T::AccountId,
+        kitty_id: [u8; 32],
+        remark: Option<TransferRemark>,
     ) -> DispatchResult {
-        // Obtiene el kitty desde almacenamiento.
-        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        Self::move_kitty(&from, &to, kitty_id)?;
 
-        // Verifica que esté en venta.
-        let real_price = kitty.price.ok_or(Error::<T>::NotForSale)?;
+        // Emite evento de transferencia.
+        Self::deposit_event(Event::<T>::Transferred { from, to, kitty_id, remark });
 
-        // Asegura que el comprador ofrece al menos el precio mínimo.
-        ensure!(price >= real_price, Error::<T>::MaxPriceTooLow);
+        Ok(())
+    }
+
+    /// Transferencia decidida por `Config::ForceOrigin`: no necesita la firma de `from`, pero
+    /// valida y actualiza lo mismo que `do_transfer`. Para `from` es una confiscación: si el kitty
+    /// estaba asegurado, la pérdida queda registrada para `file_claim`.
+    pub fn do_force_transfer(from: T::AccountId, to: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let policy = InsurancePolicies::<T>::get(kitty_id);
+        Self::move_kitty(&from, &to, kitty_id)?;
+        Self::record_insured_loss(kitty_id, policy);
+
+        Self::deposit_event(Event::<T>::ForceTransferred { from, to, kitty_id });
+        Ok(())
+    }
 
-        // Transfiere los fondos al vendedor manteniendo el saldo vivo.
-        T::NativeBalance::transfer(&buyer, &kitty.owner, real_price, Preservation::Preserve)?;
+    /// Cambia el dueño de `kitty_id` de `from` a `to` y actualiza todos los índices. No emite
+    /// eventos; cada llamador emite el suyo.
+    fn move_kitty(from: &T::AccountId, to: &T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        // No se puede transferir un kitty a uno mismo ni a una cuenta de la lista negra.
+        ensure!(from != to, Error::<T>::TransferToSelf);
+        Self::ensure_not_blacklisted(to)?;
 
-        // Transfiere la propiedad del kitty.
-        Self::do_transfer(kitty.owner, buyer.clone(), kitty_id)?;
+        // Obtiene el kitty de almacenamiento, si no existe lanza error.
+        let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
 
-        // Emite evento de venta completada.
-        Self::deposit_event(Event::<T>::Sold { buyer, kitty_id, price: real_price });
+        // Verifica que quien realiza la operación sea el dueño actual.
+        ensure!(&kitty.owner == from, Error::<T>::NotOwner);
 
+        // Un kitty en rifa o en una venta con escrow solo cambia de dueño al cerrarse esta.
+        Self::ensure_movable(kitty_id)?;
+
+        // Actualiza el dueño y elimina el precio (ya no está en venta).
+        kitty.owner = to.clone();
+        Self::delist(kitty_id, &mut kitty);
+
+        // Un préstamo de cría y la póliza de seguro son del dueño anterior; no sobreviven al
+        // cambio de dueño.
+        BreedingLoans::<T>::remove(kitty_id);
+        InsurancePolicies::<T>::remove(kitty_id);
+        RemoteBreedingOffers::<T>::remove(kitty_id);
+        StudListings::<T>::remove(kitty_id);
+        PendingTransfers::<T>::remove(kitty_id);
+        KittyApprovals::<T>::remove(kitty_id);
+        EscrowVault::<T>::remove(kitty_id);
+        KittiesAbroad::<T>::remove(kitty_id);
+        KittiesOnEthereum::<T>::remove(kitty_id);
+        ParkedImports::<T>::remove(kitty_id);
+        let _ = CoOwnerActions::<T>::clear_prefix(kitty_id, u32::MAX, None);
+        RentalListings::<T>::remove(kitty_id);
+        if let Some(rental) = Rentals::<T>::take(kitty_id) {
+            Self::release_rental_collateral(&rental);
+        }
+        RentalEndRequests::<T>::remove(kitty_id);
+
+        // Pasa el kitty del listado del remitente al del receptor, dentro de su límite, y
+        // ajusta las referencias de cuenta según las nuevas tenencias.
+        Self::add_owned(to, kitty_id)?;
+        Self::remove_owned(from, kitty_id)?;
+
+        // Actualiza almacenamiento: nuevo dueño y estado del kitty.
+        Kitties::<T>::insert(kitty_id, kitty);
+
+        Self::index_history(kitty_id, KittyHistoryRecord::Transferred { from: from.clone(), to: to.clone() });
+        T::OnKittyTransferred::on_kitty_transferred(from, to, kitty_id);
+        Ok(())
+    }
+
+    /// Añade `kitty_id` al listado de `owner`, dentro de su límite. Las cuentas de custodia no
+    /// llevan listado.
+    fn add_owned(owner: &T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        if Self::is_custody_account(owner) {
+            return Ok(());
+        }
+        Self::ensure_can_receive(owner)?;
+        KittiesOwned::<T>::try_append(owner, kitty_id).map_err(|_| Error::<T>::TooManyOwned)?;
+        Self::on_kitties_received(owner);
         Ok(())
     }
+
+    /// Quita `kitty_id` del listado de `owner`. Una cuenta de custodia puede no tenerlo en su
+    /// listado; si lo tiene, de antes de quedar exenta, se limpia igual.
+    fn remove_owned(owner: &T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let mut owned = KittiesOwned::<T>::get(owner);
+        let Some(index) = owned.iter().position(|&id| id == kitty_id) else {
+            ensure!(Self::is_custody_account(owner), Error::<T>::NoKitty);
+            return Ok(());
+        };
+        owned.swap_remove(index); // Remueve el elemento rápidamente (sin mantener orden).
+        if owned.is_empty() {
+            KittiesOwned::<T>::remove(owner);
+        } else {
+            KittiesOwned::<T>::insert(owner, owned);
+        }
+        Self::on_kitties_removed(owner)
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: autorizaciones
+    // -------------------------------------------------------------------------
+
+    // Las autorizaciones caducadas se ignoran al usarlas. Las sustituye la siguiente `approve` o
+    // `set_approval_for_all`, y cualquiera puede borrarlas con `clear_expired_approval` o
+    // `clear_expired_operator`. Cada operador retiene `OperatorDeposit` al dueño mientras exista.
+
+    /// `owner` autoriza a `spender` sobre `kitty_id` con el papel `role` hasta `expires_at`.
+    pub fn do_approve(
+        owner: T::AccountId,
+        spender: T::AccountId,
+        kitty_id: [u8; 32],
+        role: ApprovalRole,
+        expires_at: Option<BlockNumberFor<T>>,
+    ) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.owner == owner, Error::<T>::NotOwner);
+        ensure!(owner != spender, Error::<T>::TransferToSelf);
+        ensure!(Self::approval_active(expires_at), Error::<T>::InvalidApprovalExpiry);
+        KittyApprovals::<T>::insert(kitty_id, KittyApproval { spender: spender.clone(), role, expires_at });
+
+        Self::deposit_event(Event::<T>::Approval { owner, spender, kitty_id, role, expires_at });
+        Ok(())
+    }
+
+    /// `owner` retira la autorización de `kitty_id`.
+    pub fn do_revoke_approval(owner: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.owner == owner, Error::<T>::NotOwner);
+        KittyApprovals::<T>::take(kitty_id).ok_or(Error::<T>::NoApproval)?;
+
+        Self::deposit_event(Event::<T>::ApprovalRevoked { owner, kitty_id });
+        Ok(())
+    }
+
+    /// `owner` nombra o retira a `operator` como gestor de todos sus kitties hasta `expires_at`.
+    pub fn do_set_approval_for_all(
+        owner: T::AccountId,
+        operator: T::AccountId,
+        approved: bool,
+        expires_at: Option<BlockNumberFor<T>>,
+    ) -> DispatchResult {
+        ensure!(owner != operator, Error::<T>::TransferToSelf);
+        // Una revocación no caduca.
+        let expires_at = expires_at.filter(|_| approved);
+        if approved {
+            ensure!(Self::approval_active(expires_at), Error::<T>::InvalidApprovalExpiry);
+            Self::hold_operator_deposit(&owner, &operator)?;
+            OperatorApprovals::<T>::insert(&owner, &operator, expires_at);
+        } else {
+            Self::remove_operator(&owner, &operator);
+        }
+
+        Self::deposit_event(Event::<T>::ApprovalForAll { owner, operator, approved, expires_at });
+        Ok(())
+    }
+
+    /// Borra la autorización de `kitty_id` si ya caducó.
+    pub fn do_clear_expired_approval(kitty_id: [u8; 32]) -> DispatchResult {
+        let approval = KittyApprovals::<T>::get(kitty_id).ok_or(Error::<T>::NoApproval)?;
+        ensure!(!Self::approval_active(approval.expires_at), Error::<T>::ApprovalStillActive);
+        KittyApprovals::<T>::remove(kitty_id);
+
+        Self::deposit_event(Event::<T>::ExpiredApprovalCleared { kitty_id });
+        Ok(())
+    }
+
+    /// Borra la delegación de `owner` en `operator` si ya caducó y devuelve su depósito.
+    pub fn do_clear_expired_operator(owner: T::AccountId, operator: T::AccountId) -> DispatchResult {
+        let expires_at = OperatorApprovals::<T>::get(&owner, &operator).ok_or(Error::<T>::NoApproval)?;
+        ensure!(!Self::approval_active(expires_at), Error::<T>::ApprovalStillActive);
+        Self::remove_operator(&owner, &operator);
+
+        Self::deposit_event(Event::<T>::ExpiredOperatorCleared { owner, operator });
+        Ok(())
+    }
+
+    /// Retiene `OperatorDeposit` a `owner` por nombrar a `operator`, si no lo tenía ya retenido
+    /// (renovar la delegación no cobra otro depósito).
+    fn hold_operator_deposit(owner: &T::AccountId, operator: &T::AccountId) -> DispatchResult {
+        let deposit = T::OperatorDeposit::get();
+        if deposit.is_zero() || OperatorDeposits::<T>::contains_key(owner, operator) {
+            return Ok(());
+        }
+        T::NativeBalance::hold(&HoldReason::OperatorDeposit.into(), owner, deposit)?;
+        OperatorDeposits::<T>::insert(owner, operator, deposit);
+        Ok(())
+    }
+
+    /// Borra la delegación de `owner` en `operator`, si la hay, y le devuelve su depósito.
+    fn remove_operator(owner: &T::AccountId, operator: &T::AccountId) {
+        OperatorApprovals::<T>::remove(owner, operator);
+        if let Some(deposit) = OperatorDeposits::<T>::take(owner, operator) {
+            // Con `BestEffort` no puede fallar: como mucho libera lo que quede retenido.
+            let _ = T::NativeBalance::release(
+                &HoldReason::OperatorDeposit.into(),
+                owner,
+                deposit,
+                Precision::BestEffort,
+            );
+        }
+    }
+
+    /// Si una autorización que caduca en `expires_at` sigue vigente en el bloque actual.
+    fn approval_active(expires_at: Option<BlockNumberFor<T>>) -> bool {
+        expires_at.is_none_or(|end| frame_system::Pallet::<T>::block_number() < end)
+    }
+
+    /// Si `who` es un operador vigente de `owner`.
+    fn is_operator(owner: &T::AccountId, who: &T::AccountId) -> bool {
+        OperatorApprovals::<T>::get(owner, who).is_some_and(Self::approval_active)
+    }
+
+    /// Si `who` tiene una autorización vigente con el papel `role` sobre `kitty_id`.
+    fn has_approval(kitty_id: [u8; 32], who: &T::AccountId, role: ApprovalRole) -> bool {
+        KittyApprovals::<T>::get(kitty_id).is_some_and(|approval| {
+            &approval.spender == who && approval.role == role && Self::approval_active(approval.expires_at)
+        })
+    }
+
+    /// Falla si `who` no puede anunciar ni subastar `kitty_id` de `owner`: no es el dueño, ni uno
+    /// de sus operadores vigentes, ni su agente de ventas vigente.
+    pub fn ensure_can_list(kitty_id: [u8; 32], owner: &T::AccountId, who: &T::AccountId) -> DispatchResult {
+        ensure!(
+            owner == who ||
+                Self::is_operator(owner, who) ||
+                Self::has_approval(kitty_id, who, ApprovalRole::SalesAgent),
+            Error::<T>::NotOwner
+        );
+        Ok(())
+    }
+
+    /// Si `who` puede mover `kitty_id` de `owner`: es el dueño, uno de sus operadores vigentes o
+    /// tiene una autorización `ApprovalRole::Transfer` vigente. Un agente de ventas no puede.
+    fn can_transfer(kitty_id: [u8; 32], owner: &T::AccountId, who: &T::AccountId) -> bool {
+        owner == who ||
+            Self::is_operator(owner, who) ||
+            Self::has_approval(kitty_id, who, ApprovalRole::Transfer)
+    }
+
+    /// `who` transfiere `kitty_id` de `from` a `to`; emite el `Transferred` de `do_transfer`.
+    /// La autorización se comprueba contra el dueño real, que tiene que ser `from`.
+    pub fn do_transfer_from(
+        who: T::AccountId,
+        from: T::AccountId,
+        to: T::AccountId,
+        kitty_id: [u8; 32],
+    ) -> DispatchResult {
+        let owner = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?.owner;
+        ensure!(owner == from, Error::<T>::NotOwner);
+        ensure!(Self::can_transfer(kitty_id, &owner, &who), Error::<T>::NotApproved);
+        Self::do_transfer(from, to, kitty_id)
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: transferencias en dos pasos
+    // -------------------------------------------------------------------------
+    // El dueño ofrece el kitty y el destinatario lo acepta. El kitty sigue siendo del dueño
+    // hasta entonces; si cambia de manos por otra vía, la oferta desaparece con `do_transfer`.
+
+    /// `who` ofrece `kitty_id` de su dueño a `to` durante `TransferOfferExpiry` bloques.
+    pub fn do_offer_transfer(who: T::AccountId, to: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        // Un operador o una cuenta autorizada a moverlo lo ofrece en nombre del dueño.
+        ensure!(Self::can_transfer(kitty_id, &kitty.owner, &who), Error::<T>::NotOwner);
+        let from = kitty.owner;
+        ensure!(from != to, Error::<T>::TransferToSelf);
+
+        let expires_at =
+            frame_system::Pallet::<T>::block_number().saturating_add(T::TransferOfferExpiry::get());
+        PendingTransfers::<T>::insert(
+            kitty_id,
+            PendingTransfer { from: from.clone(), to: to.clone(), expires_at },
+        );
+
+        Self::deposit_event(Event::<T>::TransferOffered { from, to, kitty_id, expires_at });
+        Ok(())
+    }
+
+    /// `who` acepta el kitty que le ofrecieron; emite el `Transferred` de `do_transfer`.
+    pub fn do_accept_transfer(who: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let pending = PendingTransfers::<T>::get(kitty_id).ok_or(Error::<T>::NoPendingTransfer)?;
+        ensure!(pending.to == who, Error::<T>::NotRecipient);
+        ensure!(
+            frame_system::Pallet::<T>::block_number() < pending.expires_at,
+            Error::<T>::TransferOfferExpired
+        );
+        Self::do_transfer(pending.from, who, kitty_id)
+    }
+
+    /// Retira la transferencia ofrecida de `kitty_id`.
+    pub fn do_cancel_transfer(who: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let pending = PendingTransfers::<T>::get(kitty_id).ok_or(Error::<T>::NoPendingTransfer)?;
+        let expired = frame_system::Pallet::<T>::block_number() >= pending.expires_at;
+        ensure!(who == pending.from || expired, Error::<T>::NotOwner);
+        PendingTransfers::<T>::remove(kitty_id);
+
+        Self::deposit_event(Event::<T>::TransferOfferCancelled { kitty_id });
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: bóveda de custodia
+    // -------------------------------------------------------------------------
+    // El kitty pasa a una subcuenta del pallet, que nadie puede firmar, y `EscrowVault` recuerda
+    // a su dueño real. Sirve a protocolos que necesitan que el kitty no se mueva a mitad de un
+    // proceso. La bóveda es una cuenta de custodia: no lleva `KittiesOwned` ni su límite, así
+    // que nadie puede llenarla y dejar fuera a los demás; `EscrowVault` es su índice.
+
+    /// Subcuenta que custodia los kitties de la bóveda.
+    pub fn escrow_vault_account() -> T::AccountId {
+        T::PalletId::get().into_sub_account_truncating(b"vault")
+    }
+
+    /// Si `who` es una subcuenta del pallet que custodia kitties ajenos.
+    pub fn is_custody_account(who: &T::AccountId) -> bool {
+        *who == Self::escrow_vault_account()
+            || *who == Self::xcm_reserve_account()
+            || *who == Self::eth_bridge_account()
+            || *who == Self::token_wrapper_account()
+    }
+
+    /// Dueño real de `kitty_id` si está en la bóveda.
+    pub fn escrow_beneficiary(kitty_id: [u8; 32]) -> Option<T::AccountId> {
+        EscrowVault::<T>::get(kitty_id)
+    }
+
+    /// Pasa `kitty_id` de `owner` a la bóveda.
+    pub fn do_deposit_to_escrow(owner: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        Self::move_kitty(&owner, &Self::escrow_vault_account(), kitty_id)?;
+        EscrowVault::<T>::insert(kitty_id, &owner);
+        Self::set_status(kitty_id, KittyStatus::Escrowed)?;
+
+        Self::deposit_event(Event::<T>::DepositedToEscrow { owner, kitty_id });
+        Ok(())
+    }
+
+    /// Devuelve `kitty_id` de la bóveda a `owner`, su dueño real.
+    pub fn do_withdraw_from_escrow(owner: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        ensure!(
+            EscrowVault::<T>::get(kitty_id).as_ref() == Some(&owner),
+            Error::<T>::NotInEscrowVault
+        );
+        EscrowVault::<T>::remove(kitty_id);
+        Self::clear_status(kitty_id, KittyStatus::Escrowed);
+        Self::move_kitty(&Self::escrow_vault_account(), &owner, kitty_id)?;
+
+        Self::deposit_event(Event::<T>::WithdrawnFromEscrow { owner, kitty_id });
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: kitties en otras cadenas
+    // -------------------------------------------------------------------------
+    // Flujo con reserva: el kitty enviado pasa a `xcm_reserve_account` y `KittyTransport` pide
+    // a la parachain de destino que acuñe un derivado. Cuando esa parachain lo quema, devuelve
+    // el kitty con `receive_kitty` y sale de la reserva. Solo la parachain que tiene el derivado
+    // puede sacarlo. La reserva es una cuenta de custodia sin límite de kitties; `KittiesAbroad`
+    // es su índice. Con la feature `xcm`, `xcm_adapter::KittiesTransactor` deja al ejecutor XCM
+    // del runtime mover kitties sobre este mismo registro.
+    // Un NFT ajeno que llega por `receive_kitty` se envuelve en un kitty nuevo de generación 0;
+    // su procedencia queda en `ForeignKitties` y `KittyProvenance`.
+
+    /// Subcuenta que custodia los kitties enviados a otras cadenas.
+    pub fn xcm_reserve_account() -> T::AccountId {
+        T::PalletId::get().into_sub_account_truncating(b"xcmreserve")
+    }
+
+    /// Pasa `kitty_id` de `owner` a la reserva y lo envía a `para_id`.
+    pub fn do_send_kitty_to_parachain(
+        owner: T::AccountId,
+        kitty_id: [u8; 32],
+        para_id: u32,
+        beneficiary: [u8; 32],
+    ) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        Self::move_kitty(&owner, &Self::xcm_reserve_account(), kitty_id)?;
+        KittiesAbroad::<T>::insert(kitty_id, para_id);
+        T::KittyTransport::send_kitty(para_id, kitty_id, &kitty.dna, beneficiary)?;
+
+        Self::deposit_event(Event::<T>::KittySentAbroad { kitty_id, from: owner, para_id, beneficiary });
+        Ok(())
+    }
+
+    /// Recibe `kitty_id` de `para_id` para `beneficiary`: saca el original de la reserva si es
+    /// un derivado de vuelta o, si no, acuña su envoltorio.
+    pub fn do_receive_kitty(
+        para_id: u32,
+        kitty_id: [u8; 32],
+        dna: KittyDna<T>,
+        beneficiary: T::AccountId,
+    ) -> DispatchResult {
+        if KittiesAbroad::<T>::get(kitty_id) == Some(para_id) {
+            return Self::return_kitty(para_id, kitty_id, dna, beneficiary);
+        }
+        // Un kitty enviado a otra parachain solo puede volver desde ella.
+        ensure!(!KittiesAbroad::<T>::contains_key(kitty_id), Error::<T>::NotAbroad);
+        Self::wrap_foreign_kitty(para_id, kitty_id, dna, beneficiary)
+    }
+
+    /// Saca `kitty_id` de la reserva para `beneficiary`. El derivado debe traer el mismo ADN.
+    fn return_kitty(
+        para_id: u32,
+        kitty_id: [u8; 32],
+        dna: KittyDna<T>,
+        beneficiary: T::AccountId,
+    ) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.dna == dna, Error::<T>::InvalidForeignDna);
+        KittiesAbroad::<T>::remove(kitty_id);
+        Self::move_kitty(&Self::xcm_reserve_account(), &beneficiary, kitty_id)?;
+
+        Self::deposit_event(Event::<T>::KittyReturned { kitty_id, para_id, to: beneficiary });
+        Ok(())
+    }
+
+    /// Acuña para `beneficiary` un kitty que envuelve el NFT `foreign_id` de `para_id`. Su
+    /// identificador local se deriva del origen, así que no choca con los kitties nativos.
+    fn wrap_foreign_kitty(
+        para_id: u32,
+        foreign_id: [u8; 32],
+        dna: KittyDna<T>,
+        beneficiary: T::AccountId,
+    ) -> DispatchResult {
+        ensure!(dna.len() == T::DnaLength::get() as usize, Error::<T>::InvalidForeignDna);
+        ensure!(!ForeignKitties::<T>::contains_key(para_id, foreign_id), Error::<T>::AlreadyWrapped);
+        let kitty_id: [u8; 32] = BlakeTwo256::hash_of(&(b"foreign", para_id, foreign_id)).into();
+        Self::mint_with_dna(beneficiary.clone(), kitty_id, dna, 0, None)?;
+        ForeignKitties::<T>::insert(para_id, foreign_id, kitty_id);
+        KittyProvenance::<T>::insert(kitty_id, (para_id, foreign_id));
+
+        Self::deposit_event(Event::<T>::ForeignKittyWrapped { kitty_id, para_id, foreign_id, to: beneficiary });
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: puente con Ethereum
+    // -------------------------------------------------------------------------
+    // Igual que con las parachains: el kitty exportado pasa a `eth_bridge_account` y
+    // `EthereumOutbound` entrega al contrato ERC-721 un mensaje de `eth_bridge` para que acuñe el
+    // token. Cuando el token se quema allí, el puente verifica la prueba y `import_kitty` saca el
+    // kitty de la custodia. Los mensajes de entrada se aceptan en orden estricto de nonce. La
+    // custodia no tiene límite de kitties; `KittiesOnEthereum` y `ParkedImports` son su índice.
+
+    /// Subcuenta que custodia los kitties exportados a Ethereum.
+    pub fn eth_bridge_account() -> T::AccountId {
+        T::PalletId::get().into_sub_account_truncating(b"ethbridge")
+    }
+
+    /// Pasa `kitty_id` de `owner` a la custodia y envía su mensaje a `eth_address`.
+    pub fn do_export_kitty(owner: T::AccountId, kitty_id: [u8; 32], eth_address: [u8; 20]) -> DispatchResult {
+        // El ERC-721 no admite la dirección cero como dueña.
+        ensure!(eth_address != [0u8; 20], Error::<T>::InvalidEthAddress);
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        Self::move_kitty(&owner, &Self::eth_bridge_account(), kitty_id)?;
+
+        let nonce = EthOutboundNonce::<T>::get().saturating_add(1);
+        let message = eth_bridge::ExportMessage {
+            nonce,
+            token_id: kitty_id,
+            to: eth_address,
+            dna_hash: eth_bridge::dna_hash(&kitty.dna),
+        };
+        T::EthereumOutbound::send_message(&message.abi_encode())?;
+        EthOutboundNonce::<T>::put(nonce);
+        KittiesOnEthereum::<T>::insert(kitty_id, eth_address);
+
+        Self::deposit_event(Event::<T>::KittyExported {
+            kitty_id,
+            from: owner,
+            eth_address,
+            nonce,
+            commitment: message.commitment(),
+        });
+        Ok(())
+    }
+
+    /// Aplica un mensaje de Ethereum ya verificado: saca el kitty de la custodia para su
+    /// destinatario si el ADN coincide. Solo falla un mensaje mal formado o fuera de orden; los
+    /// demás consumen su nonce para no bloquear el canal. Uno que no se puede aplicar se descarta
+    /// con `KittyImportRejected`. Si el destinatario no puede recibir el kitty, este se queda en
+    /// custodia a su nombre hasta que lo reclame con `claim_parked_import`.
+    pub fn do_import_kitty(payload: &[u8; eth_bridge::MESSAGE_LEN]) -> DispatchResult {
+        let message = eth_bridge::ImportMessage::abi_decode(payload).ok_or(Error::<T>::InvalidBridgeMessage)?;
+        ensure!(message.nonce == EthInboundNonce::<T>::get().saturating_add(1), Error::<T>::UnexpectedBridgeNonce);
+        EthInboundNonce::<T>::put(message.nonce);
+        let (kitty_id, nonce) = (message.token_id, message.nonce);
+
+        let to = match Self::import_recipient(&message) {
+            Ok(to) => to,
+            Err(error) => {
+                Self::deposit_event(Event::<T>::KittyImportRejected { kitty_id, nonce, error });
+                return Ok(());
+            },
+        };
+        KittiesOnEthereum::<T>::remove(kitty_id);
+        match with_storage_layer(|| Self::move_kitty(&Self::eth_bridge_account(), &to, kitty_id)) {
+            Ok(()) => Self::deposit_event(Event::<T>::KittyImported { kitty_id, to, nonce }),
+            Err(error) => {
+                ParkedImports::<T>::insert(kitty_id, &to);
+                Self::deposit_event(Event::<T>::KittyImportParked { kitty_id, to, nonce, error });
+            },
+        }
+        Ok(())
+    }
+
+    /// Destinatario de `message`, si el kitty está en Ethereum y el ADN coincide.
+    fn import_recipient(message: &eth_bridge::ImportMessage) -> Result<T::AccountId, DispatchError> {
+        ensure!(KittiesOnEthereum::<T>::contains_key(message.token_id), Error::<T>::NotOnEthereum);
+        let kitty = Kitties::<T>::get(message.token_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(eth_bridge::dna_hash(&kitty.dna) == message.dna_hash, Error::<T>::InvalidForeignDna);
+        T::AccountId::decode(&mut &message.recipient[..]).map_err(|_| Error::<T>::InvalidBridgeMessage.into())
+    }
+
+    /// `who` reclama el kitty que volvió de Ethereum a su nombre cuando no podía recibirlo.
+    pub fn do_claim_parked_import(who: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        ensure!(ParkedImports::<T>::get(kitty_id).as_ref() == Some(&who), Error::<T>::NotParked);
+        Self::move_kitty(&Self::eth_bridge_account(), &who, kitty_id)?;
+
+        Self::deposit_event(Event::<T>::ParkedImportClaimed { kitty_id, to: who });
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: kitties envueltos en tokens
+    // -------------------------------------------------------------------------
+    // El kitty pasa a `token_wrapper_account` y se crea en `KittyTokens` un activo nuevo con una
+    // sola unidad, que recibe el dueño. Quien tenga esa unidad puede quemarla y quedarse el
+    // kitty. `WrappedKitties` impide envolver dos veces el mismo kitty. El activo queda vacío
+    // tras desenvolverlo y no se reutiliza: cada envoltorio necesita un id nuevo. Como la bóveda,
+    // es una cuenta de custodia sin límite de kitties; `WrappedKitties` es su índice.
+
+    /// Subcuenta que custodia los kitties envueltos y administra sus activos.
+    pub fn token_wrapper_account() -> T::AccountId {
+        T::PalletId::get().into_sub_account_truncating(b"tokenwrap")
+    }
+
+    /// Pasa `kitty_id` de `owner` a la custodia y le acuña la unidad del activo `asset_id`.
+    pub fn do_wrap_kitty(owner: T::AccountId, kitty_id: [u8; 32], asset_id: KittyTokenIdOf<T>) -> DispatchResult {
+        ensure!(!WrappedKitties::<T>::contains_key(kitty_id), Error::<T>::KittyWrapped);
+        ensure!(!T::KittyTokens::asset_exists(asset_id.clone()), Error::<T>::TokenAssetInUse);
+        let wrapper = Self::token_wrapper_account();
+        Self::move_kitty(&owner, &wrapper, kitty_id)?;
+        T::KittyTokens::create(asset_id.clone(), wrapper, false, One::one())?;
+        T::KittyTokens::mint_into(asset_id.clone(), &owner, One::one())?;
+        WrappedKitties::<T>::insert(kitty_id, asset_id.clone());
+
+        Self::deposit_event(Event::<T>::KittyWrappedIntoToken { kitty_id, asset_id, owner });
+        Ok(())
+    }
+
+    /// Quema la unidad que tiene `who` del activo de `kitty_id` y le entrega el kitty.
+    pub fn do_unwrap_kitty(who: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let asset_id = WrappedKitties::<T>::take(kitty_id).ok_or(Error::<T>::NotWrapped)?;
+        T::KittyTokens::burn_from(
+            asset_id.clone(),
+            &who,
+            One::one(),
+            Preservation::Expendable,
+            Precision::Exact,
+            Fortitude::Polite,
+        )?;
+        Self::move_kitty(&Self::token_wrapper_account(), &who, kitty_id)?;
+
+        Self::deposit_event(Event::<T>::KittyUnwrapped { kitty_id, asset_id, to: who });
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: copropiedad
+    // -------------------------------------------------------------------------
+    // El kitty sigue a nombre del dueño, pero bloqueado como los de una subasta o un regalo.
+    // Los copropietarios proponen y aprueban acciones; al ejecutar una, la copropiedad termina y
+    // se descartan las demás propuestas.
+
+    /// `owner` reparte `kitty_id` entre él mismo y `co_owners` con umbral `threshold`.
+    pub fn do_enable_co_ownership(
+        owner: T::AccountId,
+        kitty_id: [u8; 32],
+        co_owners: Vec<T::AccountId>,
+        threshold: u32,
+    ) -> DispatchResult {
+        let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.owner == owner, Error::<T>::NotOwner);
+        Self::ensure_movable(kitty_id)?;
+
+        // El dueño siempre es copropietario; las repeticiones no cuentan dos veces.
+        let mut owners = Vec::with_capacity(co_owners.len() + 1);
+        for who in core::iter::once(owner).chain(co_owners) {
+            if !owners.contains(&who) {
+                owners.push(who);
+            }
+        }
+        ensure!(threshold >= 1 && threshold as usize <= owners.len(), Error::<T>::InvalidThreshold);
+        let owners: BoundedVec<T::AccountId, T::MaxCoOwners> =
+            owners.try_into().map_err(|_| Error::<T>::TooManyCoOwners)?;
+
+        // Un kitty en copropiedad no puede seguir a la venta.
+        Self::delist(kitty_id, &mut kitty);
+        Kitties::<T>::insert(kitty_id, kitty);
+        Self::set_status(kitty_id, KittyStatus::CoOwned)?;
+        CoOwners::<T>::insert(kitty_id, CoOwnership { owners: owners.clone(), threshold });
+
+        Self::deposit_event(Event::<T>::CoOwnershipEnabled { kitty_id, owners: owners.into_inner(), threshold });
+        Ok(())
+    }
+
+    /// Falla si `who` no es copropietario de `kitty_id`; si lo es, devuelve el reparto.
+    fn ensure_co_owner(kitty_id: [u8; 32], who: &T::AccountId) -> Result<CoOwnershipOf<T>, DispatchError> {
+        let co_ownership = CoOwners::<T>::get(kitty_id).ok_or(Error::<T>::NotCoOwned)?;
+        ensure!(co_ownership.owners.contains(who), Error::<T>::NotCoOwner);
+        Ok(co_ownership)
+    }
+
+    /// `who` propone `action` sobre `kitty_id` y la aprueba.
+    pub fn do_propose_action(
+        who: T::AccountId,
+        kitty_id: [u8; 32],
+        action: CoOwnerAction<T::AccountId>,
+    ) -> DispatchResult {
+        Self::ensure_co_owner(kitty_id, &who)?;
+
+        let action_id = NextCoOwnerActionId::<T>::get();
+        NextCoOwnerActionId::<T>::put(action_id.wrapping_add(1));
+        // Un copropietario siempre cabe: son como mucho `MaxCoOwners`.
+        let approvals = BoundedVec::truncate_from(Vec::from([who.clone()]));
+        CoOwnerActions::<T>::insert(kitty_id, action_id, PendingCoOwnerAction { action: action.clone(), approvals });
+
+        Self::deposit_event(Event::<T>::CoOwnerActionProposed { kitty_id, action_id, action, proposer: who });
+        Ok(())
+    }
+
+    /// `who` aprueba la acción `action_id` de `kitty_id`.
+    pub fn do_approve_action(who: T::AccountId, kitty_id: [u8; 32], action_id: u32) -> DispatchResult {
+        Self::ensure_co_owner(kitty_id, &who)?;
+        CoOwnerActions::<T>::try_mutate(kitty_id, action_id, |pending| -> DispatchResult {
+            let pending = pending.as_mut().ok_or(Error::<T>::NoCoOwnerAction)?;
+            ensure!(!pending.approvals.contains(&who), Error::<T>::AlreadyApproved);
+            pending.approvals.try_push(who.clone()).map_err(|_| Error::<T>::TooManyCoOwners)?;
+            Ok(())
+        })?;
+
+        Self::deposit_event(Event::<T>::CoOwnerActionApproved { kitty_id, action_id, who });
+        Ok(())
+    }
+
+    /// Ejecuta la acción `action_id` de `kitty_id` si reúne el umbral.
+    pub fn do_execute_action(who: T::AccountId, kitty_id: [u8; 32], action_id: u32) -> DispatchResult {
+        let co_ownership = Self::ensure_co_owner(kitty_id, &who)?;
+        let pending = CoOwnerActions::<T>::get(kitty_id, action_id).ok_or(Error::<T>::NoCoOwnerAction)?;
+        // Solo cuentan quienes siguen siendo copropietarios.
+        let approvals = pending.approvals.iter().filter(|who| co_ownership.owners.contains(who)).count();
+        ensure!(approvals as u32 >= co_ownership.threshold, Error::<T>::NotEnoughApprovals);
+
+        CoOwners::<T>::remove(kitty_id);
+        Self::clear_status(kitty_id, KittyStatus::CoOwned);
+        let _ = CoOwnerActions::<T>::clear_prefix(kitty_id, u32::MAX, None);
+        match pending.action {
+            CoOwnerAction::Transfer { to } => {
+                let owner = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?.owner;
+                Self::do_transfer(owner, to, kitty_id)?;
+            },
+            CoOwnerAction::Dissolve => {},
+        }
+
+        Self::deposit_event(Event::<T>::CoOwnerActionExecuted { kitty_id, action_id });
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: alquileres
+    // -------------------------------------------------------------------------
+    // El dueño conserva el kitty, pero mientras dura el alquiler su usuario (`kitty_user`) es
+    // quien lo alquiló: solo él puede criar con él, y el kitty queda bloqueado como en una
+    // subasta. Sin garantía el alquiler se paga entero al empezar; con garantía, el inquilino
+    // la deja retenida (`HoldReason::RentalCollateral`) y paga al terminar. Si entonces no puede
+    // pagar, el dueño cobra lo debido de la garantía sin más trámite. Cada alquiler se apunta en
+    // `RentalsEndingAt` bajo su bloque final y `on_initialize` lo cierra al llegar ese bloque.
+
+    /// Si `kitty_id` tiene un alquiler en curso.
+    pub fn is_rented(kitty_id: [u8; 32]) -> bool {
+        Rentals::<T>::get(kitty_id)
+            .is_some_and(|rental| frame_system::Pallet::<T>::block_number() < rental.ends_at)
+    }
+
+    /// Cuenta que puede usar `kitty` (con identificador `kitty_id`) en cría o juegos: quien lo
+    /// tiene alquilado o, si no, su dueño.
+    pub fn kitty_user(kitty_id: [u8; 32], kitty: &Kitty<T>) -> T::AccountId {
+        match Rentals::<T>::get(kitty_id) {
+            Some(rental) if frame_system::Pallet::<T>::block_number() < rental.ends_at => rental.renter,
+            _ => kitty.owner.clone(),
+        }
+    }
+
+    /// `caller` ofrece `kitty_id` de su dueño en alquiler.
+    pub fn do_list_for_rent(
+        caller: T::AccountId,
+        kitty_id: [u8; 32],
+        price_per_block: BalanceOf<T>,
+        max_duration: BlockNumberFor<T>,
+        collateral: BalanceOf<T>,
+    ) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        // Un operador o agente lo anuncia en nombre del dueño, que es quien cobra.
+        Self::ensure_can_list(kitty_id, &kitty.owner, &caller)?;
+        ensure!(!max_duration.is_zero(), Error::<T>::InvalidRentalDuration);
+        Self::ensure_can_trade(&kitty.owner)?;
+        RentalListings::<T>::insert(kitty_id, RentalListing { price_per_block, max_duration, collateral });
+
+        Self::deposit_event(Event::<T>::RentalListed { kitty_id, price_per_block, max_duration, collateral });
+        Ok(())
+    }
+
+    /// Retira el anuncio de alquiler de `kitty_id`.
+    pub fn do_unlist_for_rent(caller: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        Self::ensure_can_list(kitty_id, &kitty.owner, &caller)?;
+        RentalListings::<T>::take(kitty_id).ok_or(Error::<T>::NotForRent)?;
+
+        Self::deposit_event(Event::<T>::RentalUnlisted { kitty_id });
+        Ok(())
+    }
+
+    /// `renter` alquila `kitty_id` durante `duration` bloques y paga al dueño o, si el anuncio pide
+    /// garantía, la retiene.
+    pub fn do_rent(renter: T::AccountId, kitty_id: [u8; 32], duration: BlockNumberFor<T>) -> DispatchResult {
+        Self::ensure_can_trade(&renter)?;
+        let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        let listing = RentalListings::<T>::get(kitty_id).ok_or(Error::<T>::NotForRent)?;
+        Self::ensure_can_trade(&kitty.owner)?;
+        ensure!(kitty.owner != renter, Error::<T>::TransferToSelf);
+        ensure!(
+            !duration.is_zero() && duration <= listing.max_duration,
+            Error::<T>::InvalidRentalDuration
+        );
+        // Un kitty bloqueado no se puede alquilar, igual que no se puede vender.
+        Self::ensure_movable(kitty_id)?;
+
+        let blocks: BalanceOf<T> = duration.saturated_into::<u128>().saturated_into();
+        let price = listing.price_per_block.saturating_mul(blocks);
+        let collateral = listing.collateral;
+        let (paid, owed) = if collateral.is_zero() { (price, Zero::zero()) } else { (Zero::zero(), price) };
+        if !paid.is_zero() {
+            T::NativeBalance::transfer(&renter, &kitty.owner, paid, Preservation::Preserve)?;
+        }
+        if !collateral.is_zero() {
+            T::NativeBalance::hold(&HoldReason::RentalCollateral.into(), &renter, collateral)?;
+        }
+        let ends_at = frame_system::Pallet::<T>::block_number().saturating_add(duration);
+        RentalsEndingAt::<T>::try_append(ends_at, kitty_id)
+            .map_err(|_| Error::<T>::TooManyRentalsEnding)?;
+        // Una petición de terminar un alquiler anterior no afecta al nuevo.
+        RentalEndRequests::<T>::remove(kitty_id);
+        Rentals::<T>::insert(kitty_id, Rental { renter: renter.clone(), ends_at, owed, collateral });
+        // Alquilado deja de estar a la venta.
+        Self::delist(kitty_id, &mut kitty);
+        Kitties::<T>::insert(kitty_id, kitty);
+        Self::set_status(kitty_id, KittyStatus::Rented)?;
+
+        Self::deposit_event(Event::<T>::KittyRented { kitty_id, renter, ends_at, paid, collateral });
+        Ok(())
+    }
+
+    /// `who` pide terminar el alquiler de `kitty_id`; si la otra parte ya lo pidió, termina.
+    pub fn do_end_rental(who: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(Self::is_rented(kitty_id), Error::<T>::NotRented);
+        let rental = Rentals::<T>::get(kitty_id).ok_or(Error::<T>::NotRented)?;
+        ensure!(who == kitty.owner || who == rental.renter, Error::<T>::NotRentalParty);
+
+        match RentalEndRequests::<T>::get(kitty_id) {
+            Some(requested_by) if requested_by != who => {
+                RentalsEndingAt::<T>::mutate(rental.ends_at, |ending| ending.retain(|id| *id != kitty_id));
+                Self::close_rental(kitty_id);
+            },
+            _ => {
+                RentalEndRequests::<T>::insert(kitty_id, &who);
+                Self::deposit_event(Event::<T>::RentalEndRequested { kitty_id, by: who });
+            },
+        }
+        Ok(())
+    }
+
+    /// Cierra el alquiler de `kitty_id`, si lo hay, y liquida lo que se deba.
+    pub(crate) fn close_rental(kitty_id: [u8; 32]) {
+        RentalEndRequests::<T>::remove(kitty_id);
+        if let Some(rental) = Rentals::<T>::take(kitty_id) {
+            Self::clear_status(kitty_id, KittyStatus::Rented);
+            if let Some(kitty) = Kitties::<T>::get(kitty_id) {
+                Self::settle_rental(kitty_id, &kitty.owner, &rental);
+            }
+            Self::release_rental_collateral(&rental);
+            Self::deposit_event(Event::<T>::RentalEnded { kitty_id, renter: rental.renter });
+        }
+    }
+
+    /// Cobra a `rental.renter` lo que debe a `owner`. Si no le alcanza el saldo libre, el dueño
+    /// cobra de la garantía lo debido (como mucho, toda la garantía).
+    fn settle_rental(kitty_id: [u8; 32], owner: &T::AccountId, rental: &RentalOf<T>) {
+        if rental.owed.is_zero() ||
+            T::NativeBalance::transfer(&rental.renter, owner, rental.owed, Preservation::Preserve).is_ok()
+        {
+            return;
+        }
+        let slashed = T::NativeBalance::transfer_on_hold(
+            &HoldReason::RentalCollateral.into(),
+            &rental.renter,
+            owner,
+            rental.owed.min(rental.collateral),
+            Precision::BestEffort,
+            Restriction::Free,
+            Fortitude::Force,
+        )
+        .unwrap_or_else(|_| Zero::zero());
+        Self::deposit_event(Event::<T>::RentalDefaulted {
+            kitty_id,
+            renter: rental.renter.clone(),
+            slashed,
+        });
+    }
+
+    /// Devuelve al inquilino lo que quede de la garantía de `rental`.
+    fn release_rental_collateral(rental: &RentalOf<T>) {
+        if !rental.collateral.is_zero() {
+            let _ = T::NativeBalance::release(
+                &HoldReason::RentalCollateral.into(),
+                &rental.renter,
+                rental.collateral,
+                Precision::BestEffort,
+            );
+        }
+    }
+
+    /// Cierra los alquileres que terminan en `now`. Los ya terminados a mano se ignoran.
+    pub(crate) fn end_expired_rentals(now: BlockNumberFor<T>) -> Weight {
+        let ending = RentalsEndingAt::<T>::take(now);
+        for kitty_id in ending.iter() {
+            Self::close_rental(*kitty_id);
+        }
+        T::DbWeight::get()
+            .reads_writes(1, 1)
+            .saturating_add(T::WeightInfo::close_rental().saturating_mul(ending.len() as u64))
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: estado de los kitties
+    // -------------------------------------------------------------------------
+    // Cada subsistema que ocupa un kitty lo pasa a su estado con `set_status`, que rechaza el
+    // cambio si el kitty ya está ocupado en otro, y lo devuelve a `Idle` con `clear_status` al
+    // soltarlo. Así dos subsistemas no pueden reclamar el mismo kitty a la vez.
+
+    /// Estado actual de `kitty_id`.
+    pub fn kitty_status(kitty_id: [u8; 32]) -> KittyStatus {
+        KittyStatuses::<T>::get(kitty_id)
+    }
+
+    /// Dueño del kitty `item` de la colección `collection` de `nonfungibles_v2`.
+    fn kitty_owner(collection: &u32, item: &[u8; 32]) -> Result<T::AccountId, DispatchError> {
+        ensure!(*collection == T::KittyCollection::get(), Error::<T>::UnknownCollection);
+        Ok(Kitties::<T>::get(item).ok_or(Error::<T>::NoKitty)?.owner)
+    }
+
+    /// Falla, con el error del estado que lo ocupa, si `kitty_id` no está libre.
+    pub(crate) fn ensure_movable(kitty_id: [u8; 32]) -> DispatchResult {
+        match Self::kitty_status(kitty_id) {
+            KittyStatus::Idle | KittyStatus::Listed | KittyStatus::Breeding => Ok(()),
+            KittyStatus::InAuction => Err(Error::<T>::KittyInAuction.into()),
+            KittyStatus::Rented => Err(Error::<T>::KittyRented.into()),
+            KittyStatus::Escrowed => Err(Error::<T>::KittyInVault.into()),
+            KittyStatus::Locked => Err(Error::<T>::KittyLocked.into()),
+            KittyStatus::InRaffle => Err(Error::<T>::KittyInRaffle.into()),
+            KittyStatus::InEscrow => Err(Error::<T>::KittyInEscrow.into()),
+            KittyStatus::InBundle => Err(Error::<T>::KittyInBundle.into()),
+            KittyStatus::Gifted => Err(Error::<T>::KittyGifted.into()),
+            KittyStatus::CoOwned => Err(Error::<T>::KittyCoOwned.into()),
+        }
+    }
+
+    /// Saca `kitty_id` de la venta: quita el precio de `kitty`, la venta en activo, la reserva
+    /// y el depósito del listado. Guardar `kitty` queda a cargo del llamador.
+    pub(crate) fn delist(kitty_id: [u8; 32], kitty: &mut Kitty<T>) {
+        Self::track_listing(kitty_id, kitty.price.take(), None);
+        AssetListings::<T>::remove(kitty_id);
+        ReservedBuyers::<T>::remove(kitty_id);
+        Self::release_listing_deposit(kitty_id);
+        Self::clear_status(kitty_id, KittyStatus::Listed);
+    }
+
+    /// Pasa `kitty_id` a `next`, si la transición está permitida.
+    fn set_status(kitty_id: [u8; 32], next: KittyStatus) -> DispatchResult {
+        if !Self::kitty_status(kitty_id).can_become(next) {
+            Self::ensure_movable(kitty_id)?;
+        }
+        if next == KittyStatus::Idle {
+            KittyStatuses::<T>::remove(kitty_id);
+        } else {
+            KittyStatuses::<T>::insert(kitty_id, next);
+        }
+        Ok(())
+    }
+
+    /// Devuelve `kitty_id` a `Idle` si está en `current`. Devuelve si lo estaba.
+    fn clear_status(kitty_id: [u8; 32], current: KittyStatus) -> bool {
+        let was = Self::kitty_status(kitty_id) == current;
+        if was {
+            KittyStatuses::<T>::remove(kitty_id);
+        }
+        was
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: bloqueo por el dueño
+    // -------------------------------------------------------------------------
+    // Medida de seguridad personal: mientras un kitty está bloqueado no se puede transferir,
+    // vender, subastar, alquilar ni criar con él. Solo se bloquea un kitty libre, y al
+    // bloquearlo se retira de la venta.
+
+    /// Bloquea `kitty_id` de `owner`.
+    pub fn do_lock_kitty(owner: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.owner == owner, Error::<T>::NotOwner);
+        Self::ensure_movable(kitty_id)?;
+
+        Self::delist(kitty_id, &mut kitty);
+        Kitties::<T>::insert(kitty_id, kitty);
+        Self::set_status(kitty_id, KittyStatus::Locked)?;
+
+        Self::deposit_event(Event::<T>::KittyLocked { kitty_id });
+        Ok(())
+    }
+
+    /// Desbloquea `kitty_id` de `owner`.
+    pub fn do_unlock_kitty(owner: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.owner == owner, Error::<T>::NotOwner);
+        ensure!(Self::clear_status(kitty_id, KittyStatus::Locked), Error::<T>::NotLocked);
+
+        Self::deposit_event(Event::<T>::KittyUnlocked { kitty_id });
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: regalos
+    // -------------------------------------------------------------------------
+    // Como la transferencia en dos pasos, pero el kitty queda bloqueado mientras el regalo está
+    // pendiente. Cada regalo se apunta en `GiftsExpiringAt` bajo su bloque de caducidad y
+    // `on_initialize` lo devuelve al remitente al llegar ese bloque.
+
+    /// `who` regala `kitty_id` de su dueño a `to` durante `GiftExpiry` bloques.
+    pub fn do_send_gift(who: T::AccountId, to: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        // Un operador o una cuenta autorizada a moverlo lo regala en nombre del dueño.
+        ensure!(Self::can_transfer(kitty_id, &kitty.owner, &who), Error::<T>::NotOwner);
+        let from = kitty.owner.clone();
+        ensure!(from != to, Error::<T>::TransferToSelf);
+        Self::ensure_movable(kitty_id)?;
+
+        // Un regalo no puede seguir a la venta.
+        Self::delist(kitty_id, &mut kitty);
+        Kitties::<T>::insert(kitty_id, kitty);
+
+        let expires_at = frame_system::Pallet::<T>::block_number().saturating_add(T::GiftExpiry::get());
+        GiftsExpiringAt::<T>::try_append(expires_at, kitty_id)
+            .map_err(|_| Error::<T>::TooManyGiftsExpiring)?;
+        Self::set_status(kitty_id, KittyStatus::Gifted)?;
+        Gifts::<T>::insert(kitty_id, PendingTransfer { from: from.clone(), to: to.clone(), expires_at });
+
+        Self::deposit_event(Event::<T>::GiftSent { from, to, kitty_id, expires_at });
+        Ok(())
+    }
+
+    /// `who` reclama el kitty que le regalaron.
+    pub fn do_claim_gift(who: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let gift = Gifts::<T>::take(kitty_id).ok_or(Error::<T>::NoGift)?;
+        ensure!(gift.to == who, Error::<T>::NotRecipient);
+        GiftsExpiringAt::<T>::mutate(gift.expires_at, |expiring| expiring.retain(|id| *id != kitty_id));
+        Self::clear_status(kitty_id, KittyStatus::Gifted);
+        Self::do_transfer(gift.from, who.clone(), kitty_id)?;
+
+        Self::deposit_event(Event::<T>::GiftClaimed { kitty_id, to: who });
+        Ok(())
+    }
+
+    /// Devuelve al remitente los regalos que caducan en `now`.
+    pub(crate) fn reclaim_expired_gifts(now: BlockNumberFor<T>) -> Weight {
+        let expiring = GiftsExpiringAt::<T>::take(now);
+        for kitty_id in expiring.iter() {
+            Self::reclaim_gift(*kitty_id);
+        }
+        T::DbWeight::get()
+            .reads_writes(1, 1)
+            .saturating_add(T::WeightInfo::reclaim_gift().saturating_mul(expiring.len() as u64))
+    }
+
+    /// Devuelve al remitente el regalo pendiente de `kitty_id`, si lo hay. Los ya reclamados se
+    /// ignoran.
+    pub(crate) fn reclaim_gift(kitty_id: [u8; 32]) {
+        if let Some(gift) = Gifts::<T>::take(kitty_id) {
+            Self::clear_status(kitty_id, KittyStatus::Gifted);
+            Self::deposit_event(Event::<T>::GiftReclaimed { kitty_id, from: gift.from });
+        }
+    }
+
+    /// Transfiere todos los `kitty_ids` de `from` a `to`; cada uno emite su `Transferred`.
+    pub fn do_transfer_many(from: T::AccountId, to: T::AccountId, kitty_ids: Vec<[u8; 32]>) -> DispatchResult {
+        for kitty_id in kitty_ids {
+            Self::do_transfer(from.clone(), to.clone(), kitty_id)?;
+        }
+        Ok(())
+    }
+
+    /// Transfiere a `to` todos los kitties de `from`. El peor caso son `MaxKittiesOwned`.
+    pub fn do_transfer_all(from: T::AccountId, to: T::AccountId) -> DispatchResult {
+        let owned = KittiesOwned::<T>::get(&from);
+        ensure!(!owned.is_empty(), Error::<T>::NoKitty);
+        Self::do_transfer_many(from, to, owned.into_inner())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Función: do_set_price()
+    // -------------------------------------------------------------------------
+    // Permite al dueño establecer o quitar un precio de venta para su kitty.
+    pub fn do_set_price(
+        caller: T::AccountId,             // Quien realiza la llamada
+        kitty_id: [u8; 32],               // ID del kitty
+        new_price: Option<BalanceOf<T>>,  // Precio opcional (None = no venta)
+        whitelisted_buyer: Option<T::AccountId>, // Único comprador permitido (venta privada)
+    ) -> DispatchResult {
+        // Verifica que el kitty exista.
+        let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+
+        // Solo el dueño, uno de sus operadores o su agente de ventas puede establecer el precio.
+        Self::ensure_can_list(kitty_id, &kitty.owner, &caller)?;
+        let owner = kitty.owner.clone();
+
+        // Un kitty en rifa o ya vendido con escrow no se puede poner además a la venta.
+        if new_price.is_some() {
+            Self::ensure_movable(kitty_id)?;
+            // Retirar un anuncio sí se permite; anunciar, no.
+            Self::ensure_can_trade(&owner)?;
+        }
+
+        // Retirar un anuncio activo cuenta en la reputación del vendedor.
+        let listed = kitty.price.is_some() || AssetListings::<T>::contains_key(kitty_id);
+        if listed && new_price.is_none() {
+            Self::note_reputation(&owner, ReputationEvent::ListingCancelled);
+        }
+
+        // Anunciar retiene el depósito (una vez por anuncio); retirarlo lo devuelve.
+        if new_price.is_some() {
+            Self::hold_listing_deposit(&owner, kitty_id)?;
+        } else {
+            Self::release_listing_deposit(kitty_id);
+        }
+
+        // Actualiza el precio en la estructura; el precio en token nativo sustituye al de activo.
+        Self::track_listing(kitty_id, kitty.price, new_price);
+        kitty.price = new_price;
+        AssetListings::<T>::remove(kitty_id);
+
+        // Reserva la venta para un comprador concreto; sin precio no hay nada que reservar.
+        let whitelisted_buyer = whitelisted_buyer.filter(|_| new_price.is_some());
+        match &whitelisted_buyer {
+            Some(buyer) => ReservedBuyers::<T>::insert(kitty_id, buyer),
+            None => ReservedBuyers::<T>::remove(kitty_id),
+        }
+
+        // Guarda los cambios en almacenamiento.
+        Kitties::<T>::insert(kitty_id, kitty);
+        if new_price.is_some() {
+            Self::set_status(kitty_id, KittyStatus::Listed)?;
+        } else {
+            Self::clear_status(kitty_id, KittyStatus::Listed);
+        }
+
+        // Emite evento de cambio de precio.
+        Self::deposit_event(Event::<T>::PriceSet {
+            owner,
+            kitty_id,
+            new_price,
+            whitelisted_buyer,
+        });
+
+        Ok(())
+    }
+
+    /// Falla si `kitty_id` está reservado para un comprador distinto de `buyer`.
+    fn ensure_reserved_for(kitty_id: [u8; 32], buyer: &T::AccountId) -> DispatchResult {
+        if let Some(reserved) = ReservedBuyers::<T>::get(kitty_id) {
+            ensure!(&reserved == buyer, Error::<T>::NotWhitelistedBuyer);
+        }
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Función: do_buy_kitty()
+    // -------------------------------------------------------------------------
+    // Permite a un comprador adquirir un kitty en venta si paga el precio correcto.
+    pub fn do_buy_kitty(
+        buyer: T::AccountId,        // Comprador
+        kitty_id: [u8; 32],         // ID del kitty a comprar
+        price: BalanceOf<T>,        // Precio máximo dispuesto a pagar
+    ) -> DispatchResult {
+        Self::ensure_can_trade(&buyer)?;
+
+        // Obtiene el kitty desde almacenamiento.
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+
+        // Verifica que esté en venta y que el vendedor pueda seguir vendiendo.
+        let real_price = kitty.price.ok_or(Error::<T>::NotForSale)?;
+        Self::ensure_can_trade(&kitty.owner)?;
+
+        // Asegura que el comprador ofrece al menos el precio mínimo.
+        ensure!(price >= real_price, Error::<T>::MaxPriceTooLow);
+
+        // Una venta privada solo la puede ejecutar el comprador acordado.
+        Self::ensure_reserved_for(kitty_id, &buyer)?;
+
+        // Retiene el pago, cambia el dueño y solo entonces liquida la retención al vendedor, todo
+        // en una misma capa: si algo falla no queda dinero movido sin kitty ni kitty sin pagar.
+        let reason = HoldReason::Purchase.into();
+        with_storage_layer(|| {
+            T::NativeBalance::hold(&reason, &buyer, real_price)?;
+            Self::do_transfer(kitty.owner.clone(), buyer.clone(), kitty_id)?;
+            Self::pay_sale(&kitty.owner, kitty_id, real_price, |to, amount| {
+                T::NativeBalance::transfer_on_hold(
+                    &reason,
+                    &buyer,
+                    to,
+                    amount,
+                    Precision::Exact,
+                    Restriction::Free,
+                    Fortitude::Polite,
+                )
+                .map(|_| ())
+            })
+        })?;
+        Self::note_reputation(&kitty.owner, ReputationEvent::Sale);
+
+        // Emite evento de venta completada.
+        Self::deposit_event(Event::<T>::Sold { buyer, kitty_id, price: real_price });
+
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: lotes
+    // -------------------------------------------------------------------------
+    // Un lote vende varios kitties del mismo dueño por un único precio. Mientras el lote existe
+    // sus kitties no se pueden vender, rifar, transferir ni destruir por separado.
+
+    /// `caller` crea un lote con `kitty_ids`, todos del mismo dueño, que es quien lo vende. Los
+    /// kitties salen de cualquier anuncio suelto.
+    pub fn do_create_bundle(
+        caller: T::AccountId,
+        kitty_ids: BoundedVec<[u8; 32], T::MaxBundleSize>,
+        price: BalanceOf<T>,
+    ) -> DispatchResult {
+        ensure!(!kitty_ids.is_empty(), Error::<T>::EmptyBundle);
+        let seller = Kitties::<T>::get(kitty_ids[0]).ok_or(Error::<T>::NoKitty)?.owner;
+        Self::ensure_can_trade(&seller)?;
+
+        let bundle_id = NextBundleId::<T>::get();
+        for kitty_id in kitty_ids.iter() {
+            let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+            ensure!(kitty.owner == seller, Error::<T>::NotOwner);
+            // Un operador o agente lo incluye en nombre del dueño.
+            Self::ensure_can_list(*kitty_id, &seller, &caller)?;
+            // También rechaza un kitty repetido en la misma lista.
+            Self::ensure_movable(*kitty_id)?;
+
+            Self::delist(*kitty_id, &mut kitty);
+            Kitties::<T>::insert(kitty_id, kitty);
+            Self::set_status(*kitty_id, KittyStatus::InBundle)?;
+            KittyBundle::<T>::insert(kitty_id, bundle_id);
+        }
+
+        NextBundleId::<T>::put(bundle_id.wrapping_add(1));
+        Bundles::<T>::insert(
+            bundle_id,
+            Bundle { seller: seller.clone(), kitty_ids: kitty_ids.clone(), price },
+        );
+
+        Self::deposit_event(Event::<T>::BundleCreated {
+            bundle_id,
+            seller,
+            kitty_ids: kitty_ids.into_inner(),
+            price,
+        });
+        Ok(())
+    }
+
+    /// Deshace el lote `bundle_id`, a petición del vendedor o de uno de sus operadores; sus
+    /// kitties quedan libres y sin precio.
+    pub fn do_cancel_bundle(caller: T::AccountId, bundle_id: u32) -> DispatchResult {
+        let bundle = Bundles::<T>::get(bundle_id).ok_or(Error::<T>::NoBundle)?;
+        ensure!(
+            bundle.seller == caller || Self::is_operator(&bundle.seller, &caller),
+            Error::<T>::NotOwner
+        );
+        Self::unlock_bundle(bundle_id, &bundle);
+        Self::note_reputation(&bundle.seller, ReputationEvent::ListingCancelled);
+
+        Self::deposit_event(Event::<T>::BundleCancelled { bundle_id });
+        Ok(())
+    }
+
+    /// Compra el lote entero. Igual que `do_buy_kitty`, el pago se retiene hasta que todos los
+    /// kitties son del comprador; la comisión y las regalías se calculan por kitty, repartiendo el
+    /// precio a partes iguales.
+    pub fn do_buy_bundle(buyer: T::AccountId, bundle_id: u32, max_price: BalanceOf<T>) -> DispatchResult {
+        Self::ensure_can_trade(&buyer)?;
+        let bundle = Bundles::<T>::get(bundle_id).ok_or(Error::<T>::NoBundle)?;
+        Self::ensure_can_trade(&bundle.seller)?;
+        ensure!(max_price >= bundle.price, Error::<T>::MaxPriceTooLow);
+
+        let reason = HoldReason::Purchase.into();
+        with_storage_layer(|| {
+            T::NativeBalance::hold(&reason, &buyer, bundle.price)?;
+            Self::unlock_bundle(bundle_id, &bundle);
+
+            let count: BalanceOf<T> = (bundle.kitty_ids.len() as u32).into();
+            let share = bundle.price / count;
+            let mut remaining = bundle.price;
+            for (index, kitty_id) in bundle.kitty_ids.iter().enumerate() {
+                Self::do_transfer(bundle.seller.clone(), buyer.clone(), *kitty_id)?;
+                // El último kitty se lleva el resto de la división.
+                let part = if index + 1 == bundle.kitty_ids.len() { remaining } else { share };
+                remaining = remaining.saturating_sub(part);
+                Self::pay_sale(&bundle.seller, *kitty_id, part, |to, amount| {
+                    T::NativeBalance::transfer_on_hold(
+                        &reason,
+                        &buyer,
+                        to,
+                        amount,
+                        Precision::Exact,
+                        Restriction::Free,
+                        Fortitude::Polite,
+                    )
+                    .map(|_| ())
+                })?;
+            }
+            Ok::<_, DispatchError>(())
+        })?;
+        Self::note_reputation(&bundle.seller, ReputationEvent::Sale);
+
+        Self::deposit_event(Event::<T>::BundleSold { bundle_id, buyer, price: bundle.price });
+        Ok(())
+    }
+
+    /// Borra el lote y libera sus kitties.
+    fn unlock_bundle(bundle_id: u32, bundle: &BundleOf<T>) {
+        Bundles::<T>::remove(bundle_id);
+        for kitty_id in bundle.kitty_ids.iter() {
+            KittyBundle::<T>::remove(kitty_id);
+            Self::clear_status(*kitty_id, KittyStatus::InBundle);
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: intercambios
+    // -------------------------------------------------------------------------
+    // Un intercambio cambia un kitty por otro, opcionalmente con un pago extra del proponente.
+    // Los kitties no se bloquean al proponer: la propiedad y el pago se comprueban al aceptar.
+
+    /// Propone cambiar `offered` (de `proposer`) por `wanted`, con un pago extra opcional.
+    pub fn do_propose_swap(
+        proposer: T::AccountId,
+        offered: [u8; 32],
+        wanted: [u8; 32],
+        extra_payment: Option<BalanceOf<T>>,
+    ) -> DispatchResult {
+        let kitty = Kitties::<T>::get(offered).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.owner == proposer, Error::<T>::NotOwner);
+        let other = Kitties::<T>::get(wanted).ok_or(Error::<T>::NoKitty)?;
+        ensure!(other.owner != proposer, Error::<T>::TransferToSelf);
+
+        let swap_id = NextSwapId::<T>::get();
+        NextSwapId::<T>::put(swap_id.wrapping_add(1));
+        let expires_at =
+            frame_system::Pallet::<T>::block_number().saturating_add(T::SwapExpiry::get());
+        Swaps::<T>::insert(
+            swap_id,
+            KittySwap { proposer: proposer.clone(), offered, wanted, extra_payment, expires_at },
+        );
+
+        Self::deposit_event(Event::<T>::SwapProposed {
+            swap_id,
+            proposer,
+            offered,
+            wanted,
+            extra_payment,
+            expires_at,
+        });
+        Ok(())
+    }
+
+    /// El dueño de `wanted` acepta el intercambio: los dos kitties y el pago extra se mueven a
+    /// la vez o no se mueve nada.
+    pub fn do_accept_swap(who: T::AccountId, swap_id: u32) -> DispatchResult {
+        let swap = Swaps::<T>::get(swap_id).ok_or(Error::<T>::NoSwap)?;
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(now < swap.expires_at, Error::<T>::SwapExpired);
+        Self::ensure_can_trade(&who)?;
+        Self::ensure_can_trade(&swap.proposer)?;
+
+        with_storage_layer(|| {
+            Self::do_transfer(who.clone(), swap.proposer.clone(), swap.wanted)?;
+            Self::do_transfer(swap.proposer.clone(), who.clone(), swap.offered)?;
+            if let Some(extra) = swap.extra_payment {
+                T::NativeBalance::transfer(&swap.proposer, &who, extra, Preservation::Preserve)?;
+            }
+            Ok::<_, DispatchError>(())
+        })?;
+        Swaps::<T>::remove(swap_id);
+
+        Self::deposit_event(Event::<T>::SwapAccepted { swap_id, accepted_by: who });
+        Ok(())
+    }
+
+    /// Retira un intercambio: el proponente en cualquier momento, cualquiera una vez caducado.
+    pub fn do_cancel_swap(who: T::AccountId, swap_id: u32) -> DispatchResult {
+        let swap = Swaps::<T>::get(swap_id).ok_or(Error::<T>::NoSwap)?;
+        let expired = frame_system::Pallet::<T>::block_number() >= swap.expires_at;
+        ensure!(who == swap.proposer || expired, Error::<T>::NotOwner);
+        Swaps::<T>::remove(swap_id);
+
+        Self::deposit_event(Event::<T>::SwapCancelled { swap_id });
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: ofertas
+    // -------------------------------------------------------------------------
+    // Cualquiera puede ofertar por cualquier kitty, esté o no a la venta. El importe queda
+    // retenido (`HoldReason::KittyOffer`) en la cuenta del ofertante hasta que el dueño acepta la
+    // oferta o esta se retira; así aceptar nunca falla porque el ofertante gastó el saldo. Una
+    // oferta caducada la puede retirar cualquiera, y la retención vuelve siempre al ofertante.
+
+    /// Oferta `amount` por `kitty_id`, válida hasta el bloque `expiry` (sin incluirlo).
+    pub fn do_make_offer(
+        bidder: T::AccountId,
+        kitty_id: [u8; 32],
+        amount: BalanceOf<T>,
+        expiry: BlockNumberFor<T>,
+    ) -> DispatchResult {
+        Self::ensure_can_trade(&bidder)?;
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.owner != bidder, Error::<T>::TransferToSelf);
+        ensure!(expiry > frame_system::Pallet::<T>::block_number(), Error::<T>::OfferExpired);
+        // El importe tiene que poder abrir por sí solo la cuenta de un vendedor sin saldo.
+        ensure!(amount >= T::NativeBalance::minimum_balance(), Error::<T>::OfferTooLow);
+
+        T::NativeBalance::hold(&HoldReason::KittyOffer.into(), &bidder, amount)?;
+        let offer_id = NextOfferId::<T>::get();
+        NextOfferId::<T>::put(offer_id.wrapping_add(1));
+        Offers::<T>::insert(
+            kitty_id,
+            offer_id,
+            Offer { bidder: bidder.clone(), amount, expires_at: expiry },
+        );
+
+        Self::deposit_event(Event::<T>::OfferMade { offer_id, kitty_id, bidder, amount, expires_at: expiry });
+        Ok(())
+    }
+
+    /// El dueño de `kitty_id` (o quien puede venderlo por él) acepta la oferta: recibe el importe (menos comisión y regalías) y
+    /// el ofertante recibe el kitty.
+    pub fn do_accept_offer(caller: T::AccountId, kitty_id: [u8; 32], offer_id: u32) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        // Un operador o agente acepta en nombre del dueño, que es quien cobra.
+        Self::ensure_can_list(kitty_id, &kitty.owner, &caller)?;
+        let owner = kitty.owner;
+        let offer = Offers::<T>::get(kitty_id, offer_id).ok_or(Error::<T>::NoOffer)?;
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(now < offer.expires_at, Error::<T>::OfferExpired);
+        Self::ensure_can_trade(&owner)?;
+        Self::ensure_can_trade(&offer.bidder)?;
+
+        let reason = HoldReason::KittyOffer.into();
+        with_storage_layer(|| {
+            Self::do_transfer(owner.clone(), offer.bidder.clone(), kitty_id)?;
+            Self::pay_sale(&owner, kitty_id, offer.amount, |to, amount| {
+                T::NativeBalance::transfer_on_hold(
+                    &reason,
+                    &offer.bidder,
+                    to,
+                    amount,
+                    Precision::Exact,
+                    Restriction::Free,
+                    Fortitude::Polite,
+                )
+                .map(|_| ())
+            })
+        })?;
+        Offers::<T>::remove(kitty_id, offer_id);
+        Self::note_reputation(&owner, ReputationEvent::Sale);
+
+        Self::deposit_event(Event::<T>::OfferAccepted {
+            offer_id,
+            kitty_id,
+            seller: owner,
+            bidder: offer.bidder,
+            amount: offer.amount,
+        });
+        Ok(())
+    }
+
+    /// Retira una oferta y devuelve el importe: el ofertante en cualquier momento, cualquiera una
+    /// vez caducada.
+    pub fn do_cancel_offer(who: T::AccountId, kitty_id: [u8; 32], offer_id: u32) -> DispatchResult {
+        let offer = Offers::<T>::get(kitty_id, offer_id).ok_or(Error::<T>::NoOffer)?;
+        let expired = frame_system::Pallet::<T>::block_number() >= offer.expires_at;
+        ensure!(who == offer.bidder || expired, Error::<T>::NotOwner);
+
+        // Con `BestEffort` no puede fallar: como mucho libera lo que quede retenido.
+        let _ = T::NativeBalance::release(
+            &HoldReason::KittyOffer.into(),
+            &offer.bidder,
+            offer.amount,
+            Precision::BestEffort,
+        );
+        Offers::<T>::remove(kitty_id, offer_id);
+
+        Self::deposit_event(Event::<T>::OfferCancelled { offer_id, kitty_id });
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: subastas
+    // -------------------------------------------------------------------------
+    // Subasta inglesa: cada puja tiene que superar a la anterior y queda retenida
+    // (`HoldReason::AuctionBid`) en la cuenta del pujador; al ser superada se libera. Desde el
+    // bloque `end` cualquiera puede liquidarla. Mientras dura, el kitty sigue con el vendedor pero
+    // bloqueado. Cada subasta se apunta en `AuctionsEndingAt` bajo su bloque final y
+    // `on_initialize` la liquida al llegar ese bloque; `settle_auction` queda como respaldo.
+
+    /// Subasta `kitty_id` durante `duration` bloques con una puja mínima de `min_bid` y, si se
+    /// indica, un precio de compra inmediata `buy_now`.
+    pub fn do_start_auction(
+        caller: T::AccountId,
+        kitty_id: [u8; 32],
+        min_bid: BalanceOf<T>,
+        duration: BlockNumberFor<T>,
+        buy_now: Option<BalanceOf<T>>,
+    ) -> DispatchResult {
+        let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        // Un operador o agente subasta en nombre del dueño, que es quien cobra.
+        Self::ensure_can_list(kitty_id, &kitty.owner, &caller)?;
+        let seller = kitty.owner.clone();
+        Self::ensure_can_trade(&seller)?;
+        Self::ensure_movable(kitty_id)?;
+        ensure!(
+            !duration.is_zero() && duration <= T::MaxAuctionDuration::get(),
+            Error::<T>::InvalidAuctionDuration
+        );
+        // La puja ganadora tiene que poder llegar a un vendedor sin saldo.
+        ensure!(min_bid >= T::NativeBalance::minimum_balance(), Error::<T>::BidTooLow);
+        ensure!(buy_now.is_none_or(|price| price >= min_bid), Error::<T>::BuyNowBelowMinBid);
+
+        Self::delist(kitty_id, &mut kitty);
+        Kitties::<T>::insert(kitty_id, kitty);
+
+        let auction_id = NextAuctionId::<T>::get();
+        NextAuctionId::<T>::put(auction_id.wrapping_add(1));
+        let end = frame_system::Pallet::<T>::block_number().saturating_add(duration);
+        AuctionsEndingAt::<T>::try_append(end, auction_id)
+            .map_err(|_| Error::<T>::TooManyAuctionsEnding)?;
+        Auctions::<T>::insert(
+            auction_id,
+            Auction { seller: seller.clone(), kitty_id, min_bid, buy_now, end, top_bid: None },
+        );
+        KittyAuction::<T>::insert(kitty_id, auction_id);
+        Self::set_status(kitty_id, KittyStatus::InAuction)?;
+
+        Self::deposit_event(Event::<T>::AuctionStarted {
+            auction_id,
+            kitty_id,
+            seller,
+            min_bid,
+            buy_now,
+            end,
+        });
+        Ok(())
+    }
+
+    /// Puja `amount` en `auction_id`, retiene el importe y libera la puja superada.
+    pub fn do_bid(bidder: T::AccountId, auction_id: u32, amount: BalanceOf<T>) -> DispatchResult {
+        Self::ensure_can_trade(&bidder)?;
+        let mut auction = Auctions::<T>::get(auction_id).ok_or(Error::<T>::NoAuction)?;
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(now < auction.end, Error::<T>::AuctionEnded);
+        ensure!(bidder != auction.seller, Error::<T>::TransferToSelf);
+        Self::ensure_can_trade(&auction.seller)?;
+        ensure!(amount >= auction.min_bid, Error::<T>::BidTooLow);
+        if let Some((_, top)) = &auction.top_bid {
+            ensure!(amount > *top, Error::<T>::BidTooLow);
+        }
+
+        // Llegar al precio de compra inmediata cierra la subasta en el acto, a ese precio.
+        if let Some(price) = auction.buy_now.filter(|price| amount >= *price) {
+            return Self::buy_out_auction(bidder, auction_id, auction, price);
+        }
+
+        // Una puja en los últimos `AuctionExtensionWindow` bloques alarga la subasta, para que
+        // los demás tengan tiempo de responder a una puja de última hora.
+        let extended = auction.end.saturating_sub(now) <= T::AuctionExtensionWindow::get();
+        if extended {
+            let end = auction.end.saturating_add(T::AuctionExtension::get());
+            AuctionsEndingAt::<T>::mutate(auction.end, |ending| ending.retain(|id| *id != auction_id));
+            AuctionsEndingAt::<T>::try_append(end, auction_id)
+                .map_err(|_| Error::<T>::TooManyAuctionsEnding)?;
+            auction.end = end;
+        }
+
+        let reason = HoldReason::AuctionBid.into();
+        T::NativeBalance::hold(&reason, &bidder, amount)?;
+        if let Some((previous, top)) = auction.top_bid.replace((bidder.clone(), amount)) {
+            let _ = T::NativeBalance::release(&reason, &previous, top, Precision::BestEffort);
+        }
+        let end = auction.end;
+        Auctions::<T>::insert(auction_id, auction);
+
+        Self::deposit_event(Event::<T>::BidPlaced { auction_id, bidder, amount });
+        if extended {
+            Self::deposit_event(Event::<T>::AuctionExtended { auction_id, end });
+        }
+        Ok(())
+    }
+
+    /// Compra inmediata: cobra `price` a `buyer`, le entrega el kitty y libera la puja más alta.
+    /// Si la entrega falla no cambia nada y la subasta sigue abierta.
+    fn buy_out_auction(
+        buyer: T::AccountId,
+        auction_id: u32,
+        auction: AuctionOf<T>,
+        price: BalanceOf<T>,
+    ) -> DispatchResult {
+        let reason = HoldReason::AuctionBid.into();
+        with_storage_layer(|| {
+            T::NativeBalance::hold(&reason, &buyer, price)?;
+            Auctions::<T>::remove(auction_id);
+            KittyAuction::<T>::remove(auction.kitty_id);
+            Self::clear_status(auction.kitty_id, KittyStatus::InAuction);
+            AuctionsEndingAt::<T>::mutate(auction.end, |ending| ending.retain(|id| *id != auction_id));
+            Self::do_transfer(auction.seller.clone(), buyer.clone(), auction.kitty_id)?;
+            Self::pay_sale(&auction.seller, auction.kitty_id, price, |to, amount| {
+                T::NativeBalance::transfer_on_hold(
+                    &reason,
+                    &buyer,
+                    to,
+                    amount,
+                    Precision::Exact,
+                    Restriction::Free,
+                    Fortitude::Polite,
+                )
+                .map(|_| ())
+            })
+        })?;
+        if let Some((previous, top)) = auction.top_bid {
+            let _ = T::NativeBalance::release(&reason, &previous, top, Precision::BestEffort);
+        }
+        Self::note_reputation(&auction.seller, ReputationEvent::Sale);
+
+        Self::deposit_event(Event::<T>::BoughtOut { auction_id, buyer, price });
+        Ok(())
+    }
+
+    /// Liquida una subasta terminada: el kitty pasa al mejor postor y la puja al vendedor. Sin
+    /// pujas, o si el ganador ya no puede recibir el kitty, este se queda con el vendedor y la
+    /// puja se libera.
+    pub fn do_settle_auction(auction_id: u32) -> DispatchResult {
+        let auction = Auctions::<T>::get(auction_id).ok_or(Error::<T>::NoAuction)?;
+        ensure!(frame_system::Pallet::<T>::block_number() >= auction.end, Error::<T>::AuctionNotEnded);
+        Auctions::<T>::remove(auction_id);
+        KittyAuction::<T>::remove(auction.kitty_id);
+        Self::clear_status(auction.kitty_id, KittyStatus::InAuction);
+
+        let reason = HoldReason::AuctionBid.into();
+        let mut sold = None;
+        if let Some((winner, price)) = auction.top_bid {
+            let completed = with_storage_layer(|| {
+                Self::do_transfer(auction.seller.clone(), winner.clone(), auction.kitty_id)?;
+                Self::pay_sale(&auction.seller, auction.kitty_id, price, |to, amount| {
+                    T::NativeBalance::transfer_on_hold(
+                        &reason,
+                        &winner,
+                        to,
+                        amount,
+                        Precision::Exact,
+                        Restriction::Free,
+                        Fortitude::Polite,
+                    )
+                    .map(|_| ())
+                })
+            })
+            .is_ok();
+            if completed {
+                Self::note_reputation(&auction.seller, ReputationEvent::Sale);
+                sold = Some((winner, price));
+            } else {
+                let _ = T::NativeBalance::release(&reason, &winner, price, Precision::BestEffort);
+            }
+        }
+
+        let (winner, price) = sold.unzip();
+        Self::deposit_event(Event::<T>::AuctionSettled { auction_id, winner, price });
+        Ok(())
+    }
+
+    /// Liquida las subastas que terminan en `now`. Las ya liquidadas a mano se ignoran.
+    pub(crate) fn settle_ending_auctions(now: BlockNumberFor<T>) -> Weight {
+        let ending = AuctionsEndingAt::<T>::take(now);
+        for auction_id in ending.iter() {
+            let _ = Self::do_settle_auction(*auction_id);
+        }
+        T::DbWeight::get()
+            .reads_writes(1, 1)
+            .saturating_add(T::WeightInfo::settle_auction().saturating_mul(ending.len() as u64))
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: ventas en otros activos
+    // -------------------------------------------------------------------------
+    // Igual que la venta en token nativo, pero el precio, la comisión y la regalía se pagan en
+    // un activo de `Config::Fungibles`.
+
+    /// Pone `kitty_id` a la venta por `amount` de `asset_id`.
+    pub fn do_set_price_in_asset(
+        caller: T::AccountId,
+        kitty_id: [u8; 32],
+        asset_id: AssetIdOf<T>,
+        amount: AssetBalanceOf<T>,
+    ) -> DispatchResult {
+        let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        Self::ensure_can_list(kitty_id, &kitty.owner, &caller)?;
+        let owner = kitty.owner.clone();
+        Self::ensure_can_trade(&owner)?;
+        Self::ensure_movable(kitty_id)?;
+        ensure!(T::Fungibles::asset_exists(asset_id.clone()), Error::<T>::UnknownAsset);
+
+        Self::hold_listing_deposit(&owner, kitty_id)?;
+        ReservedBuyers::<T>::remove(kitty_id);
+        if let Some(price) = kitty.price.take() {
+            Self::track_listing(kitty_id, Some(price), None);
+            Kitties::<T>::insert(kitty_id, kitty);
+        }
+        AssetListings::<T>::insert(kitty_id, (asset_id.clone(), amount));
+        Self::set_status(kitty_id, KittyStatus::Listed)?;
+
+        Self::deposit_event(Event::<T>::AssetPriceSet { owner, kitty_id, asset_id, amount });
+        Ok(())
+    }
+
+    /// Compra `kitty_id` pagando en `asset_id`, con la misma comisión y regalía que en token
+    /// nativo.
+    pub fn do_buy_kitty_with_asset(
+        buyer: T::AccountId,
+        kitty_id: [u8; 32],
+        asset_id: AssetIdOf<T>,
+        max_amount: AssetBalanceOf<T>,
+    ) -> DispatchResult {
+        Self::ensure_can_trade(&buyer)?;
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        let (listed_asset, price) = AssetListings::<T>::get(kitty_id).ok_or(Error::<T>::NotForSale)?;
+        ensure!(listed_asset == asset_id, Error::<T>::WrongAsset);
+        ensure!(max_amount >= price, Error::<T>::MaxPriceTooLow);
+        let seller = kitty.owner;
+        Self::ensure_can_trade(&seller)?;
+
+        let commission = T::SaleCommission::get() * price;
+        if !commission.is_zero() {
+            let destination = T::CommissionDestination::get().unwrap_or_else(Self::pot_account);
+            T::Fungibles::transfer(
+                asset_id.clone(),
+                &buyer,
+                &destination,
+                commission,
+                Preservation::Preserve,
+            )?;
+        }
+        let mut royalty = Zero::zero();
+        if let Some(minter) = KittyMinters::<T>::get(kitty_id).filter(|minter| *minter != seller) {
+            let amount = T::RoyaltyRate::get() * price;
+            let payable = !amount.is_zero() &&
+                T::Fungibles::can_deposit(asset_id.clone(), &minter, amount, Provenance::Extant) ==
+                    DepositConsequence::Success;
+            if payable {
+                T::Fungibles::transfer(asset_id.clone(), &buyer, &minter, amount, Preservation::Preserve)?;
+                royalty = amount;
+            }
+        }
+        let proceeds = price.saturating_sub(commission).saturating_sub(royalty);
+        T::Fungibles::transfer(asset_id.clone(), &buyer, &seller, proceeds, Preservation::Preserve)?;
+
+        Self::do_transfer(seller.clone(), buyer.clone(), kitty_id)?;
+        Self::note_reputation(&seller, ReputationEvent::Sale);
+
+        Self::deposit_event(Event::<T>::SoldForAsset {
+            buyer,
+            kitty_id,
+            asset_id,
+            price,
+            commission,
+            royalty,
+        });
+        Ok(())
+    }
+
+    /// Reparte `price` con `pay(destino, cantidad)`: `SaleCommission` va a `CommissionDestination`
+    /// (o al bote), la regalía a quien creó el kitty (ver `royalty_info`) y el resto a `seller`.
+    fn pay_sale(
+        seller: &T::AccountId,
+        kitty_id: [u8; 32],
+        price: BalanceOf<T>,
+        pay: impl Fn(&T::AccountId, BalanceOf<T>) -> DispatchResult,
+    ) -> DispatchResult {
+        let commission = T::SaleCommission::get() * price;
+        if !commission.is_zero() {
+            let destination = T::CommissionDestination::get().unwrap_or_else(Self::pot_account);
+            pay(&destination, commission)?;
+            Self::deposit_event(Event::<T>::CommissionCharged {
+                kitty_id,
+                seller: seller.clone(),
+                commission,
+            });
+        }
+        let mut royalty = Zero::zero();
+        if let Some((minter, amount)) = Self::royalty_info(kitty_id, price) {
+            // Una regalía que no llega al depósito existencial de una cuenta vacía no se puede
+            // pagar; en ese caso se la queda el vendedor.
+            let payable = minter != *seller &&
+                T::NativeBalance::can_deposit(&minter, amount, Provenance::Extant) ==
+                    DepositConsequence::Success;
+            if payable {
+                pay(&minter, amount)?;
+                Self::deposit_event(Event::<T>::RoyaltyPaid { kitty_id, minter, royalty: amount });
+                royalty = amount;
+            }
+        }
+        let proceeds = price.saturating_sub(commission).saturating_sub(royalty);
+        pay(seller, proceeds)?;
+
+        MarketStats::<T>::mutate(|stats| {
+            stats.total_volume.saturating_accrue(price);
+            stats.sales.saturating_inc();
+        });
+        Self::note_sale_price(kitty_id, price);
+        Self::index_history(kitty_id, KittyHistoryRecord::Sold { seller: seller.clone(), price });
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: historial en el índice off-chain
+    // -------------------------------------------------------------------------
+    // Con `OffchainIndexing` activo, cada mint, traspaso y venta en token nativo escribe una
+    // entrada con `sp_io::offchain_index`. No ocupa estado ni peso de lectura: solo lo guardan
+    // los nodos con la indexación activada. Las claves de un kitty comparten prefijo y ordenan
+    // por bloque y posición en el bloque, así que su historial se lee recorriendo el prefijo.
+
+    /// Prefijo de todas las entradas del historial de `kitty_id`.
+    pub fn history_prefix(kitty_id: [u8; 32]) -> Vec<u8> {
+        let mut key = b"kitties/history/".to_vec();
+        key.extend_from_slice(&kitty_id);
+        key
+    }
+
+    /// Clave de una entrada: el prefijo del kitty, el bloque y el número de eventos emitidos
+    /// hasta entonces en el bloque (en big-endian, para que ordenen), y el tipo de registro, que
+    /// distingue una venta del traspaso que la sigue.
+    pub fn history_key(kitty_id: [u8; 32], block: u64, event_index: u32, record: &KittyHistoryRecordOf<T>) -> Vec<u8> {
+        let kind: u8 = match record {
+            KittyHistoryRecord::Minted { .. } => 0,
+            KittyHistoryRecord::Transferred { .. } => 1,
+            KittyHistoryRecord::Sold { .. } => 2,
+        };
+        let mut key = Self::history_prefix(kitty_id);
+        key.extend_from_slice(&block.to_be_bytes());
+        key.extend_from_slice(&event_index.to_be_bytes());
+        key.push(kind);
+        key
+    }
+
+    /// Escribe `record` en el historial de `kitty_id`, si `OffchainIndexing` está activo.
+    fn index_history(kitty_id: [u8; 32], record: KittyHistoryRecordOf<T>) {
+        if !T::OffchainIndexing::get() {
+            return;
+        }
+        let block = frame_system::Pallet::<T>::block_number();
+        let key = Self::history_key(
+            kitty_id,
+            block.saturated_into(),
+            frame_system::Pallet::<T>::event_count(),
+            &record,
+        );
+        frame::deps::sp_io::offchain_index::set(&key, &(block, record).encode());
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: estadísticas del mercado
+    // -------------------------------------------------------------------------
+    // Volumen y número de ventas se acumulan en `pay_sale`, por donde pasan todas las ventas en
+    // token nativo (las ventas en otros activos no cuentan: no son comparables). Los anuncios
+    // activos se indexan en `ListingsByPrice` con el precio al principio de la clave (ver
+    // `listing_key`), así que el mapa se recorre del más barato al más caro y el suelo es su
+    // primera entrada.
+
+    /// Estadísticas acumuladas del mercado en token nativo.
+    pub fn market_stats() -> MarketStatsOf<T> {
+        MarketStats::<T>::get()
+    }
+
+    /// Precio del anuncio más barato en token nativo, si hay alguno.
+    pub fn floor_price() -> Option<BalanceOf<T>> {
+        FloorPrice::<T>::get()
+    }
+
+    /// Añade `price` al historial de `kitty_id`; con el historial lleno se olvida el más antiguo.
+    /// En un lote el precio apuntado es la parte que le corresponde a cada kitty.
+    fn note_sale_price(kitty_id: [u8; 32], price: BalanceOf<T>) {
+        PriceHistory::<T>::mutate(kitty_id, |history| {
+            if history.is_full() && !history.is_empty() {
+                history.remove(0);
+            }
+            let _ = history.try_push(price);
+        });
+    }
+
+    /// Primera clave de `price` en `ListingsByPrice`: el precio como `u128` en big-endian, que
+    /// ordena las claves igual que los precios.
+    pub fn listing_key(price: BalanceOf<T>) -> [u8; 16] {
+        let price: u128 = price.saturated_into();
+        price.to_be_bytes()
+    }
+
+    /// Mueve el anuncio de `kitty_id` de precio `old` a `new` (`None` = sin anunciar) y ajusta
+    /// el suelo.
+    pub(crate) fn track_listing(
+        kitty_id: [u8; 32],
+        old: Option<BalanceOf<T>>,
+        new: Option<BalanceOf<T>>,
+    ) {
+        if old == new {
+            return;
+        }
+        let mut floor = FloorPrice::<T>::get();
+        if let Some(old) = old {
+            ListingsByPrice::<T>::remove(Self::listing_key(old), kitty_id);
+            MarketStats::<T>::mutate(|stats| stats.listings.saturating_dec());
+        }
+        if let Some(new) = new {
+            ListingsByPrice::<T>::insert(Self::listing_key(new), kitty_id, new);
+            MarketStats::<T>::mutate(|stats| stats.listings.saturating_inc());
+        }
+        // Solo hace falta buscar de nuevo si se fue (o se encareció) un anuncio al precio suelo.
+        if old.is_some() && old == floor {
+            floor = ListingsByPrice::<T>::iter_values().next();
+        } else if let Some(new) = new {
+            if floor.is_none_or(|floor| new < floor) {
+                floor = Some(new);
+            }
+        }
+        FloorPrice::<T>::set(floor);
+    }
+
+    /// Hasta `limit` anuncios que cumplen `accept(kitty_id, precio)`, del más barato al más caro,
+    /// mirando solo los `scan` más baratos.
+    pub fn cheapest_listings(
+        limit: u32,
+        scan: u32,
+        accept: impl Fn(&[u8; 32], BalanceOf<T>) -> bool,
+    ) -> Vec<([u8; 32], BalanceOf<T>)> {
+        ListingsByPrice::<T>::iter()
+            .take(scan as usize)
+            .filter(|(_, kitty_id, price)| accept(kitty_id, *price))
+            .take(limit as usize)
+            .map(|(_, kitty_id, price)| (kitty_id, price))
+            .collect()
+    }
+
+    /// Compra los `count` anuncios más baratos a `max_price` o menos cada uno, saltando los
+    /// propios, los de vendedores que ya no pueden operar y las ventas reservadas a otro
+    /// comprador. Para que el peso no dependa de cuántos
+    /// se saltan, solo mira los `2 * count` más baratos; falla entero si entre ellos no hay
+    /// `count` que comprar.
+    pub fn do_sweep_floor(buyer: T::AccountId, count: u32, max_price: BalanceOf<T>) -> DispatchResult {
+        ensure!(count > 0 && count <= T::MaxSweep::get(), Error::<T>::InvalidSweep);
+        let targets = Self::cheapest_listings(count, count.saturating_mul(2), |kitty_id, price| {
+            price <= max_price &&
+                Kitties::<T>::get(kitty_id)
+                    .is_some_and(|kitty| kitty.owner != buyer && Self::ensure_can_trade(&kitty.owner).is_ok()) &&
+                ReservedBuyers::<T>::get(kitty_id).is_none_or(|reserved| reserved == buyer)
+        });
+        ensure!(targets.len() == count as usize, Error::<T>::NotEnoughListings);
+
+        let mut total: BalanceOf<T> = Zero::zero();
+        for (kitty_id, price) in targets.iter() {
+            Self::do_buy_kitty(buyer.clone(), *kitty_id, *price)?;
+            total.saturating_accrue(*price);
+        }
+
+        let kitty_ids = targets.into_iter().map(|(kitty_id, _)| kitty_id).collect();
+        Self::deposit_event(Event::<T>::FloorSwept { buyer, kitty_ids, total });
+        Ok(())
+    }
+
+    /// Regalía que corresponde a una venta de `kitty_id` por `price`: a quién y cuánto. Los
+    /// mercados externos pueden usarla para respetar las mismas regalías.
+    pub fn royalty_info(kitty_id: [u8; 32], price: BalanceOf<T>) -> Option<(T::AccountId, BalanceOf<T>)> {
+        let royalty = T::RoyaltyRate::get() * price;
+        if royalty.is_zero() {
+            return None;
+        }
+        KittyMinters::<T>::get(kitty_id).map(|minter| (minter, royalty))
+    }
+
+    // -------------------------------------------------------------------------
+    //  Función: do_set_name()
+    // -------------------------------------------------------------------------
+    // Pone nombre a un kitty. Los nombres son únicos, así que para que no se acaparen los cortos
+    // (los más deseados) la tasa crece cuanto más corto es el nombre y se duplica con cada
+    // renombre que la cuenta hace dentro de la misma era. Lo cobrado va al bote del pallet.
+    pub fn do_set_name(
+        caller: T::AccountId,  // Quien realiza la llamada
+        kitty_id: [u8; 32],    // ID del kitty
+        name: KittyName<T>,    // Nuevo nombre
+    ) -> DispatchResult {
+        ensure!(!name.is_empty(), Error::<T>::EmptyName);
+
+        // Solo el dueño puede nombrar su kitty.
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.owner == caller, Error::<T>::NotOwner);
+
+        // El nombre no puede estar en uso por otro kitty.
+        ensure!(!KittyByName::<T>::contains_key(&name), Error::<T>::NameTaken);
+
+        // Cuenta los renombres de la era actual (si cambió la era, empieza de cero).
+        let era = Self::current_rename_era();
+        let (last_era, renames) = RenamesInEra::<T>::get(&caller);
+        let renames = if last_era == era { renames } else { 0 };
+
+        // Cobra la tasa y la envía al bote.
+        let fee = Self::name_fee(name.len() as u32, renames);
+        if !fee.is_zero() {
+            T::NativeBalance::transfer(&caller, &Self::pot_account(), fee, Preservation::Preserve)?;
+        }
+
+        // Libera el nombre anterior y reserva el nuevo.
+        if let Some(old_name) = KittyNames::<T>::get(kitty_id) {
+            KittyByName::<T>::remove(old_name);
+        }
+        KittyByName::<T>::insert(&name, kitty_id);
+        KittyNames::<T>::insert(kitty_id, &name);
+        RenamesInEra::<T>::insert(&caller, (era, renames.saturating_add(1)));
+
+        Self::deposit_event(Event::<T>::NameSet { kitty_id, name, fee });
+
+        Ok(())
+    }
+
+    /// Tasa por un nombre de `len` bytes cuando la cuenta ya hizo `renames` renombres en la era:
+    /// `NameFee * (MaxNameLength + 1 - len) * 2^renames`, saturando en vez de desbordar.
+    pub fn name_fee(len: u32, renames: u32) -> BalanceOf<T> {
+        let shortness = T::MaxNameLength::get().saturating_add(1).saturating_sub(len).max(1);
+        let escalation = BalanceOf::<T>::from(2u32).saturating_pow(renames as usize);
+        T::NameFee::get()
+            .saturating_mul(BalanceOf::<T>::from(shortness))
+            .saturating_mul(escalation)
+    }
+
+    /// Era de renombres actual: número de bloque dividido por `RenameEra`.
+    fn current_rename_era() -> BlockNumberFor<T> {
+        let era_length = T::RenameEra::get().max(One::one());
+        frame_system::Pallet::<T>::block_number() / era_length
+    }
+
+    /// Cuenta del bote del pallet, derivada de `PalletId`.
+    pub fn pot_account() -> T::AccountId {
+        T::PalletId::get().into_account_truncating()
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: do_buy_voucher() / do_redeem_voucher()
+    // -------------------------------------------------------------------------
+    // Vales regalo: permiten regalar un mint sin conocer de antemano la cuenta del destinatario.
+    // Quien compra guarda solo `hash(código)`; quien recibe el código lo presenta para canjearlo.
+
+    /// Cobra `VoucherPrice` (va al bote del pallet) y registra el vale bajo `code_hash`.
+    pub fn do_buy_voucher(buyer: T::AccountId, code_hash: [u8; 32]) -> DispatchResult {
+        ensure!(!Vouchers::<T>::contains_key(code_hash), Error::<T>::VoucherExists);
+
+        let price = T::VoucherPrice::get();
+        if !price.is_zero() {
+            T::NativeBalance::transfer(&buyer, &Self::pot_account(), price, Preservation::Preserve)?;
+        }
+
+        Vouchers::<T>::insert(code_hash, &buyer);
+        Self::deposit_event(Event::<T>::VoucherPurchased { buyer, code_hash });
+
+        Ok(())
+    }
+
+    /// Canjea el vale cuyo hash coincide con `hash(code)` y crea un kitty para `who`.
+    pub fn do_redeem_voucher(who: T::AccountId, code: [u8; 32]) -> DispatchResult {
+        let code_hash = Self::voucher_code_hash(&code);
+
+        // El vale se consume antes del mint; si el mint falla la llamada entera se revierte.
+        Vouchers::<T>::take(code_hash).ok_or(Error::<T>::NoVoucher)?;
+
+        let kitty_id = Self::gen_dna(&who);
+        Self::mint(who.clone(), kitty_id)?;
+
+        Self::deposit_event(Event::<T>::VoucherRedeemed { code_hash, who, kitty_id });
+
+        Ok(())
+    }
+
+    /// Hash con el que se registra un código de vale (lo que se pasa a `buy_voucher`).
+    pub fn voucher_code_hash(code: &[u8; 32]) -> [u8; 32] {
+        BlakeTwo256::hash(code).into()
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: do_buy_mint_pass() / use_mint_pass() / pay_for_mint()
+    // -------------------------------------------------------------------------
+    // Pase de temporada: se vende acceso a los mints por adelantado. El pase guarda cuántos
+    // mints quedan y hasta qué bloque vale. Sin pase, cada mint paga `MintFee`.
+
+    /// Cubre un mint de `who`: con su pase si tiene uno vigente o, si no, cobrando el precio de
+    /// la curva (si está activa) o `MintFee`, que va a `MintFeeDestination` o se quema.
+    pub fn pay_for_mint(who: &T::AccountId) -> DispatchResult {
+        if Self::use_mint_pass(who) {
+            return Ok(());
+        }
+        if !T::CurveBasePrice::get().is_zero() {
+            return Self::pay_curve_price(who);
+        }
+        let fee = T::MintFee::get();
+        if fee.is_zero() {
+            return Ok(());
+        }
+        let spendable =
+            T::NativeBalance::reducible_balance(who, Preservation::Preserve, Fortitude::Polite);
+        ensure!(spendable >= fee, Error::<T>::InsufficientMintFunds);
+        match T::MintFeeDestination::get() {
+            Some(destination) => {
+                T::NativeBalance::transfer(who, &destination, fee, Preservation::Preserve)?;
+            },
+            None => {
+                T::NativeBalance::burn_from(
+                    who,
+                    fee,
+                    Preservation::Preserve,
+                    Precision::Exact,
+                    Fortitude::Polite,
+                )?;
+            },
+        }
+        Self::deposit_event(Event::<T>::MintFeePaid { who: who.clone(), fee });
+        Ok(())
+    }
+
+    /// Periodo de cuota de mint en curso: bloque actual / `MintQuotaPeriod`.
+    fn current_mint_period() -> BlockNumberFor<T> {
+        let period_length = T::MintQuotaPeriod::get().max(One::one());
+        frame_system::Pallet::<T>::block_number() / period_length
+    }
+
+    /// Gasta un mint de la cuota de `who` en el periodo actual (`MintQuota == 0` = sin límite).
+    /// Al empezar un periodo nuevo se borra la entrada del anterior, así que cada cuenta tiene
+    /// como mucho una.
+    pub fn use_mint_quota(who: &T::AccountId) -> DispatchResult {
+        let quota = T::MintQuota::get();
+        if quota == 0 {
+            return Ok(());
+        }
+        let period = Self::current_mint_period();
+        let minted = MintsInPeriod::<T>::get(who, period);
+        ensure!(minted < quota, Error::<T>::MintQuotaExceeded);
+        if minted == 0 {
+            let _ = MintsInPeriod::<T>::clear_prefix(who, 1, None);
+        }
+        MintsInPeriod::<T>::insert(who, period, minted.saturating_add(1));
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: curva de precio del mint
+    // -------------------------------------------------------------------------
+    // Con `CurveBasePrice` distinto de cero el mint no cobra `MintFee` sino un precio que se
+    // duplica cada `CurveDoublingInterval` kitties existentes. Lo cobrado se reparte entre la
+    // reserva de la curva (`CurveReserveShare`) y la tesorería (`MintProceedsTreasury` o el bote).
+
+    /// Cuenta de la reserva de la curva de mint.
+    pub fn curve_reserve_account() -> T::AccountId {
+        T::PalletId::get().into_sub_account_truncating(b"curve")
+    }
+
+    /// Precio en la curva con `supply` kitties existentes:
+    /// `CurveBasePrice * 2^(supply / CurveDoublingInterval)`.
+    pub fn curve_price(supply: u32) -> BalanceOf<T> {
+        let doublings = supply / T::CurveDoublingInterval::get().max(1);
+        let multiplier: BalanceOf<T> = 2u128.saturating_pow(doublings).saturated_into();
+        T::CurveBasePrice::get().saturating_mul(multiplier)
+    }
+
+    /// Lo que cuesta ahora crear un kitty sin pase.
+    pub fn current_mint_price() -> BalanceOf<T> {
+        if T::CurveBasePrice::get().is_zero() {
+            T::MintFee::get()
+        } else {
+            Self::curve_price(CountForKitties::<T>::get())
+        }
+    }
+
+    /// Cobra a `who` el precio actual de la curva y lo reparte entre reserva y tesorería.
+    fn pay_curve_price(who: &T::AccountId) -> DispatchResult {
+        let price = Self::curve_price(CountForKitties::<T>::get());
+        let spendable =
+            T::NativeBalance::reducible_balance(who, Preservation::Preserve, Fortitude::Polite);
+        ensure!(spendable >= price, Error::<T>::InsufficientMintFunds);
+
+        let reserve = T::CurveReserveShare::get() * price;
+        let treasury = price.saturating_sub(reserve);
+        if !reserve.is_zero() {
+            T::NativeBalance::transfer(who, &Self::curve_reserve_account(), reserve, Preservation::Preserve)?;
+        }
+        if !treasury.is_zero() {
+            let destination = T::MintProceedsTreasury::get().unwrap_or_else(Self::pot_account);
+            T::NativeBalance::transfer(who, &destination, treasury, Preservation::Preserve)?;
+        }
+
+        Self::deposit_event(Event::<T>::MintPricePaid { who: who.clone(), price, reserve, treasury });
+        Ok(())
+    }
+
+    /// Cobra `MintPassPrice` (va al bote) y da a `who` un pase nuevo.
+    pub fn do_buy_mint_pass(who: T::AccountId) -> DispatchResult {
+        // No se pisa un pase que todavía tiene mints por usar.
+        ensure!(Self::active_mint_pass(&who).is_none(), Error::<T>::MintPassActive);
+
+        let price = T::MintPassPrice::get();
+        if !price.is_zero() {
+            T::NativeBalance::transfer(&who, &Self::pot_account(), price, Preservation::Preserve)?;
+        }
+
+        let now = frame_system::Pallet::<T>::block_number();
+        let pass = MintPass {
+            remaining: T::MintPassMints::get(),
+            expires_at: now.saturating_add(T::MintPassDuration::get()),
+        };
+        MintPasses::<T>::insert(&who, &pass);
+
+        Self::deposit_event(Event::<T>::MintPassPurchased {
+            who,
+            mints: pass.remaining,
+            expires_at: pass.expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// Pase de `who` si sigue vigente y le quedan mints.
+    pub fn active_mint_pass(who: &T::AccountId) -> Option<MintPass<BlockNumberFor<T>>> {
+        let now = frame_system::Pallet::<T>::block_number();
+        MintPasses::<T>::get(who).filter(|pass| pass.remaining > 0 && now <= pass.expires_at)
+    }
+
+    /// Consume un mint del pase de `who`. Devuelve `true` si el mint queda cubierto por el pase.
+    /// Los pases vencidos o agotados se borran al pasar por aquí.
+    pub fn use_mint_pass(who: &T::AccountId) -> bool {
+        let Some(mut pass) = MintPasses::<T>::take(who) else { return false };
+        let now = frame_system::Pallet::<T>::block_number();
+        if pass.remaining == 0 || now > pass.expires_at {
+            return false;
+        }
+
+        pass.remaining.saturating_dec();
+        if pass.remaining > 0 {
+            MintPasses::<T>::insert(who, &pass);
+        }
+
+        Self::deposit_event(Event::<T>::MintPassUsed { who: who.clone(), remaining: pass.remaining });
+        true
+    }
+
+    // -------------------------------------------------------------------------
+    //  Función: do_breed()
+    // -------------------------------------------------------------------------
+    /// Cruza `parent_1` y `parent_2` para `who`. Cada progenitor debe ser de `who` o estar
+    /// prestado a `who` para criar; en ese caso se paga y consume el préstamo.
+    /// La cría queda en gestación durante `GestationPeriod` bloques; su genoma no se decide
+    /// hasta `do_claim_birth`, así que no se puede prever al criar.
+    pub fn do_breed(who: T::AccountId, parent_1: [u8; 32], parent_2: [u8; 32]) -> DispatchResult {
+        ensure!(parent_1 != parent_2, Error::<T>::SameParent);
+        let kitty_1 = Kitties::<T>::get(parent_1).ok_or(Error::<T>::NoKitty)?;
+        let kitty_2 = Kitties::<T>::get(parent_2).ok_or(Error::<T>::NoKitty)?;
+        Self::ensure_can_pair((parent_1, &kitty_1), (parent_2, &kitty_2))?;
+
+        for (parent_id, parent) in [(parent_1, &kitty_1), (parent_2, &kitty_2)] {
+            if Self::kitty_user(parent_id, parent) != who {
+                ensure!(
+                    BreedingLoans::<T>::get(parent_id).is_some_and(|loan| loan.borrower == who),
+                    Error::<T>::NoBreedingRights
+                );
+                Self::consume_breeding_loan(&who, parent_id)?;
+            }
+        }
+
+        Self::begin_gestation(who, (parent_1, kitty_1), (parent_2, kitty_2))
+    }
+
+    /// Comprueba que dos kitties pueden criar juntos ahora mismo: sexos opuestos, sin
+    /// parentesco cercano y ambos recuperados de su última cría.
+    fn ensure_can_pair(
+        (id_1, kitty_1): ([u8; 32], &Kitty<T>),
+        (id_2, kitty_2): ([u8; 32], &Kitty<T>),
+    ) -> DispatchResult {
+        ensure!(
+            Self::gender_of(&kitty_1.dna) != Self::gender_of(&kitty_2.dna),
+            Error::<T>::SameGender
+        );
+        ensure!(!Self::closely_related(id_1, id_2), Error::<T>::TooCloselyRelated);
+        ensure!(Self::kitty_status(id_1) != KittyStatus::Locked, Error::<T>::KittyLocked);
+        ensure!(Self::kitty_status(id_2) != KittyStatus::Locked, Error::<T>::KittyLocked);
+        Self::ensure_rested(kitty_1)?;
+        Self::ensure_rested(kitty_2)
+    }
+
+    /// `true` si, mirando `IncestDepth` generaciones atrás, un kitty es ancestro del otro o
+    /// ambos comparten algún ancestro (hermanos, medio hermanos, primos...).
+    pub fn closely_related(kitty_1: [u8; 32], kitty_2: [u8; 32]) -> bool {
+        let depth = T::IncestDepth::get();
+        if depth == 0 {
+            return false;
+        }
+        let ancestors_1 = Self::ancestors(kitty_1, depth);
+        let ancestors_2 = Self::ancestors(kitty_2, depth);
+        ancestors_1.contains(&kitty_2) ||
+            ancestors_2.contains(&kitty_1) ||
+            ancestors_1.iter().any(|ancestor| ancestors_2.contains(ancestor))
+    }
+
+    /// Cobra la tarifa de cría del protocolo a `who`: se transfiere a `BreedingFeeDestination`
+    /// o, si no hay destino, se quema.
+    fn charge_breeding_fee(who: &T::AccountId) -> DispatchResult {
+        let fee = T::BreedingFee::get();
+        if fee.is_zero() {
+            return Ok(());
+        }
+        match T::BreedingFeeDestination::get() {
+            Some(destination) => {
+                T::NativeBalance::transfer(who, &destination, fee, Preservation::Preserve)?;
+            },
+            None => {
+                T::NativeBalance::burn_from(
+                    who,
+                    fee,
+                    Preservation::Preserve,
+                    Precision::Exact,
+                    Fortitude::Polite,
+                )?;
+            },
+        }
+        Self::deposit_event(Event::<T>::BreedingFeePaid { who: who.clone(), fee });
+        Ok(())
+    }
+
+    /// Cobra la tarifa de cría, pone en enfriamiento a los progenitores (ya validados) y guarda
+    /// la cría en gestación.
+    fn begin_gestation(
+        who: T::AccountId,
+        (parent_1, kitty_1): ([u8; 32], Kitty<T>),
+        (parent_2, kitty_2): ([u8; 32], Kitty<T>),
+    ) -> DispatchResult {
+        let generation = Self::child_generation(kitty_1.generation.max(kitty_2.generation))?;
+        Self::charge_breeding_fee(&who)?;
+        Self::start_cooldown(parent_1);
+        Self::start_cooldown(parent_2);
+        // Solo los progenitores libres pasan a `Breeding`; un kitty alquilado, p. ej., sigue
+        // alquilado mientras gesta.
+        for parent in [parent_1, parent_2] {
+            if Self::kitty_status(parent) == KittyStatus::Idle {
+                KittyStatuses::<T>::insert(parent, KittyStatus::Breeding);
+            }
+        }
+
+        let pending_id = NextPendingBirthId::<T>::get();
+        NextPendingBirthId::<T>::put(pending_id.wrapping_add(1));
+        let due_at =
+            frame_system::Pallet::<T>::block_number().saturating_add(T::GestationPeriod::get());
+        PendingBirths::<T>::insert(
+            pending_id,
+            PendingBirth {
+                owner: who.clone(),
+                parents: (parent_1, parent_2),
+                parent_dna: (kitty_1.dna, kitty_2.dna),
+                generation,
+                due_at,
+            },
+        );
+
+        Self::deposit_event(Event::<T>::BreedingStarted {
+            owner: who,
+            pending_id,
+            parent_1,
+            parent_2,
+            due_at,
+        });
+
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Función: do_claim_birth()
+    // -------------------------------------------------------------------------
+    /// Hace nacer la cría `pending_id` de `who` una vez cumplida la gestación. El identificador
+    /// y el genoma salen de la aleatoriedad del bloque en que se reclama.
+    pub fn do_claim_birth(who: T::AccountId, pending_id: u32) -> DispatchResult {
+        let birth = PendingBirths::<T>::get(pending_id).ok_or(Error::<T>::NoPendingBirth)?;
+        ensure!(birth.owner == who, Error::<T>::NotOwner);
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(now >= birth.due_at, Error::<T>::GestationNotComplete);
+
+        let (parent_1, parent_2) = birth.parents;
+        let child_id = Self::gen_dna(&who);
+        let (dna, mutated) = Self::inherit_dna_with_mutations(
+            &birth.parent_dna.0,
+            &birth.parent_dna.1,
+            &child_id,
+            T::MutationChance::get(),
+        );
+        Self::mint_with_dna(who.clone(), child_id, dna, birth.generation, Some(birth.parents))?;
+        PendingBirths::<T>::remove(pending_id);
+        Self::clear_status(parent_1, KittyStatus::Breeding);
+        Self::clear_status(parent_2, KittyStatus::Breeding);
+
+        Self::deposit_event(Event::<T>::Bred { owner: who, parent_1, parent_2, child_id });
+        Self::note_mutations(child_id, mutated);
+
+        Ok(())
+    }
+
+    /// Emite `Mutated` si la cría `kitty_id` tuvo alguna mutación.
+    fn note_mutations(kitty_id: [u8; 32], genes: Vec<u8>) {
+        if !genes.is_empty() {
+            Self::deposit_event(Event::<T>::Mutated { kitty_id, genes });
+        }
+    }
+
+    /// Ancestros de `kitty_id` hasta `depth` generaciones atrás (1 = solo los progenitores),
+    /// generación a generación y sin repetidos. Los ancestros que no están en este pallet (p. ej.
+    /// parejas remotas) aparecen, pero su linaje no se puede seguir.
+    pub fn ancestors(kitty_id: [u8; 32], depth: u32) -> Vec<[u8; 32]> {
+        let mut ancestors: Vec<[u8; 32]> = Vec::new();
+        let mut current: Vec<[u8; 32]> = Vec::from([kitty_id]);
+        for _ in 0..depth {
+            let mut next: Vec<[u8; 32]> = Vec::new();
+            for id in current {
+                let Some((parent_1, parent_2)) = Kitties::<T>::get(id).and_then(|k| k.parents)
+                else {
+                    continue;
+                };
+                for parent in [parent_1, parent_2] {
+                    if !ancestors.contains(&parent) {
+                        ancestors.push(parent);
+                        next.push(parent);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            current = next;
+        }
+        ancestors
+    }
+
+    /// Generación de la cría de unos progenitores cuya generación mayor es `parents`.
+    fn child_generation(parents: u32) -> Result<u32, DispatchError> {
+        let generation = parents.checked_add(1).ok_or(Error::<T>::MaxGenerationReached)?;
+        if let Some(max) = T::MaxGeneration::get() {
+            ensure!(generation <= max, Error::<T>::MaxGenerationReached);
+        }
+        Ok(generation)
+    }
+
+    /// Sexo codificado en el genoma: el bit bajo de su primer byte (1 = macho).
+    pub fn gender_of(dna: &[u8]) -> Gender {
+        match dna.first() {
+            Some(gene) if gene & 1 == 1 => Gender::Male,
+            _ => Gender::Female,
+        }
+    }
+
+    /// Falla con `BreedingCooldownActive` si `kitty` no puede criar todavía.
+    fn ensure_rested(kitty: &Kitty<T>) -> DispatchResult {
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(now >= kitty.cooldown_until, Error::<T>::BreedingCooldownActive);
+        Ok(())
+    }
+
+    /// Tras criar, `kitty_id` no puede volver a ser progenitor hasta pasado `BreedingCooldown`.
+    fn start_cooldown(kitty_id: [u8; 32]) {
+        let until =
+            frame_system::Pallet::<T>::block_number().saturating_add(T::BreedingCooldown::get());
+        Kitties::<T>::mutate(kitty_id, |maybe_kitty| {
+            if let Some(kitty) = maybe_kitty {
+                kitty.cooldown_until = until;
+            }
+        });
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: mercado de sementales
+    // -------------------------------------------------------------------------
+    // Un dueño puede abrir su kitty a la cría pública por una tarifa. Cualquiera lo usa como
+    // progenitor con `breed_with` pagando la tarifa al dueño, que conserva el kitty. A diferencia
+    // de un préstamo de cría, el anuncio no se gasta: sigue activo hasta que se retira o el kitty
+    // cambia de dueño.
+
+    /// Anuncia (`Some`) o retira (`None`) `kitty_id` como semental con esa tarifa.
+    pub fn do_set_breeding_price(
+        owner: T::AccountId,
+        kitty_id: [u8; 32],
+        price: Option<BalanceOf<T>>,
+    ) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.owner == owner, Error::<T>::NotOwner);
+        match price {
+            Some(price) => StudListings::<T>::insert(kitty_id, price),
+            None => StudListings::<T>::remove(kitty_id),
+        }
+        Self::deposit_event(Event::<T>::StudListed { kitty_id, price });
+        Ok(())
+    }
+
+    /// Cruza `my_kitty` (de `who`) con el semental anunciado `stud`, pagando su tarifa.
+    pub fn do_breed_with(who: T::AccountId, my_kitty: [u8; 32], stud: [u8; 32]) -> DispatchResult {
+        ensure!(my_kitty != stud, Error::<T>::SameParent);
+        let fee = StudListings::<T>::get(stud).ok_or(Error::<T>::NotStud)?;
+        let mine = Kitties::<T>::get(my_kitty).ok_or(Error::<T>::NoKitty)?;
+        ensure!(Self::kitty_user(my_kitty, &mine) == who, Error::<T>::NotOwner);
+        let stud_kitty = Kitties::<T>::get(stud).ok_or(Error::<T>::NoKitty)?;
+        Self::ensure_can_pair((my_kitty, &mine), (stud, &stud_kitty))?;
+
+        if stud_kitty.owner != who && !fee.is_zero() {
+            T::NativeBalance::transfer(&who, &stud_kitty.owner, fee, Preservation::Preserve)?;
+        }
+        Self::deposit_event(Event::<T>::StudUsed { stud, breeder: who.clone(), fee });
+
+        Self::begin_gestation(who, (my_kitty, mine), (stud, stud_kitty))
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: préstamos de cría
+    // -------------------------------------------------------------------------
+    // Un dueño puede prestar su kitty para una única cría. El prestatario paga la tasa al dueño
+    // en el mismo momento en que cría (`consume_breeding_loan`), nunca antes.
+
+    /// Registra (o reemplaza) el préstamo de cría de `kitty_id`.
+    pub fn do_grant_breeding_loan(
+        owner: T::AccountId,
+        kitty_id: [u8; 32],
+        borrower: T::AccountId,
+        fee: BalanceOf<T>,
+        expires_at: BlockNumberFor<T>,
+    ) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.owner == owner, Error::<T>::NotOwner);
+        ensure!(borrower != owner, Error::<T>::LoanToSelf);
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(expires_at >= now, Error::<T>::ExpiryInPast);
+
+        BreedingLoans::<T>::insert(
+            kitty_id,
+            BreedingLoan { borrower: borrower.clone(), fee, expires_at },
+        );
+        Self::deposit_event(Event::<T>::BreedingLoanGranted { kitty_id, borrower, fee, expires_at });
+
+        Ok(())
+    }
+
+    /// Borra el préstamo de cría de `kitty_id` (solo su dueño).
+    pub fn do_revoke_breeding_loan(owner: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.owner == owner, Error::<T>::NotOwner);
+        BreedingLoans::<T>::take(kitty_id).ok_or(Error::<T>::NoBreedingLoan)?;
+        Self::deposit_event(Event::<T>::BreedingLoanRevoked { kitty_id });
+        Ok(())
+    }
+
+    /// Consume el préstamo de cría de `kitty_id` a favor de `borrower`: comprueba que sea suyo y
+    /// esté vigente, paga la tasa al dueño y lo borra. Pensado para llamarse dentro de la cría,
+    /// de modo que pago y cría ocurren (o fallan) juntos.
+    pub fn consume_breeding_loan(borrower: &T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let loan = BreedingLoans::<T>::get(kitty_id)
+            .filter(|loan| &loan.borrower == borrower)
+            .ok_or(Error::<T>::NoBreedingLoan)?;
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(now <= loan.expires_at, Error::<T>::BreedingLoanExpired);
+
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        if !loan.fee.is_zero() {
+            T::NativeBalance::transfer(borrower, &kitty.owner, loan.fee, Preservation::Preserve)?;
+        }
+
+        BreedingLoans::<T>::remove(kitty_id);
+        Self::deposit_event(Event::<T>::BreedingLoanUsed {
+            kitty_id,
+            borrower: borrower.clone(),
+            fee: loan.fee,
+        });
+
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: seguro de kitties
+    // -------------------------------------------------------------------------
+    // Los dueños pagan primas periódicas a un fondo (subcuenta del pallet). Si pierden el kitty
+    // sin culpa propia (gobernanza lo quema o lo confisca), presentan un reclamo y el `ArbiterOrigin` decide
+    // si se paga `InsurancePayout` desde el fondo.
+
+    /// Cuenta del fondo del seguro, separada del bote general del pallet.
+    pub fn insurance_pot_account() -> T::AccountId {
+        T::PalletId::get().into_sub_account_truncating(b"insurance")
+    }
+
+    /// Cobra `periods` primas y extiende la cobertura de `kitty_id` desde donde termine.
+    pub fn do_pay_premium(holder: T::AccountId, kitty_id: [u8; 32], periods: u32) -> DispatchResult {
+        ensure!(periods > 0, Error::<T>::ZeroPeriods);
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.owner == holder, Error::<T>::NotOwner);
+
+        let amount = T::InsurancePremium::get().saturating_mul(BalanceOf::<T>::from(periods));
+        T::NativeBalance::transfer(
+            &holder,
+            &Self::insurance_pot_account(),
+            amount,
+            Preservation::Preserve,
+        )?;
+
+        // La cobertura nueva empieza al final de la actual, o ahora si ya había vencido.
+        let now = frame_system::Pallet::<T>::block_number();
+        let start = InsurancePolicies::<T>::get(kitty_id)
+            .map(|policy| policy.paid_until.max(now))
+            .unwrap_or(now);
+        let extension = T::InsurancePeriod::get().saturating_mul(periods.into());
+        let paid_until = start.saturating_add(extension);
+        InsurancePolicies::<T>::insert(
+            kitty_id,
+            InsurancePolicy { holder: holder.clone(), paid_until },
+        );
+
+        Self::deposit_event(Event::<T>::PremiumPaid { kitty_id, holder, amount, paid_until });
+
+        Ok(())
+    }
+
+    /// Guarda la pérdida de `kitty_id` (quemado o confiscado por gobernanza) si tenía póliza,
+    /// para que el asegurado la reclame. Una pérdida anterior sin reclamar del mismo identificador
+    /// se sustituye.
+    fn record_insured_loss(
+        kitty_id: [u8; 32],
+        policy: Option<InsurancePolicy<T::AccountId, BlockNumberFor<T>>>,
+    ) {
+        let Some(InsurancePolicy { holder, paid_until }) = policy else {
+            return;
+        };
+        let lost_at = frame_system::Pallet::<T>::block_number();
+        InsuredLosses::<T>::insert(kitty_id, InsuredLoss { holder, paid_until, lost_at });
+    }
+
+    /// Registra un reclamo de `claimant` sobre `kitty_id`. El kitty tiene que haberlo quemado o
+    /// confiscado gobernanza mientras la póliza de `claimant` lo cubría; la pérdida se consume con el reclamo.
+    pub fn do_file_claim(claimant: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        ensure!(!InsuranceClaims::<T>::contains_key(kitty_id), Error::<T>::ClaimExists);
+        let loss = InsuredLosses::<T>::get(kitty_id)
+            .filter(|loss| loss.holder == claimant)
+            .ok_or(Error::<T>::NoPolicy)?;
+        ensure!(loss.lost_at <= loss.paid_until, Error::<T>::PolicyLapsed);
+
+        let now = frame_system::Pallet::<T>::block_number();
+        InsuredLosses::<T>::remove(kitty_id);
+        InsuranceClaims::<T>::insert(
+            kitty_id,
+            InsuranceClaim { claimant: claimant.clone(), filed_at: now, status: ClaimStatus::Pending },
+        );
+        Self::deposit_event(Event::<T>::ClaimFiled { kitty_id, claimant });
+
+        Ok(())
+    }
+
+    /// Resuelve un reclamo pendiente; si se aprueba, paga la indemnización desde el fondo.
+    pub fn do_resolve_claim(kitty_id: [u8; 32], approve: bool) -> DispatchResult {
+        let mut claim = InsuranceClaims::<T>::get(kitty_id).ok_or(Error::<T>::NoClaim)?;
+        ensure!(claim.status == ClaimStatus::Pending, Error::<T>::ClaimNotPending);
+
+        let payout = if approve { T::InsurancePayout::get() } else { Zero::zero() };
+        if !payout.is_zero() {
+            T::NativeBalance::transfer(
+                &Self::insurance_pot_account(),
+                &claim.claimant,
+                payout,
+                Preservation::Expendable,
+            )?;
+        }
+
+        claim.status = if approve { ClaimStatus::Approved } else { ClaimStatus::Rejected };
+        let claimant = claim.claimant.clone();
+        InsuranceClaims::<T>::insert(kitty_id, claim);
+
+        Self::deposit_event(Event::<T>::ClaimResolved { kitty_id, claimant, approved: approve, payout });
+
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: rifas
+    // -------------------------------------------------------------------------
+    // El dueño rifa su kitty vendiendo boletos. El dinero de cada rifa se guarda en su propia
+    // subcuenta del pallet hasta el cierre: si se vendieron suficientes boletos se sortea el
+    // ganador con la aleatoriedad del runtime y el vendedor cobra (menos la comisión); si no, se
+    // devuelve cada boleto.
+
+    /// Subcuenta que guarda el dinero de los boletos de la rifa de `kitty_id`.
+    pub fn raffle_account(kitty_id: &[u8; 32]) -> T::AccountId {
+        T::PalletId::get().into_sub_account_truncating((b"raffle", kitty_id))
+    }
+
+    /// Abre la rifa de `kitty_id`. El kitty sale de la venta directa mientras dure.
+    pub fn do_start_raffle(
+        caller: T::AccountId,
+        kitty_id: [u8; 32],
+        ticket_price: BalanceOf<T>,
+        max_tickets: u32,
+        close_block: BlockNumberFor<T>,
+    ) -> DispatchResult {
+        let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        // Un operador o agente rifa en nombre del dueño, que es quien cobra.
+        Self::ensure_can_list(kitty_id, &kitty.owner, &caller)?;
+        let seller = kitty.owner.clone();
+        Self::ensure_can_trade(&seller)?;
+        Self::ensure_movable(kitty_id)?;
+
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(
+            max_tickets > 0 && max_tickets <= T::MaxRaffleTickets::get() && close_block > now,
+            Error::<T>::InvalidRaffle
+        );
+        // Cada boleto tiene que poder abrir por sí solo la subcuenta de la rifa.
+        ensure!(ticket_price >= T::NativeBalance::minimum_balance(), Error::<T>::TicketPriceTooLow);
+
+        Self::delist(kitty_id, &mut kitty);
+        Kitties::<T>::insert(kitty_id, kitty);
+        Self::set_status(kitty_id, KittyStatus::InRaffle)?;
+        Raffles::<T>::insert(
+            kitty_id,
+            Raffle { seller: seller.clone(), ticket_price, max_tickets, close_block },
+        );
+
+        Self::deposit_event(Event::<T>::RaffleStarted {
+            kitty_id,
+            seller,
+            ticket_price,
+            max_tickets,
+            close_block,
+        });
+
+        Ok(())
+    }
+
+    /// Vende un boleto de la rifa de `kitty_id` a `buyer`.
+    pub fn do_buy_ticket(buyer: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        Self::ensure_can_trade(&buyer)?;
+        let raffle = Raffles::<T>::get(kitty_id).ok_or(Error::<T>::NoRaffle)?;
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(now < raffle.close_block, Error::<T>::RaffleClosed);
+        Self::ensure_can_trade(&raffle.seller)?;
+
+        let mut tickets = RaffleTickets::<T>::get(kitty_id);
+        ensure!((tickets.len() as u32) < raffle.max_tickets, Error::<T>::RaffleSoldOut);
+        tickets.try_push(buyer.clone()).map_err(|_| Error::<T>::RaffleSoldOut)?;
+
+        T::NativeBalance::transfer(
+            &buyer,
+            &Self::raffle_account(&kitty_id),
+            raffle.ticket_price,
+            Preservation::Preserve,
+        )?;
+
+        let tickets_sold = tickets.len() as u32;
+        RaffleTickets::<T>::insert(kitty_id, tickets);
+        Self::deposit_event(Event::<T>::TicketBought { kitty_id, buyer, tickets_sold });
+
+        Ok(())
+    }
+
+    /// Cierra la rifa de `kitty_id` una vez vencida o agotada.
+    pub fn do_close_raffle(kitty_id: [u8; 32]) -> DispatchResult {
+        let raffle = Raffles::<T>::get(kitty_id).ok_or(Error::<T>::NoRaffle)?;
+        let tickets = RaffleTickets::<T>::get(kitty_id);
+        let sold = tickets.len() as u32;
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(now >= raffle.close_block || sold >= raffle.max_tickets, Error::<T>::RaffleStillOpen);
+
+        // La rifa se cierra antes de mover el kitty, para que `do_transfer` lo permita.
+        Raffles::<T>::remove(kitty_id);
+        RaffleTickets::<T>::remove(kitty_id);
+        Self::clear_status(kitty_id, KittyStatus::InRaffle);
+
+        let escrow = Self::raffle_account(&kitty_id);
+        let min_sold = raffle.max_tickets.saturating_sub(
+            (Permill::one().saturating_sub(T::RaffleMinFill::get())) * raffle.max_tickets,
+        );
+        if sold > 0 && sold >= min_sold {
+            let winner = Self::draw_raffle_winner(&kitty_id, &tickets);
+            // Si el ganador no puede recibir el kitty (p. ej. ya tiene el máximo), se reembolsa.
+            let delivered = winner == raffle.seller ||
+                with_storage_layer(|| {
+                    Self::do_transfer(raffle.seller.clone(), winner.clone(), kitty_id)
+                })
+                .is_ok();
+            if delivered {
+                Self::note_reputation(&raffle.seller, ReputationEvent::Sale);
+                let proceeds = raffle.ticket_price.saturating_mul(BalanceOf::<T>::from(sold));
+                let fee = T::RaffleFee::get() * proceeds;
+                if !fee.is_zero() {
+                    T::NativeBalance::transfer(&escrow, &Self::pot_account(), fee, Preservation::Expendable)?;
+                }
+                T::NativeBalance::transfer(
+                    &escrow,
+                    &raffle.seller,
+                    proceeds.saturating_sub(fee),
+                    Preservation::Expendable,
+                )?;
+                Self::deposit_event(Event::<T>::RaffleWon { kitty_id, winner, proceeds, fee });
+                return Ok(());
+            }
+        }
+
+        // Sin ganador: se devuelve cada boleto a quien lo compró.
+        for buyer in tickets.iter() {
+            T::NativeBalance::transfer(&escrow, buyer, raffle.ticket_price, Preservation::Expendable)?;
+        }
+        Self::deposit_event(Event::<T>::RaffleRefunded { kitty_id, tickets_refunded: sold });
+
+        Ok(())
+    }
+
+    /// Elige un boleto al azar con la aleatoriedad del runtime.
+    fn draw_raffle_winner(kitty_id: &[u8; 32], tickets: &[T::AccountId]) -> T::AccountId {
+        let (seed, _) = T::Randomness::random(&(b"kitties/raffle", kitty_id).encode());
+        let random = u32::decode(&mut seed.as_ref()).unwrap_or_default();
+        tickets[(random as usize) % tickets.len()].clone()
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: ventas con escrow y disputas
+    // -------------------------------------------------------------------------
+    // El pago de una venta con escrow queda en una subcuenta del pallet y el kitty bloqueado con
+    // el vendedor. Pasado `EscrowPeriod` cualquiera puede liquidar (kitty al comprador, pago al
+    // vendedor). Antes de eso cualquiera de las partes puede abrir una disputa, que congela la
+    // venta hasta que el `ArbiterOrigin` falle; si no lo hace en `DisputePeriod` bloques, se
+    // reembolsa al comprador y el kitty queda con el vendedor.
+
+    /// Subcuenta que retiene el pago de la venta `sale_id`.
+    pub fn escrow_account(sale_id: u32) -> T::AccountId {
+        T::PalletId::get().into_sub_account_truncating((b"escrow", sale_id))
+    }
+
+    /// Compra `kitty_id` con el pago retenido en escrow.
+    pub fn do_buy_kitty_escrowed(
+        buyer: T::AccountId,
+        kitty_id: [u8; 32],
+        max_price: BalanceOf<T>,
+    ) -> DispatchResult {
+        Self::ensure_can_trade(&buyer)?;
+        let mut kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        let price = kitty.price.ok_or(Error::<T>::NotForSale)?;
+        Self::ensure_can_trade(&kitty.owner)?;
+        ensure!(max_price >= price, Error::<T>::MaxPriceTooLow);
+        ensure!(kitty.owner != buyer, Error::<T>::TransferToSelf);
+        Self::ensure_reserved_for(kitty_id, &buyer)?;
+
+        let sale_id = NextSaleId::<T>::get();
+        NextSaleId::<T>::put(sale_id.wrapping_add(1));
+        T::NativeBalance::transfer(&buyer, &Self::escrow_account(sale_id), price, Preservation::Preserve)?;
+
+        // Sale de la venta directa mientras el escrow está pendiente.
+        let seller = kitty.owner.clone();
+        Self::delist(kitty_id, &mut kitty);
+        Kitties::<T>::insert(kitty_id, kitty);
+        Self::set_status(kitty_id, KittyStatus::InEscrow)?;
+        KittyEscrowSale::<T>::insert(kitty_id, sale_id);
+
+        let release_at =
+            frame_system::Pallet::<T>::block_number().saturating_add(T::EscrowPeriod::get());
+        EscrowedSales::<T>::insert(
+            sale_id,
+            EscrowedSale {
+                kitty_id,
+                seller: seller.clone(),
+                buyer: buyer.clone(),
+                price,
+                release_at,
+                status: EscrowStatus::Open,
+            },
+        );
+
+        Self::deposit_event(Event::<T>::EscrowedSaleOpened { sale_id, kitty_id, seller, buyer, price });
+
+        Ok(())
+    }
+
+    /// Abre una disputa sobre `sale_id` (comprador o vendedor, antes de `release_at`).
+    pub fn do_raise_dispute(who: T::AccountId, sale_id: u32) -> DispatchResult {
+        let mut sale = EscrowedSales::<T>::get(sale_id).ok_or(Error::<T>::NoSale)?;
+        ensure!(who == sale.buyer || who == sale.seller, Error::<T>::NotSaleParty);
+        ensure!(sale.status == EscrowStatus::Open, Error::<T>::SaleDisputed);
+        let now = frame_system::Pallet::<T>::block_number();
+        ensure!(now < sale.release_at, Error::<T>::DisputeWindowClosed);
+
+        let deadline = now.saturating_add(T::DisputePeriod::get());
+        sale.status = EscrowStatus::Disputed { deadline };
+        EscrowedSales::<T>::insert(sale_id, sale);
+
+        Self::deposit_event(Event::<T>::DisputeRaised { sale_id, by: who, deadline });
+
+        Ok(())
+    }
+
+    /// Fallo del árbitro sobre una venta en disputa.
+    pub fn do_rule_dispute(sale_id: u32, buyer_wins: bool) -> DispatchResult {
+        let sale = EscrowedSales::<T>::get(sale_id).ok_or(Error::<T>::NoSale)?;
+        ensure!(
+            matches!(sale.status, EscrowStatus::Disputed { .. }),
+            Error::<T>::SaleNotDisputed
+        );
+
+        // El resultado de la disputa cuenta en la reputación del vendedor.
+        let outcome =
+            if buyer_wins { ReputationEvent::DisputeLost } else { ReputationEvent::DisputeWon };
+        Self::note_reputation(&sale.seller, outcome);
+
+        Self::deposit_event(Event::<T>::DisputeRuled { sale_id, buyer_wins });
+        Self::finalize_escrowed_sale(sale_id, sale, !buyer_wins)
+    }
+
+    /// Liquidación por defecto una vez vencidos los plazos.
+    pub fn do_settle_escrowed_sale(sale_id: u32) -> DispatchResult {
+        let sale = EscrowedSales::<T>::get(sale_id).ok_or(Error::<T>::NoSale)?;
+        let now = frame_system::Pallet::<T>::block_number();
+        let complete = match sale.status {
+            EscrowStatus::Open => {
+                ensure!(now >= sale.release_at, Error::<T>::SaleNotDue);
+                true
+            },
+            // El árbitro no actuó a tiempo: se vuelve al estado anterior a la venta.
+            EscrowStatus::Disputed { deadline } => {
+                ensure!(now >= deadline, Error::<T>::SaleDisputed);
+                false
+            },
+        };
+        Self::finalize_escrowed_sale(sale_id, sale, complete)
+    }
+
+    /// Cierra la venta: con `complete` entrega el kitty y paga al vendedor; si no (o si el
+    /// comprador ya no puede recibir el kitty), reembolsa al comprador.
+    fn finalize_escrowed_sale(sale_id: u32, sale: EscrowedSaleOf<T>, complete: bool) -> DispatchResult {
+        EscrowedSales::<T>::remove(sale_id);
+        KittyEscrowSale::<T>::remove(sale.kitty_id);
+        Self::clear_status(sale.kitty_id, KittyStatus::InEscrow);
+
+        let escrow = Self::escrow_account(sale_id);
+        let completed = complete &&
+            with_storage_layer(|| {
+                Self::do_transfer(sale.seller.clone(), sale.buyer.clone(), sale.kitty_id)
+            })
+            .is_ok();
+        if completed {
+            Self::pay_sale(&sale.seller, sale.kitty_id, sale.price, |to, amount| {
+                T::NativeBalance::transfer(&escrow, to, amount, Preservation::Expendable).map(|_| ())
+            })?;
+            Self::note_reputation(&sale.seller, ReputationEvent::Sale);
+        } else {
+            T::NativeBalance::transfer(&escrow, &sale.buyer, sale.price, Preservation::Expendable)?;
+        }
+
+        Self::deposit_event(Event::<T>::EscrowedSaleSettled { sale_id, completed });
+
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: cría entre cadenas
+    // -------------------------------------------------------------------------
+    // El dueño ofrece su kitty a una parachain hermana. Esa cadena envía por XCM el ADN de la
+    // pareja (la atestación es el propio origen XCM, comprobado por `RemoteBreedingOrigin`);
+    // la cría nace aquí y se devuelve un recibo con `BreedingReceipts`. Cada oferta sirve para
+    // una sola cría. No hay gestación: el recibo necesita el identificador de la cría.
+
+    /// Guarda (o reemplaza) la oferta de cría remota de `kitty_id`.
+    pub fn do_offer_remote_breeding(
+        owner: T::AccountId,
+        kitty_id: [u8; 32],
+        para_id: u32,
+    ) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.owner == owner, Error::<T>::NotOwner);
+        RemoteBreedingOffers::<T>::insert(kitty_id, para_id);
+        Self::deposit_event(Event::<T>::RemoteBreedingOffered { kitty_id, para_id });
+        Ok(())
+    }
+
+    /// Borra la oferta de cría remota de `kitty_id` (solo su dueño).
+    pub fn do_cancel_remote_breeding(owner: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(kitty.owner == owner, Error::<T>::NotOwner);
+        RemoteBreedingOffers::<T>::take(kitty_id).ok_or(Error::<T>::NoRemoteOffer)?;
+        Self::deposit_event(Event::<T>::RemoteBreedingCancelled { kitty_id });
+        Ok(())
+    }
+
+    /// Cría `kitty_id` con la pareja remota `partner_id` enviada desde `para_id`.
+    pub fn do_breed_with_remote(
+        para_id: u32,
+        kitty_id: [u8; 32],
+        partner_id: [u8; 32],
+        partner_dna: KittyDna<T>,
+    ) -> DispatchResult {
+        let offered_to = RemoteBreedingOffers::<T>::get(kitty_id).ok_or(Error::<T>::NoRemoteOffer)?;
+        ensure!(offered_to == para_id, Error::<T>::WrongParachain);
+        ensure!(
+            partner_dna.len() == T::DnaLength::get() as usize,
+            Error::<T>::InvalidPartnerDna
+        );
+        let kitty = Kitties::<T>::get(kitty_id).ok_or(Error::<T>::NoKitty)?;
+        ensure!(Self::kitty_status(kitty_id) != KittyStatus::Locked, Error::<T>::KittyLocked);
+        ensure!(
+            Self::gender_of(&kitty.dna) != Self::gender_of(&partner_dna),
+            Error::<T>::SameGender
+        );
+        // Del linaje remoto solo se conoce la pareja: basta con que no sea ancestro del local.
+        ensure!(
+            !Self::ancestors(kitty_id, T::IncestDepth::get()).contains(&partner_id),
+            Error::<T>::TooCloselyRelated
+        );
+        Self::ensure_rested(&kitty)?;
+
+        // El identificador de la cría es aleatorio; el genoma mezcla el de ambos progenitores.
+        // La generación de la pareja remota no se conoce aquí: cuenta solo la del kitty local.
+        let generation = Self::child_generation(kitty.generation)?;
+        let child_id = Self::gen_dna(&kitty.owner);
+        let (dna, mutated) = Self::inherit_dna_with_mutations(
+            &kitty.dna,
+            &partner_dna,
+            &child_id,
+            T::MutationChance::get(),
+        );
+        Self::mint_with_dna(kitty.owner, child_id, dna, generation, Some((kitty_id, partner_id)))?;
+        Self::note_mutations(child_id, mutated);
+        Self::start_cooldown(kitty_id);
+        RemoteBreedingOffers::<T>::remove(kitty_id);
+
+        // Si no se puede enviar el recibo, la cría entera se revierte.
+        T::BreedingReceipts::send_receipt(para_id, partner_id, child_id)?;
+
+        Self::deposit_event(Event::<T>::RemoteKittyBred { kitty_id, para_id, partner_id, child_id });
+
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: límites de propiedad por niveles
+    // -------------------------------------------------------------------------
+    // Cada cuenta puede tener `BaseKittiesPerAccount` kitties, más `KittiesPerCapacityTier` por
+    // cada `StakePerCapacityTier` aportado, más `CollectorPassBonus` si posee un pase de
+    // coleccionista, sin pasar nunca de `MaxKittiesOwned`. El límite solo se evalúa al recibir
+    // un kitty (mint o transferencia): quien pierde nivel conserva lo que ya tiene.
+
+    /// Subcuenta que guarda el stake de capacidad.
+    pub fn capacity_stake_account() -> T::AccountId {
+        T::PalletId::get().into_sub_account_truncating(b"capacity")
+    }
+
+    /// Límite efectivo de kitties de `who` con un stake dado.
+    fn max_kitties_with_stake(who: &T::AccountId, stake: BalanceOf<T>) -> u32 {
+        let per_tier = T::StakePerCapacityTier::get();
+        let tiers: u32 = if per_tier.is_zero() { 0 } else { (stake / per_tier).saturated_into() };
+        let has_pass =
+            KittiesOwned::<T>::get(who).iter().any(CollectorPasses::<T>::contains_key);
+        T::BaseKittiesPerAccount::get()
+            .saturating_add(tiers.saturating_mul(T::KittiesPerCapacityTier::get()))
+            .saturating_add(if has_pass { T::CollectorPassBonus::get() } else { 0 })
+            .min(T::MaxKittiesOwned::get())
+    }
+
+    /// Límite efectivo de kitties de `who`.
+    pub fn max_kitties_for(who: &T::AccountId) -> u32 {
+        Self::max_kitties_with_stake(who, CapacityStakes::<T>::get(who))
+    }
+
+    /// Comprueba que `who` puede recibir un kitty más.
+    fn ensure_can_receive(who: &T::AccountId) -> DispatchResult {
+        let owned = KittiesOwned::<T>::decode_len(who).unwrap_or(0) as u32;
+        ensure!(owned < Self::max_kitties_for(who), Error::<T>::TooManyOwned);
+        Ok(())
+    }
+
+    /// Aumenta el stake de capacidad de `who`.
+    pub fn do_stake_for_capacity(who: T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+        T::NativeBalance::transfer(&who, &Self::capacity_stake_account(), amount, Preservation::Preserve)?;
+        let stake = CapacityStakes::<T>::mutate(&who, |stake| {
+            stake.saturating_accrue(amount);
+            *stake
+        });
+        let max_kitties = Self::max_kitties_with_stake(&who, stake);
+        Self::deposit_event(Event::<T>::CapacityStaked { who, amount, max_kitties });
+        Ok(())
+    }
+
+    /// Devuelve parte del stake de capacidad de `who`.
+    pub fn do_unstake_capacity(who: T::AccountId, amount: BalanceOf<T>) -> DispatchResult {
+        let stake = CapacityStakes::<T>::get(&who);
+        ensure!(amount <= stake, Error::<T>::InsufficientStake);
+        let remaining = stake.saturating_sub(amount);
+        let max_kitties = Self::max_kitties_with_stake(&who, remaining);
+        let owned = KittiesOwned::<T>::decode_len(&who).unwrap_or(0) as u32;
+        ensure!(owned <= max_kitties, Error::<T>::StakeBacksKitties);
+
+        T::NativeBalance::transfer(&Self::capacity_stake_account(), &who, amount, Preservation::Expendable)?;
+        if remaining.is_zero() {
+            CapacityStakes::<T>::remove(&who);
+        } else {
+            CapacityStakes::<T>::insert(&who, remaining);
+        }
+        Self::deposit_event(Event::<T>::CapacityUnstaked { who, amount, max_kitties });
+        Ok(())
+    }
+
+    /// Marca o desmarca `kitty_id` como pase de coleccionista.
+    pub fn do_set_collector_pass(kitty_id: [u8; 32], is_pass: bool) -> DispatchResult {
+        ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::NoKitty);
+        if is_pass {
+            CollectorPasses::<T>::insert(kitty_id, ());
+        } else {
+            CollectorPasses::<T>::remove(kitty_id);
+        }
+        Self::deposit_event(Event::<T>::CollectorPassSet { kitty_id, is_pass });
+        Ok(())
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: tasaciones del oráculo
+    // -------------------------------------------------------------------------
+
+    /// Guarda la tasación de `target` sellada con el bloque actual.
+    pub fn do_post_appraisal(target: AppraisalTarget, value: BalanceOf<T>) -> DispatchResult {
+        if let AppraisalTarget::Kitty(kitty_id) = target {
+            ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::NoKitty);
+        }
+        let updated_at = frame_system::Pallet::<T>::block_number();
+        Appraisals::<T>::insert(target, Appraisal { value, updated_at });
+
+        Self::deposit_event(Event::<T>::AppraisalPosted { target, value });
+
+        Ok(())
+    }
+
+    /// Valor de `target` si la tasación tiene como mucho `AppraisalMaxAge` bloques. Es lo que
+    /// deben usar préstamos, seguros, etc. para valorar un kitty como garantía.
+    pub fn fresh_appraisal(target: AppraisalTarget) -> Option<BalanceOf<T>> {
+        let appraisal = Appraisals::<T>::get(target)?;
+        let age = frame_system::Pallet::<T>::block_number().saturating_sub(appraisal.updated_at);
+        (age <= T::AppraisalMaxAge::get()).then_some(appraisal.value)
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: reputación de vendedores
+    // -------------------------------------------------------------------------
+    // Cada venta, anuncio retirado o disputa suma o resta puntos a la cuenta vendedora. La
+    // puntuación se reduce a la mitad cada `ReputationHalfLife` bloques, así que pesa más lo
+    // reciente. Los contadores no decaen: son el historial completo.
+
+    /// Puntuación vigente de `who`, con el decaimiento aplicado hasta el bloque actual.
+    pub fn reputation_of(who: &T::AccountId) -> i32 {
+        let reputation = Reputations::<T>::get(who);
+        Self::decayed_score(&reputation)
+    }
+
+    /// Registra un evento en la reputación de `who`.
+    pub fn note_reputation(who: &T::AccountId, event: ReputationEvent) {
+        Reputations::<T>::mutate(who, |reputation| {
+            reputation.score = Self::decayed_score(reputation).saturating_add(event.points());
+            reputation.last_update = frame_system::Pallet::<T>::block_number();
+            match event {
+                ReputationEvent::Sale => reputation.completed_sales.saturating_inc(),
+                ReputationEvent::ListingCancelled => reputation.cancelled_listings.saturating_inc(),
+                ReputationEvent::DisputeWon => reputation.disputes_won.saturating_inc(),
+                ReputationEvent::DisputeLost => reputation.disputes_lost.saturating_inc(),
+            }
+        });
+    }
+
+    /// Aplica a `score` una división por 2 por cada vida media transcurrida desde `last_update`.
+    fn decayed_score(reputation: &Reputation<BlockNumberFor<T>>) -> i32 {
+        let now = frame_system::Pallet::<T>::block_number();
+        let half_life = T::ReputationHalfLife::get().max(One::one());
+        let elapsed = now.saturating_sub(reputation.last_update) / half_life;
+        let halvings: u32 = elapsed.saturated_into();
+        reputation.score.checked_shr(halvings).unwrap_or(if reputation.score < 0 { -1 } else { 0 })
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: consultas de la API de runtime
+    // -------------------------------------------------------------------------
+    // Lo que implementa `runtime_api::KittiesApi`. Se ejecutan fuera de bloque, pero cada
+    // página se limita a `MAX_QUERY_PAGE` kitties para acotar la respuesta.
+
+    /// Tamaño máximo de una página de `owned_kitties`, `kitties_of` y `kitties_for_sale`.
+    pub const MAX_QUERY_PAGE: u32 = 100;
+
+    /// `kitty_id` con su nombre y estado, si existe.
+    pub fn kitty_info(kitty_id: [u8; 32]) -> Option<KittyInfoOf<T>> {
+        Kitties::<T>::get(kitty_id).map(|kitty| Self::to_info(kitty_id, kitty))
+    }
+
+    /// Ids de los kitties de `owner` en orden de id, como mucho `limit`, después de `cursor`.
+    /// `KittiesOwned` no guarda orden (`remove_owned` usa `swap_remove`), así que se ordena una
+    /// copia y el cursor es el último id devuelto: un kitty que sigue siendo de `owner` sale en
+    /// alguna página aunque otros se vayan entre una y otra.
+    pub fn owned_kitties(owner: &T::AccountId, cursor: Option<PageCursor>, limit: u32) -> KittyPage<[u8; 32]> {
+        let mut owned = KittiesOwned::<T>::get(owner).into_inner();
+        owned.sort_unstable();
+        let start = cursor.map_or(0, |PageCursor(last)| owned.partition_point(|kitty_id| *kitty_id <= last));
+        let end = start.saturating_add(limit.min(Self::MAX_QUERY_PAGE) as usize).min(owned.len());
+        let items = owned[start..end].to_vec();
+        let next = (end < owned.len()).then(|| items.last().copied().map(PageCursor)).flatten();
+        KittyPage { items, next }
+    }
+
+    /// Como `owned_kitties`, pero con los datos de cada kitty.
+    pub fn kitties_of(owner: &T::AccountId, cursor: Option<PageCursor>, limit: u32) -> KittyPage<KittyInfoOf<T>> {
+        let page = Self::owned_kitties(owner, cursor, limit);
+        KittyPage { items: page.items.into_iter().filter_map(Self::kitty_info).collect(), next: page.next }
+    }
+
+    /// Kitties con precio en token nativo, en el orden de `Kitties`, después de `cursor`. El
+    /// cursor no tiene por qué seguir existiendo: se continúa desde su posición en el mapa.
+    pub fn kitties_for_sale(cursor: Option<[u8; 32]>, limit: u32) -> Vec<KittyInfoOf<T>> {
+        let kitties = match cursor {
+            Some(cursor) => Kitties::<T>::iter_from(Kitties::<T>::hashed_key_for(cursor)),
+            None => Kitties::<T>::iter(),
+        };
+        kitties
+            .filter(|(_, kitty)| kitty.price.is_some())
+            .take(limit.min(Self::MAX_QUERY_PAGE) as usize)
+            .map(|(kitty_id, kitty)| Self::to_info(kitty_id, kitty))
+            .collect()
+    }
+
+    fn to_info(kitty_id: [u8; 32], kitty: Kitty<T>) -> KittyInfoOf<T> {
+        KittyInfo {
+            id: kitty_id,
+            dna: kitty.dna.into_inner(),
+            owner: kitty.owner,
+            price: kitty.price,
+            cooldown_until: kitty.cooldown_until,
+            generation: kitty.generation,
+            parents: kitty.parents,
+            rarity: kitty.rarity,
+            special_edition: kitty.special_edition,
+            name: KittyNames::<T>::get(kitty_id).map(|name| name.into_inner()),
+            status: Self::kitty_status(kitty_id),
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: metadatos ERC-721
+    // -------------------------------------------------------------------------
+    // JSON con el esquema de metadatos de ERC-721 que entienden OpenSea y los demás mercados:
+    // `name`, `description`, `attributes` (`trait_type`/`value`) y, en lugar de `image`, la
+    // semilla (el genoma) con la que la pasarela dibuja el kitty. Los atributos libres de
+    // `ItemAttributes` se añaden como rasgos de texto.
+
+    /// Metadatos de `kitty_id`, o un vector vacío si no existe. Es lo que devuelve
+    /// `runtime_api::KittiesApi::token_metadata`.
+    pub fn token_metadata(kitty_id: [u8; 32]) -> Vec<u8> {
+        let Some(kitty) = Kitties::<T>::get(kitty_id) else {
+            return Vec::new();
+        };
+        let mut json = String::from("{\"name\":");
+        match KittyNames::<T>::get(kitty_id) {
+            Some(name) => Self::push_json_string(&mut json, &name),
+            None => {
+                json.push_str("\"Kitty 0x");
+                Self::push_hex(&mut json, &kitty_id[..4]);
+                json.push('"');
+            },
+        }
+        let _ = write!(json, ",\"description\":\"Generation {} kitty\",\"image_seed\":\"0x", kitty.generation);
+        Self::push_hex(&mut json, &kitty.dna);
+        json.push_str("\",\"attributes\":[");
+
+        let attributes = Self::attributes(&kitty.dna);
+        for kitty_trait in attributes.traits() {
+            let (trait_type, value) = Self::trait_names(kitty_trait);
+            let _ = write!(json, "{{\"trait_type\":\"{trait_type}\",\"value\":\"{value}\"}},");
+        }
+        let _ = write!(
+            json,
+            "{{\"display_type\":\"number\",\"trait_type\":\"Generation\",\"value\":{}}},",
+            kitty.generation
+        );
+        let _ = write!(json, "{{\"trait_type\":\"Rarity\",\"value\":{},\"max_value\":1000}}", kitty.rarity);
+        if let Some(season) = kitty.special_edition {
+            let _ = write!(json, ",{{\"trait_type\":\"Special edition\",\"value\":{season}}}");
+        }
+        for (key, value) in ItemAttributes::<T>::iter_prefix(kitty_id) {
+            json.push_str(",{\"trait_type\":");
+            Self::push_json_string(&mut json, &key);
+            json.push_str(",\"value\":");
+            Self::push_json_string(&mut json, &value);
+            json.push('}');
+        }
+        json.push_str("]}");
+        json.into_bytes()
+    }
+
+    /// Tipo y valor de un rasgo, con los nombres que se muestran en los mercados.
+    fn trait_names(kitty_trait: KittyTrait) -> (&'static str, &'static str) {
+        match kitty_trait {
+            KittyTrait::Gender(Gender::Female) => ("Gender", "Female"),
+            KittyTrait::Gender(Gender::Male) => ("Gender", "Male"),
+            KittyTrait::Fur(Fur::Short) => ("Fur", "Short"),
+            KittyTrait::Fur(Fur::Long) => ("Fur", "Long"),
+            KittyTrait::Fur(Fur::Curly) => ("Fur", "Curly"),
+            KittyTrait::Fur(Fur::Hairless) => ("Fur", "Hairless"),
+            KittyTrait::EyeColor(EyeColor::Amber) => ("Eye color", "Amber"),
+            KittyTrait::EyeColor(EyeColor::Blue) => ("Eye color", "Blue"),
+            KittyTrait::EyeColor(EyeColor::Green) => ("Eye color", "Green"),
+            KittyTrait::EyeColor(EyeColor::Brown) => ("Eye color", "Brown"),
+            KittyTrait::EyeColor(EyeColor::Hazel) => ("Eye color", "Hazel"),
+            KittyTrait::EyeColor(EyeColor::OddEyed) => ("Eye color", "Odd-eyed"),
+            KittyTrait::Pattern(Pattern::Solid) => ("Pattern", "Solid"),
+            KittyTrait::Pattern(Pattern::Tabby) => ("Pattern", "Tabby"),
+            KittyTrait::Pattern(Pattern::Spotted) => ("Pattern", "Spotted"),
+            KittyTrait::Pattern(Pattern::Calico) => ("Pattern", "Calico"),
+        }
+    }
+
+    /// Añade `bytes` como cadena JSON, escapando comillas, barras y caracteres de control. Los
+    /// bytes que no son UTF-8 válido se sustituyen por U+FFFD.
+    fn push_json_string(json: &mut String, bytes: &[u8]) {
+        json.push('"');
+        for c in String::from_utf8_lossy(bytes).chars() {
+            match c {
+                '"' => json.push_str("\\\""),
+                '\\' => json.push_str("\\\\"),
+                c if (c as u32) < 0x20 => {
+                    let _ = write!(json, "\\u{:04x}", c as u32);
+                },
+                c => json.push(c),
+            }
+        }
+        json.push('"');
+    }
+
+    /// Añade `bytes` en hexadecimal (minúsculas, sin prefijo).
+    fn push_hex(json: &mut String, bytes: &[u8]) {
+        for byte in bytes {
+            let _ = write!(json, "{byte:02x}");
+        }
+    }
+
+    // -------------------------------------------------------------------------
+    //  Funciones: on_kitties_received() / on_kitties_removed()
+    // -------------------------------------------------------------------------
+    // Una cuenta que solo tiene kitties (sin balance) no debe poder ser eliminada, o sus
+    // entradas en `Kitties` quedarían huérfanas. Por eso el pallet aporta una referencia de
+    // "provider" mientras la cuenta tenga al menos un kitty.
+    // Deben llamarse después de actualizar `KittiesOwned`.
+
+    /// Añade la referencia de provider cuando la cuenta pasa de 0 a 1 kitty.
+    pub(crate) fn on_kitties_received(who: &T::AccountId) {
+        if KittiesOwned::<T>::decode_len(who) == Some(1) {
+            frame_system::Pallet::<T>::inc_providers(who);
+        }
+    }
+
+    /// Libera la referencia de provider cuando la cuenta se queda sin kitties.
+    pub(crate) fn on_kitties_removed(who: &T::AccountId) -> DispatchResult {
+        if KittiesOwned::<T>::decode_len(who).unwrap_or(0) == 0 {
+            frame_system::Pallet::<T>::dec_providers(who)?;
+        }
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+//  KittiesProvider
+// -----------------------------------------------------------------------------
+
+impl<T: Config> KittiesProvider<T::AccountId, BalanceOf<T>> for Pallet<T> {
+    fn mint_to(who: &T::AccountId) -> Result<[u8; 32], DispatchError> {
+        Self::ensure_not_paused(CallClass::Minting)?;
+        let kitty_id = Self::gen_dna(who);
+        Self::mint(who.clone(), kitty_id)?;
+        Ok(kitty_id)
+    }
+
+    fn transfer(from: &T::AccountId, to: &T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+        Self::ensure_not_paused(CallClass::Transfers)?;
+        Self::do_transfer(from.clone(), to.clone(), kitty_id)
+    }
+
+    fn owner_of(kitty_id: [u8; 32]) -> Option<T::AccountId> {
+        Pallet::<T>::owner_of(kitty_id)
+    }
+
+    fn price_of(kitty_id: [u8; 32]) -> Option<BalanceOf<T>> {
+        Pallet::<T>::price_of(kitty_id)
+    }
+}
+
+// -----------------------------------------------------------------------------
+//  nonfungibles_v2
+// -----------------------------------------------------------------------------
+// Los kitties se exponen como los ítems (por su identificador) de una única colección,
+// `KittyCollection`, para que otros pallets y herramientas NFT genéricas los consulten y muevan
+// sin conocer el almacenamiento de este pallet. Cualquier otra colección no existe. Las
+// operaciones pasan por las mismas funciones que las extrínsecas (`do_transfer`, `mint`,
+// `burn`), con sus comprobaciones y eventos; como aquí no pasan por `PauseFilter`, las
+// transferencias y los mints comprueban la pausa de su clase. Los atributos clave/valor viven en
+// `ItemAttributes`, se borran con el kitty y no tienen nada que ver con sus rasgos genéticos.
+
+impl<T: Config> nonfungibles_v2::Inspect<T::AccountId> for Pallet<T> {
+    type ItemId = [u8; 32];
+    type CollectionId = u32;
+
+    fn owner(collection: &u32, item: &[u8; 32]) -> Option<T::AccountId> {
+        if *collection != T::KittyCollection::get() {
+            return None;
+        }
+        Kitties::<T>::get(item).map(|kitty| kitty.owner)
+    }
+
+    fn attribute(collection: &u32, item: &[u8; 32], key: &[u8]) -> Option<Vec<u8>> {
+        if *collection != T::KittyCollection::get() {
+            return None;
+        }
+        let key = AttributeKey::<T>::try_from(key.to_vec()).ok()?;
+        ItemAttributes::<T>::get(item, key).map(|value| value.into_inner())
+    }
+
+    fn can_transfer(collection: &u32, item: &[u8; 32]) -> bool {
+        *collection == T::KittyCollection::get() &&
+            Kitties::<T>::contains_key(item) &&
+            Self::ensure_movable(*item).is_ok()
+    }
+}
+
+impl<T: Config> nonfungibles_v2::Transfer<T::AccountId> for Pallet<T> {
+    fn transfer(collection: &u32, item: &[u8; 32], destination: &T::AccountId) -> DispatchResult {
+        let owner = Pallet::<T>::kitty_owner(collection, item)?;
+        Self::ensure_not_paused(CallClass::Transfers)?;
+        Self::do_transfer(owner, destination.clone(), *item)
+    }
+
+    /// Bloquea el kitty como si lo hiciera su dueño con `lock_kitty`.
+    fn disable_transfer(collection: &u32, item: &[u8; 32]) -> DispatchResult {
+        let owner = Pallet::<T>::kitty_owner(collection, item)?;
+        Self::do_lock_kitty(owner, *item)
+    }
+
+    /// Desbloquea el kitty como si lo hiciera su dueño con `unlock_kitty`.
+    fn enable_transfer(collection: &u32, item: &[u8; 32]) -> DispatchResult {
+        let owner = Pallet::<T>::kitty_owner(collection, item)?;
+        Self::do_unlock_kitty(owner, *item)
+    }
+}
+
+impl<T: Config> nonfungibles_v2::Mutate<T::AccountId, ()> for Pallet<T> {
+    /// Crea el kitty `item` para `who` como `mint()`: sin tasas de creación, pero retiene
+    /// `KittyDeposit` a `who` hasta que el kitty se queme. Falla si los mints están en pausa.
+    fn mint_into(
+        collection: &u32,
+        item: &[u8; 32],
+        who: &T::AccountId,
+        _config: &(),
+        _deposit_collection_owner: bool,
+    ) -> DispatchResult {
+        ensure!(*collection == T::KittyCollection::get(), Error::<T>::UnknownCollection);
+        Self::ensure_not_paused(CallClass::Minting)?;
+        Self::mint(who.clone(), *item)
+    }
+
+    fn burn(collection: &u32, item: &[u8; 32], maybe_check_owner: Option<&T::AccountId>) -> DispatchResult {
+        let owner = Pallet::<T>::kitty_owner(collection, item)?;
+        if let Some(check_owner) = maybe_check_owner {
+            ensure!(*check_owner == owner, Error::<T>::NotOwner);
+        }
+        Pallet::<T>::burn(&owner, *item)
+    }
+
+    fn set_attribute(collection: &u32, item: &[u8; 32], key: &[u8], value: &[u8]) -> DispatchResult {
+        Pallet::<T>::kitty_owner(collection, item)?;
+        let key = AttributeKey::<T>::try_from(key.to_vec()).map_err(|_| Error::<T>::AttributeKeyTooLong)?;
+        let value =
+            AttributeValue::<T>::try_from(value.to_vec()).map_err(|_| Error::<T>::AttributeValueTooLong)?;
+        ItemAttributes::<T>::insert(item, &key, &value);
+
+        Self::deposit_event(Event::<T>::AttributeSet { kitty_id: *item, key, value });
+        Ok(())
+    }
+
+    fn clear_attribute(collection: &u32, item: &[u8; 32], key: &[u8]) -> DispatchResult {
+        Pallet::<T>::kitty_owner(collection, item)?;
+        let key = AttributeKey::<T>::try_from(key.to_vec()).map_err(|_| Error::<T>::NoAttribute)?;
+        ItemAttributes::<T>::take(item, &key).ok_or(Error::<T>::NoAttribute)?;
+
+        Self::deposit_event(Event::<T>::AttributeCleared { kitty_id: *item, key });
+        Ok(())
+    }
+}
+
+// -----------------------------------------------------------------------------
+//  GeneEntropy
+// -----------------------------------------------------------------------------
+/// Flujo de bytes pseudoaleatorios derivado de una semilla: hashes encadenados de
+/// `(seed, contador)`. Solo es tan impredecible como la semilla que recibe.
+struct GeneEntropy {
+    seed: [u8; 32],
+    counter: u32,
+    block: [u8; 32],
+    position: usize,
+}
+
+impl GeneEntropy {
+    fn new(seed: &[u8; 32]) -> Self {
+        Self { seed: *seed, counter: 0, block: [0u8; 32], position: 32 }
+    }
+
+    /// Siguiente byte del flujo.
+    fn byte(&mut self) -> u8 {
+        if self.position == 32 {
+            self.block = BlakeTwo256::hash_of(&(self.seed, self.counter)).into();
+            self.counter = self.counter.wrapping_add(1);
+            self.position = 0;
+        }
+        self.position += 1;
+        self.block[self.position - 1]
+    }
+
+    /// `true` con probabilidad `chance`.
+    fn chance(&mut self, chance: Permill) -> bool {
+        if chance.is_zero() {
+            return false;
+        }
+        let roll = u32::from_le_bytes([self.byte(), self.byte(), self.byte(), self.byte()]);
+        roll % 1_000_000 < chance.deconstruct()
+    }
 }
@@ -1,20 +1,46 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
 
 mod impls; 
+pub mod migrations;
+pub mod eth_bridge;
+pub mod runtime_api;
+#[cfg(feature = "chain-extension")]
+pub mod chain_extension;
+#[cfg(feature = "xcm")]
+pub mod xcm_adapter;
+pub mod weights;
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
 mod tests; 
 
+use frame::arithmetic::One;
+use frame::arithmetic::Permill;
+use frame::arithmetic::Saturating;
+use frame::deps::frame_support::PalletId;
 use frame::prelude::*;
 use frame::traits::fungible::Inspect; 
 use frame::traits::fungible::Mutate;  
+use frame::traits::fungible::MutateHold;
+use frame::traits::fungibles;
+use frame::traits::Contains;
+use frame::traits::IsSubType;
+use frame::traits::Randomness;
 pub use pallet::*; 
+pub use weights::WeightInfo;
+use alloc::vec::Vec;
 
 #[frame::pallet(dev_mode)]
 pub mod pallet {
     use super::*; 
 
+    /// Versión actual del almacenamiento (ver `migrations.rs`).
+    pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(9);
+
     // --- Declaración principal del pallet ---
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(core::marker::PhantomData<T>);
     // Estructura principal del pallet.
     // `PhantomData` indica que este pallet depende del tipo genérico T (que implementa Config),
@@ -26,8 +52,390 @@ pub mod pallet {
         // Tipo de evento que usará el runtime cuando esta paleta emita eventos.
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
-        /// Manejador de balance nativo (para operaciones de compra/venta).
-        type NativeBalance: Inspect<Self::AccountId> + Mutate<Self::AccountId>;
+        /// Llamada del runtime. `integrity_test` la usa para comprobar que `BaseCallFilter` incluye
+        /// `PauseFilter`, que es quien aplica las pausas a las extrínsecas.
+        type RuntimeCall: From<Call<Self>> + IsType<<Self as frame_system::Config>::RuntimeCall>;
+
+        /// Manejador de balance nativo (para operaciones de compra/venta y depósitos retenidos).
+        type NativeBalance: Inspect<Self::AccountId>
+            + Mutate<Self::AccountId>
+            + MutateHold<Self::AccountId, Reason = Self::RuntimeHoldReason>;
+
+        /// Activos del runtime (p. ej. `pallet-assets`) en los que también se pueden vender kitties.
+        type Fungibles: fungibles::Inspect<Self::AccountId> + fungibles::Mutate<Self::AccountId>;
+
+        /// Fábrica de activos (p. ej. `pallet-assets`) en la que se crean los tokens que envuelven
+        /// kitties: cada kitty envuelto es un activo nuevo con una sola unidad.
+        type KittyTokens: fungibles::Create<Self::AccountId> + fungibles::Mutate<Self::AccountId>;
+
+        /// Motivos de retención del runtime; incluye los de este pallet (`HoldReason`).
+        type RuntimeHoldReason: From<HoldReason>;
+
+        /// Depósito de almacenamiento que se retiene a quien crea cada kitty y se le devuelve
+        /// cuando el kitty se destruye.
+        #[pallet::constant]
+        type KittyDeposit: Get<BalanceOf<Self>>;
+
+        /// Depósito que se retiene al poner un kitty a la venta y se devuelve al dejar de estarlo
+        /// (retirada, venta o cambio de dueño), para que anunciar no salga gratis.
+        #[pallet::constant]
+        type ListingDeposit: Get<BalanceOf<Self>>;
+
+        /// Depósito que se retiene al nombrar un operador con `set_approval_for_all` y se devuelve
+        /// al retirarlo o al borrar la delegación caducada, para que las delegaciones no salgan
+        /// gratis.
+        #[pallet::constant]
+        type OperatorDeposit: Get<BalanceOf<Self>>;
+
+        /// Longitud (en bytes) del ADN de cada kitty. Permite reutilizar el pallet con genomas
+        /// más cortos o más largos que los 32 bytes del identificador.
+        #[pallet::constant]
+        type DnaLength: Get<u32>;
+
+        /// Fuente de aleatoriedad del runtime (BABE/VRF en producción) que se mezcla en el ADN.
+        /// Para desarrollo sirve `BlockHashRandomness`.
+        type Randomness: Randomness<Self::Hash, BlockNumberFor<Self>>;
+
+        /// Identificador del pallet; de él se deriva la cuenta del bote (pot) que recibe las tasas.
+        #[pallet::constant]
+        type PalletId: Get<PalletId>;
+
+        /// Tasa base por nombrar un kitty; se multiplica según lo corto del nombre y los
+        /// renombres de la cuenta en la era actual.
+        #[pallet::constant]
+        type NameFee: Get<BalanceOf<Self>>;
+
+        /// Longitud máxima (en bytes) del nombre de un kitty.
+        #[pallet::constant]
+        type MaxNameLength: Get<u32>;
+
+        /// Duración (en bloques) de una era de renombres.
+        #[pallet::constant]
+        type RenameEra: Get<BlockNumberFor<Self>>;
+
+        /// Precio de un vale regalo canjeable por un mint.
+        #[pallet::constant]
+        type VoucherPrice: Get<BalanceOf<Self>>;
+
+        /// Tasa que paga cada `create_kitty` (o `reveal_create`) no cubierto por un pase, para
+        /// encarecer el spam de mints.
+        #[pallet::constant]
+        type MintFee: Get<BalanceOf<Self>>;
+
+        /// Cuenta que recibe la tasa de mint (p. ej. la tesorería); con `None` se quema.
+        type MintFeeDestination: Get<Option<Self::AccountId>>;
+
+        /// Kitties que una cuenta puede crear por periodo de `MintQuotaPeriod` bloques; con
+        /// cero no hay límite.
+        #[pallet::constant]
+        type MintQuota: Get<u32>;
+
+        /// Duración en bloques de cada periodo de la cuota de mint.
+        #[pallet::constant]
+        type MintQuotaPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Precio inicial de la curva de mint; con cero no hay curva y se cobra `MintFee`.
+        #[pallet::constant]
+        type CurveBasePrice: Get<BalanceOf<Self>>;
+
+        /// Cada cuántos kitties existentes se duplica el precio de la curva.
+        #[pallet::constant]
+        type CurveDoublingInterval: Get<u32>;
+
+        /// Parte de cada mint de la curva que va a su reserva; el resto va a la tesorería.
+        #[pallet::constant]
+        type CurveReserveShare: Get<Permill>;
+
+        /// Tesorería que recibe el resto de cada mint de la curva; con `None`, el bote del pallet.
+        type MintProceedsTreasury: Get<Option<Self::AccountId>>;
+
+        /// Precio de un pase de temporada para mints.
+        #[pallet::constant]
+        type MintPassPrice: Get<BalanceOf<Self>>;
+
+        /// Cantidad de mints que incluye cada pase.
+        #[pallet::constant]
+        type MintPassMints: Get<u32>;
+
+        /// Duración (en bloques) de la temporada que cubre un pase.
+        #[pallet::constant]
+        type MintPassDuration: Get<BlockNumberFor<Self>>;
+
+        /// Prima del seguro de un kitty por cada periodo cubierto.
+        #[pallet::constant]
+        type InsurancePremium: Get<BalanceOf<Self>>;
+
+        /// Duración (en bloques) de un periodo de cobertura del seguro.
+        #[pallet::constant]
+        type InsurancePeriod: Get<BlockNumberFor<Self>>;
+
+        /// Indemnización que recibe un asegurado cuando se aprueba su reclamo.
+        #[pallet::constant]
+        type InsurancePayout: Get<BalanceOf<Self>>;
+
+        /// Origen que arbitra los reclamos del seguro (p. ej. un consejo o Root).
+        type ArbiterOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Bloques durante los que el destinatario puede aceptar una transferencia ofrecida.
+        #[pallet::constant]
+        type TransferOfferExpiry: Get<BlockNumberFor<Self>>;
+
+        /// Bloques que tiene el destinatario para reclamar un regalo antes de que vuelva al
+        /// remitente.
+        #[pallet::constant]
+        type GiftExpiry: Get<BlockNumberFor<Self>>;
+
+        /// Máximo de regalos que pueden caducar en un mismo bloque; acota el trabajo de
+        /// `on_initialize`.
+        #[pallet::constant]
+        type MaxGiftsPerBlock: Get<u32>;
+
+        /// Máximo de alquileres que pueden terminar en un mismo bloque; acota el trabajo de
+        /// `on_initialize`.
+        #[pallet::constant]
+        type MaxRentalsPerBlock: Get<u32>;
+
+        /// Identificador de la colección con la que los kitties se exponen por los traits
+        /// `nonfungibles_v2`. Todos los kitties forman una única colección.
+        #[pallet::constant]
+        type KittyCollection: Get<u32>;
+
+        /// Longitud máxima (en bytes) de la clave de un atributo de un kitty.
+        #[pallet::constant]
+        type MaxAttributeKeyLength: Get<u32>;
+
+        /// Longitud máxima (en bytes) del valor de un atributo de un kitty.
+        #[pallet::constant]
+        type MaxAttributeValueLength: Get<u32>;
+
+        /// Máximo de kitties que se pueden mover en un `transfer_many`.
+        #[pallet::constant]
+        type MaxBatchTransfer: Get<u32>;
+
+        /// Máximo de copropietarios de un kitty.
+        #[pallet::constant]
+        type MaxCoOwners: Get<u32>;
+
+        /// Máximo de kitties en un lote (`create_bundle`).
+        #[pallet::constant]
+        type MaxBundleSize: Get<u32>;
+
+        /// Bloques durante los que se puede aceptar un intercambio propuesto.
+        #[pallet::constant]
+        type SwapExpiry: Get<BlockNumberFor<Self>>;
+
+        /// Duración máxima, en bloques, de una subasta.
+        #[pallet::constant]
+        type MaxAuctionDuration: Get<BlockNumberFor<Self>>;
+
+        /// Máximo de subastas que pueden terminar en un mismo bloque; acota el trabajo de
+        /// `on_initialize`.
+        #[pallet::constant]
+        type MaxAuctionsPerBlock: Get<u32>;
+
+        /// Bloques finales de una subasta en los que una puja la alarga (anti-sniping).
+        #[pallet::constant]
+        type AuctionExtensionWindow: Get<BlockNumberFor<Self>>;
+
+        /// Bloques que se alarga una subasta por cada puja tardía.
+        #[pallet::constant]
+        type AuctionExtension: Get<BlockNumberFor<Self>>;
+
+        /// Número de precios de venta que se guardan por kitty (`PriceHistory`).
+        #[pallet::constant]
+        type PriceHistoryLength: Get<u32>;
+
+        /// Máximo de kitties que se pueden comprar en un `sweep_floor`.
+        #[pallet::constant]
+        type MaxSweep: Get<u32>;
+
+        /// Máximo de boletos que puede tener una rifa.
+        #[pallet::constant]
+        type MaxRaffleTickets: Get<u32>;
+
+        /// Porción mínima de boletos (sobre `max_tickets`) que tiene que venderse para que la rifa
+        /// tenga ganador; si no se alcanza, se devuelve el dinero a todos.
+        #[pallet::constant]
+        type RaffleMinFill: Get<Permill>;
+
+        /// Comisión sobre lo recaudado en una rifa, que va al bote del pallet.
+        #[pallet::constant]
+        type RaffleFee: Get<Permill>;
+
+        /// Comisión del mercado sobre el precio de cada venta (directa o con escrow). El
+        /// vendedor recibe el precio menos la comisión.
+        #[pallet::constant]
+        type SaleCommission: Get<Permill>;
+
+        /// Cuenta que recibe la comisión de venta (p. ej. la tesorería); con `None`, el bote.
+        type CommissionDestination: Get<Option<Self::AccountId>>;
+
+        /// Regalía sobre el precio de cada reventa, para quien creó (o crió) el kitty.
+        #[pallet::constant]
+        type RoyaltyRate: Get<Permill>;
+
+        /// Bloques tras los cuales la puntuación de reputación de una cuenta se reduce a la mitad.
+        #[pallet::constant]
+        type ReputationHalfLife: Get<BlockNumberFor<Self>>;
+
+        /// Bloques que el pago de una venta con escrow queda retenido antes de liberarse.
+        #[pallet::constant]
+        type EscrowPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Bloques que tiene el árbitro para resolver una disputa; si no actúa, se reembolsa al
+        /// comprador.
+        #[pallet::constant]
+        type DisputePeriod: Get<BlockNumberFor<Self>>;
+
+        /// Origen autorizado a publicar tasaciones (p. ej. un oráculo de valoración).
+        type OracleOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Bloques tras los cuales una tasación se considera obsoleta.
+        #[pallet::constant]
+        type AppraisalMaxAge: Get<BlockNumberFor<Self>>;
+
+        /// Origen de los mensajes de cría entre cadenas. Debe resolver el origen XCM de una
+        /// parachain hermana a su `ParaId` (p. ej. `EnsureXcm` más un conversor de `Location`).
+        type RemoteBreedingOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = u32>;
+
+        /// Envía a la parachain de origen el recibo de una cría entre cadenas (normalmente
+        /// construyendo un mensaje XCM con `SendXcm`).
+        type BreedingReceipts: BreedingReceipts;
+
+        /// Envía a una parachain hermana la representación de un kitty que queda en reserva
+        /// aquí (normalmente con un mensaje XCM `ReserveAssetDeposited`).
+        type KittyTransport: KittyTransport;
+
+        /// Origen de los mensajes que traen kitties desde otras cadenas (propios que vuelven o
+        /// ajenos que llegan). Debe resolver el origen XCM de una parachain hermana a su `ParaId`.
+        type KittyReturnOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = u32>;
+
+        /// Canal de salida del puente con Ethereum, que entrega los mensajes de `export_kitty`.
+        type EthereumOutbound: EthereumOutbound;
+
+        /// Origen que finaliza los mensajes que llegan de Ethereum, una vez verificada su
+        /// prueba (normalmente el cliente ligero o el verificador del puente).
+        type EthereumBridgeOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Se avisa cada vez que se crea un kitty, por cualquier vía (mint, cría, fusión...).
+        type OnKittyMinted: OnKittyMinted<Self::AccountId>;
+
+        /// Se avisa cada vez que un kitty cambia de dueño, por cualquier vía (transferencia,
+        /// venta, subasta, bóveda...).
+        type OnKittyTransferred: OnKittyTransferred<Self::AccountId>;
+
+        /// Se avisa cada vez que se destruye un kitty, por cualquier vía (quema, fusión...).
+        type OnKittyBurned: OnKittyBurned<Self::AccountId>;
+
+        /// Si se escribe el historial de cada kitty (mints, transferencias y ventas) en el
+        /// índice off-chain (ver `Pallet::history_key`), para que los nodos archivo lo sirvan a
+        /// exploradores sin reejecutar bloques. Los nodos tienen que arrancar con
+        /// `--enable-offchain-indexing`.
+        #[pallet::constant]
+        type OffchainIndexing: Get<bool>;
+
+        /// Límite absoluto de kitties por cuenta (cota del `BoundedVec` de `KittiesOwned`).
+        #[pallet::constant]
+        type MaxKittiesOwned: Get<u32>;
+
+        /// Kitties que puede tener cualquier cuenta sin stake ni pase de coleccionista.
+        #[pallet::constant]
+        type BaseKittiesPerAccount: Get<u32>;
+
+        /// Stake necesario para subir un nivel de capacidad.
+        #[pallet::constant]
+        type StakePerCapacityTier: Get<BalanceOf<Self>>;
+
+        /// Kitties extra que da cada nivel de capacidad.
+        #[pallet::constant]
+        type KittiesPerCapacityTier: Get<u32>;
+
+        /// Kitties extra para quien posee al menos un kitty marcado como pase de coleccionista.
+        #[pallet::constant]
+        type CollectorPassBonus: Get<u32>;
+
+        /// Origen que puede marcar kitties como pase de coleccionista.
+        type CollectorPassOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Probabilidad de que cada byte del genoma de una cría mute a un valor aleatorio.
+        #[pallet::constant]
+        type MutationChance: Get<Permill>;
+
+        /// Bloques que debe esperar un kitty después de criar para volver a ser progenitor.
+        #[pallet::constant]
+        type BreedingCooldown: Get<BlockNumberFor<Self>>;
+
+        /// Generación máxima que puede alcanzar una cría (`None` para no limitarla).
+        #[pallet::constant]
+        type MaxGeneration: Get<Option<u32>>;
+
+        /// Bloques de gestación entre `breed_kitty` y el momento en que se puede reclamar la cría.
+        #[pallet::constant]
+        type GestationPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Tarifa del protocolo que paga quien cría en cada `breed_kitty` o `breed_with`. La cría
+        /// remota no la paga: no hay una cuenta local que firme.
+        #[pallet::constant]
+        type BreedingFee: Get<BalanceOf<Self>>;
+
+        /// Cuenta que recibe la tarifa de cría (p. ej. la tesorería); con `None` se quema.
+        type BreedingFeeDestination: Get<Option<Self::AccountId>>;
+
+        /// Generaciones que se revisan para rechazar parejas emparentadas (0 lo desactiva).
+        #[pallet::constant]
+        type IncestDepth: Get<u32>;
+
+        /// Precio del primer `reroll_dna` de un kitty; se duplica con cada uno de los siguientes.
+        #[pallet::constant]
+        type RerollBaseFee: Get<BalanceOf<Self>>;
+
+        /// Origen que puede programar temporadas de edición limitada.
+        type SeasonOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Origen que puede fijar el tope de suministro (`MaxTotalKitties`).
+        type SupplyOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Origen que gestiona la fase de mint y su lista de cuentas permitidas.
+        type MintPhaseOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Origen que puede repartir kitties con `force_mint_batch`.
+        type AirdropOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Origen que puede mover o destruir kitties sin ser su dueño (`force_transfer`,
+        /// `force_burn`), para resolver disputas, recuperar kitties robados o retirar los creados
+        /// mediante un exploit.
+        type ForceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Origen que puede pausar y reanudar el pallet ante un incidente.
+        type PauseOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Origen que gestiona la lista negra del mercado.
+        type BlacklistOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Cuentas que pueden operar en el mercado (anunciar, comprar y pujar), p. ej. las que
+        /// pasaron un KYC o tienen una membresía. En cadenas sin permisos, `Everything`.
+        type MarketParticipants: frame::traits::Contains<Self::AccountId>;
+
+        /// Máximo de destinatarios de un `force_mint_batch`.
+        #[pallet::constant]
+        type MaxAirdropRecipients: Get<u32>;
+
+        /// Bloques entre `commit_create` y el bloque cuyo hash decide el ADN. Se puede revelar
+        /// a partir del bloque siguiente, mientras su hash siga guardado en `frame_system`.
+        #[pallet::constant]
+        type RevealDelay: Get<BlockNumberFor<Self>>;
+
+        /// Si se permite el mint instantáneo (`create_kitty`), cuyo ADN se puede prever.
+        #[pallet::constant]
+        type InstantMint: Get<bool>;
+
+        /// Origen que puede crear kitties con una semilla elegida (`create_kitty_with_seed`) y
+        /// cuenta que los recibe. Pensado para redes de pruebas; en producción, `EnsureNever`.
+        type SeededMintOrigin: EnsureOrigin<Self::RuntimeOrigin, Success = Self::AccountId>;
+
+        /// Pesos de las extrínsecas y de los cierres de `on_initialize` con benchmark (ver
+        /// `weights.rs`).
+        type WeightInfo: WeightInfo;
     }
 
     // Alias para obtener fácilmente el tipo de balance del runtime.
@@ -35,112 +443,2896 @@ pub mod pallet {
     pub type BalanceOf<T> =
         <<T as Config>::NativeBalance as Inspect<<T as frame_system::Config>::AccountId>>::Balance;
 
+    // Identificador y balance de los activos de `Config::Fungibles`.
+    pub type AssetIdOf<T> = <<T as Config>::Fungibles as fungibles::Inspect<
+        <T as frame_system::Config>::AccountId,
+    >>::AssetId;
+    pub type AssetBalanceOf<T> = <<T as Config>::Fungibles as fungibles::Inspect<
+        <T as frame_system::Config>::AccountId,
+    >>::Balance;
+
+    // Identificador de los activos de `Config::KittyTokens`.
+    pub type KittyTokenIdOf<T> = <<T as Config>::KittyTokens as fungibles::Inspect<
+        <T as frame_system::Config>::AccountId,
+    >>::AssetId;
+
+    // ADN (genoma) de un kitty, de longitud `DnaLength`.
+    pub type KittyDna<T> = BoundedVec<u8, <T as Config>::DnaLength>;
+
     // --- Definición de la estructura Kitty ---
+    // El kitty se identifica por 32 bytes (`kitty_id`, la clave de `Kitties`); su genoma vive en
+    // `dna` y tiene la longitud que configure el runtime.
     #[derive(Encode, Decode, TypeInfo, MaxEncodedLen)]
     #[scale_info(skip_type_params(T))]
     pub struct Kitty<T: Config> {
-        pub dna: [u8; 32],              // ADN del kitty (identificador único de 32 bytes)
+        pub dna: KittyDna<T>,           // ADN del kitty (genoma de `DnaLength` bytes)
         pub owner: T::AccountId,        // Dueño actual del kitty
-        pub price: Option<BalanceOf<T>> // Precio actual (None si no está en venta)
+        pub price: Option<BalanceOf<T>>, // Precio actual (None si no está en venta)
+        pub cooldown_until: BlockNumberFor<T>, // Primer bloque en que puede volver a criar
+        pub generation: u32,            // 0 si se creó con `create_kitty`; si no, la de su progenitor mayor + 1
+        pub parents: Option<([u8; 32], [u8; 32])>, // Progenitores, si nació por cría
+        pub rarity: u16,                // Rareza de su genoma (0..=1000), ver `rarity_score`
+        pub special_edition: Option<u16>, // Temporada en la que se creó, si fue edición limitada
     }
 
-    // --- Almacenamientos del pallet ---
-    #[pallet::storage]
-    pub(super) type CountForKitties<T: Config> = StorageValue<Value = u32, QueryKind = ValueQuery>;
-    // Guarda el número total de kitties creados.
-    // QueryKind = ValueQuery indica que si no hay valor, devuelve 0 por defecto.
+    // --- Gestación ---
+    /// Cría en gestación: se guardan los genomas de los progenitores tal como eran al criar; el
+    /// genoma de la cría se decide al reclamarla con `claim_birth`.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct PendingBirth<T: Config> {
+        pub owner: T::AccountId,                          // Quien crió y recibirá la cría
+        pub parents: ([u8; 32], [u8; 32]),                // Progenitores
+        pub parent_dna: (KittyDna<T>, KittyDna<T>),       // Sus genomas al criar
+        pub generation: u32,                              // Generación de la cría
+        pub due_at: BlockNumberFor<T>,                    // Primer bloque en que se puede reclamar
+    }
 
-    #[pallet::storage]
-    pub(super) type Kitties<T: Config> = StorageMap<Key = [u8; 32], Value = Kitty<T>>;
-    // Mapa principal que guarda todos los kitties creados, usando su ADN (kitty_id) como clave.
+    /// Sexo de un kitty. No se guarda: se lee de su genoma con `Pallet::gender_of`.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+    pub enum Gender {
+        Female, // Bit bajo del primer byte del genoma a 0
+        Male,   // Bit bajo del primer byte del genoma a 1
+    }
 
-    #[pallet::storage]
-    pub(super) type KittiesOwned<T: Config> = StorageMap<
-        Key = T::AccountId,
-        Value = BoundedVec<[u8; 32], ConstU32<100>>,
-        QueryKind = ValueQuery,
-    >;
-    // Mapa que almacena los IDs de los kitties propiedad de cada usuario.
-    // Se limita a 100 kitties por usuario (BoundedVec) para evitar abusos o overflows.
+    // --- Fases de mint ---
+    /// Fase de lanzamiento: en `Whitelist` solo las cuentas de `MintAllowlist` pueden crear
+    /// kitties; en `Public`, cualquiera.
+    #[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug)]
+    pub enum MintPhase {
+        Whitelist,  // Solo la lista de permitidos
+        #[default]
+        Public,     // Mint abierto
+    }
 
-    // --- Eventos del pallet ---
-    #[pallet::event]
-    #[pallet::generate_deposit(pub(super) fn deposit_event)]
-    pub enum Event<T: Config> {
-        Created { owner: T::AccountId }, // Emitido cuando se crea un nuevo kitty
-        Transferred {                   // Emitido cuando se transfiere un kitty
-            from: T::AccountId,
-            to: T::AccountId,
-            kitty_id: [u8; 32]
-        },
-        PriceSet {                      // Emitido cuando un dueño pone o quita un precio
-            owner: T::AccountId,
-            kitty_id: [u8; 32],
-            new_price: Option<BalanceOf<T>>
-        },
-        Sold {                          // Emitido cuando se vende un kitty
-            buyer: T::AccountId,
-            kitty_id: [u8; 32],
-            price: BalanceOf<T>
-        },
+    // --- Pausas por clase de llamada ---
+    /// Grupo de llamadas firmadas que gobernanza puede pausar por separado, p. ej. detener el
+    /// mercado sin impedir las transferencias.
+    #[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+    pub enum CallClass {
+        Minting,    // Creación, fusión, regeneración de ADN, vales y pases de mint
+        Breeding,   // Cría, sementales, préstamos de cría y cría remota
+        Transfers,  // Transferencias directas, en dos pasos, regalos y en lote
+        Trading,    // Ventas, compras, lotes, intercambios, ofertas, subastas, rifas y escrow
+        Other,      // Quema, nombres, seguros, stake de capacidad y bloqueos
     }
 
-    // --- Errores posibles del pallet ---
-    #[pallet::error]
-    pub enum Error<T> {
-        TooManyKitties,   // Se excedió el límite total de kitties permitidos
-        DuplicateKitty,   // Ya existe un kitty con ese ADN
-        TooManyOwned,     // El dueño ya posee el máximo de 100 kitties
-        TransferToSelf,   // No se puede transferir un kitty a uno mismo
-        NoKitty,          // El kitty no existe en el mapa
-        NotOwner,         // La cuenta que intenta operar no es el dueño del kitty
-        NotForSale,       // Se intenta comprar un kitty que no está en venta
-        MaxPriceTooLow,   // El precio máximo ofrecido por el comprador es menor al precio de venta
+    /// Filtro para `frame_system::Config::BaseCallFilter`: deja pasar todo salvo las llamadas de
+    /// este pallet cuya clase está en pausa (ver `Pallet::call_class`). Es lo único que aplica
+    /// las pausas a las extrínsecas, así que el runtime tiene que incluirlo (solo o combinado con
+    /// otros filtros); `integrity_test` falla si no está. Las funciones que usan otros pallets y
+    /// la chain extension comprueban las pausas por su cuenta.
+    pub struct PauseFilter<T>(PhantomData<T>);
+
+    impl<T: Config, RuntimeCall: IsSubType<Call<T>>> Contains<RuntimeCall> for PauseFilter<T> {
+        fn contains(call: &RuntimeCall) -> bool {
+            call.is_sub_type().is_none_or(|call| Pallet::<T>::ensure_call_not_paused(call).is_ok())
+        }
     }
 
-    // --- Extrinsics (funciones públicas que pueden llamarse desde fuera del runtime) ---
-    #[pallet::call]
-    impl<T: Config> Pallet<T> {
+    // --- Transferencias en dos pasos ---
+    /// Transferencia ofrecida por `from` que `to` tiene que aceptar antes de `expires_at`.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct PendingTransfer<AccountId, BlockNumber> {
+        pub from: AccountId,            // Dueño que ofrece el kitty
+        pub to: AccountId,              // Única cuenta que puede aceptarlo
+        pub expires_at: BlockNumber,    // Primer bloque en que ya no se puede aceptar
+    }
 
-        /// Crea un nuevo kitty con ADN aleatorio y lo asigna al usuario que ejecuta la transacción.
-        pub fn create_kitty(origin: OriginFor<T>) -> DispatchResult {
-            let who = ensure_signed(origin)?; // Comprueba que la llamada proviene de una cuenta firmada (no root).
-            let dna = Self::gen_dna(); // Genera un ADN aleatorio.
-            Self::mint(who, dna)?; // Crea el kitty y lo asigna al dueño llamando a la función mint() (implementada en impls.rs)
-            Ok(())
+    pub type PendingTransferOf<T> =
+        PendingTransfer<<T as frame_system::Config>::AccountId, BlockNumberFor<T>>;
+
+    // Nota libre de una transferencia, p. ej. la referencia de un exchange o custodio.
+    pub type TransferRemark = BoundedVec<u8, ConstU32<128>>;
+
+    // --- Autorizaciones ---
+    /// Qué puede hacer la cuenta autorizada con `approve` sobre un kitty.
+    #[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+    pub enum ApprovalRole {
+        Transfer,   // Moverlo con `transfer_from`
+        SalesAgent, // Ponerle precio y subastarlo, pero no moverlo (galerías, curadores)
+    }
+
+    /// Autorización de `approve` sobre un kitty. Sin `expires_at` dura hasta que se revoca o el
+    /// kitty cambia de dueño.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct KittyApproval<AccountId, BlockNumber> {
+        pub spender: AccountId,             // Cuenta autorizada
+        pub role: ApprovalRole,             // Qué puede hacer con el kitty
+        pub expires_at: Option<BlockNumber>, // Primer bloque en que ya no vale
+    }
+
+    pub type KittyApprovalOf<T> =
+        KittyApproval<<T as frame_system::Config>::AccountId, BlockNumberFor<T>>;
+
+    // --- Copropiedad ---
+    /// Reparto de un kitty entre varias cuentas: cualquier cambio necesita `threshold`
+    /// aprobaciones de `owners`.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    #[scale_info(skip_type_params(MaxOwners))]
+    pub struct CoOwnership<AccountId, MaxOwners: Get<u32>> {
+        pub owners: BoundedVec<AccountId, MaxOwners>,   // Copropietarios, incluido el dueño
+        pub threshold: u32,                             // Aprobaciones necesarias
+    }
+
+    pub type CoOwnershipOf<T> =
+        CoOwnership<<T as frame_system::Config>::AccountId, <T as Config>::MaxCoOwners>;
+
+    /// Lo que los copropietarios pueden acordar sobre un kitty.
+    #[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, MaxEncodedLen, Clone, PartialEq, Eq, RuntimeDebug)]
+    pub enum CoOwnerAction<AccountId> {
+        Transfer { to: AccountId }, // Entregarlo a `to`; la copropiedad termina
+        Dissolve,                   // Devolverlo al dueño como kitty normal (p. ej. para venderlo)
+    }
+
+    /// Acción propuesta y quién la aprobó hasta ahora.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    #[scale_info(skip_type_params(MaxOwners))]
+    pub struct PendingCoOwnerAction<AccountId, MaxOwners: Get<u32>> {
+        pub action: CoOwnerAction<AccountId>,           // Acción propuesta
+        pub approvals: BoundedVec<AccountId, MaxOwners>, // Copropietarios que la aprobaron
+    }
+
+    pub type PendingCoOwnerActionOf<T> =
+        PendingCoOwnerAction<<T as frame_system::Config>::AccountId, <T as Config>::MaxCoOwners>;
+
+    // --- Alquileres ---
+    /// Condiciones a las que un dueño ofrece su kitty en alquiler.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct RentalListing<Balance, BlockNumber> {
+        pub price_per_block: Balance,   // Precio por bloque, pagado por adelantado
+        pub max_duration: BlockNumber,  // Duración máxima de un alquiler
+        pub collateral: Balance,        // Garantía exigida al inquilino; cero si se paga por adelantado
+    }
+
+    pub type RentalListingOf<T> = RentalListing<BalanceOf<T>, BlockNumberFor<T>>;
+
+    /// Alquiler en curso: `renter` es el usuario del kitty hasta `ends_at`. Si hay garantía,
+    /// `owed` se cobra al terminar y, si el inquilino no puede pagarlo, sale de la garantía.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct Rental<AccountId, Balance, BlockNumber> {
+        pub renter: AccountId,          // Quien usa el kitty (cría, juegos)
+        pub ends_at: BlockNumber,       // Primer bloque en que el kitty vuelve al dueño
+        pub owed: Balance,              // Precio pendiente de pago
+        pub collateral: Balance,        // Garantía retenida al inquilino
+    }
+
+    pub type RentalOf<T> = Rental<<T as frame_system::Config>::AccountId, BalanceOf<T>, BlockNumberFor<T>>;
+
+    // --- Estado de los kitties ---
+    /// En qué está ocupado un kitty. `Idle`, `Listed` y `Breeding` son estados libres: el kitty
+    /// se puede transferir y pasar a cualquier otro estado. Los demás lo inmovilizan hasta que
+    /// el subsistema que lo ocupó lo devuelve a `Idle`.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug)]
+    pub enum KittyStatus {
+        #[default]
+        Idle,       // Sin ocupar
+        Listed,     // A la venta a precio fijo (nativo o en un activo)
+        InAuction,  // En una subasta en curso
+        Breeding,   // Progenitor de una cría en gestación
+        Rented,     // Alquilado
+        Escrowed,   // Guardado en la bóveda de escrow
+        Locked,     // Bloqueado por su dueño
+        InRaffle,   // Rifado
+        InEscrow,   // Vendido con el pago en escrow, pendiente de liquidar
+        InBundle,   // Parte de un lote a la venta
+        Gifted,     // Regalado, pendiente de reclamar
+        CoOwned,    // En copropiedad
+    }
+
+    impl KittyStatus {
+        /// Si el kitty se puede transferir o pasar a otro estado.
+        pub fn is_free(self) -> bool {
+            matches!(self, KittyStatus::Idle | KittyStatus::Listed | KittyStatus::Breeding)
         }
 
-        /// Transfiere un kitty a otra cuenta.
-        pub fn transfer(
-            origin: OriginFor<T>,
-            to: T::AccountId,
-            kitty_id: [u8; 32],
-        ) -> DispatchResult {
-            let who = ensure_signed(origin)?; // Verifica que la transacción esté firmada.
-            Self::do_transfer(who, to, kitty_id)?; // Ejecuta la lógica de transferencia (valida, actualiza almacenamiento, emite evento).
-            Ok(())
+        /// Si se admite el paso de este estado a `next`: siempre se puede volver a `Idle`, y
+        /// desde un estado libre se puede ir a cualquiera.
+        pub fn can_become(self, next: KittyStatus) -> bool {
+            next == KittyStatus::Idle || self.is_free()
         }
+    }
 
-        /// Permite poner un kitty en venta o quitarlo (establecer precio o None).
-        pub fn set_price(
-            origin: OriginFor<T>,
-            kitty_id: [u8; 32],
-            new_price: Option<BalanceOf<T>>,
-        ) -> DispatchResult {
-            let who = ensure_signed(origin)?; // Comprueba que sea una cuenta firmada.
-            Self::do_set_price(who, kitty_id, new_price)?; // Llama a la lógica de negocio para actualizar el precio.
-            Ok(())
+    // --- Lotes ---
+    /// Varios kitties de `seller` a la venta juntos por `price`.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    #[scale_info(skip_type_params(MaxSize))]
+    pub struct Bundle<AccountId, Balance, MaxSize: Get<u32>> {
+        pub seller: AccountId,                          // Dueño de todos los kitties del lote
+        pub kitty_ids: BoundedVec<[u8; 32], MaxSize>,   // Kitties incluidos
+        pub price: Balance,                             // Precio del lote entero
+    }
+
+    pub type BundleOf<T> = Bundle<
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T>,
+        <T as Config>::MaxBundleSize,
+    >;
+
+    // --- Intercambios ---
+    /// Propuesta de cambiar `offered` por `wanted`, con un pago extra opcional del proponente.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct KittySwap<AccountId, Balance, BlockNumber> {
+        pub proposer: AccountId,            // Dueño de `offered`
+        pub offered: [u8; 32],              // Kitty que entrega el proponente
+        pub wanted: [u8; 32],               // Kitty que pide a cambio
+        pub extra_payment: Option<Balance>, // Pago adicional del proponente
+        pub expires_at: BlockNumber,        // Primer bloque en que ya no se puede aceptar
+    }
+
+    pub type KittySwapOf<T> =
+        KittySwap<<T as frame_system::Config>::AccountId, BalanceOf<T>, BlockNumberFor<T>>;
+
+    // --- Ofertas ---
+    /// Oferta de `bidder` por un kitty, válida hasta `expires_at`.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct Offer<AccountId, Balance, BlockNumber> {
+        pub bidder: AccountId,          // Quien oferta
+        pub amount: Balance,            // Importe ofertado
+        pub expires_at: BlockNumber,    // Primer bloque en que ya no se puede aceptar
+    }
+
+    pub type OfferOf<T> =
+        Offer<<T as frame_system::Config>::AccountId, BalanceOf<T>, BlockNumberFor<T>>;
+
+    // --- Subastas ---
+    /// Subasta inglesa de `kitty_id`; `top_bid` es la puja más alta, retenida al pujador.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct Auction<AccountId, Balance, BlockNumber> {
+        pub seller: AccountId,                      // Dueño del kitty subastado
+        pub kitty_id: [u8; 32],                     // Kitty subastado
+        pub min_bid: Balance,                       // Puja mínima aceptada
+        pub buy_now: Option<Balance>,               // Precio de compra inmediata
+        pub end: BlockNumber,                       // Primer bloque sin pujas; se alarga ante pujas tardías
+        pub top_bid: Option<(AccountId, Balance)>,  // Mejor postor e importe
+    }
+
+    pub type AuctionOf<T> =
+        Auction<<T as frame_system::Config>::AccountId, BalanceOf<T>, BlockNumberFor<T>>;
+
+    // --- Estadísticas del mercado ---
+    /// Totales del mercado en token nativo (ver `Pallet::market_stats`).
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, Default, RuntimeDebug)]
+    pub struct MarketStatsInfo<Balance> {
+        pub total_volume: Balance,  // Suma de los precios de todas las ventas
+        pub sales: u64,             // Número de kitties vendidos
+        pub listings: u32,          // Anuncios activos con precio
+    }
+
+    pub type MarketStatsOf<T> = MarketStatsInfo<BalanceOf<T>>;
+
+    // --- Depósitos ---
+    /// Motivos por los que este pallet retiene fondos.
+    #[pallet::composite_enum]
+    pub enum HoldReason {
+        KittyDeposit,   // Depósito de almacenamiento de un kitty
+        ListingDeposit, // Depósito de un kitty puesto a la venta
+        Purchase,       // Pago de una compra mientras se entrega el kitty
+        KittyOffer,     // Importe de una oferta abierta por un kitty
+        AuctionBid,     // Puja más alta de una subasta en curso
+        RentalCollateral, // Garantía de un alquiler pagado al terminar
+        OperatorDeposit,  // Depósito de un operador nombrado con `set_approval_for_all`
+    }
+
+    // --- Temporadas de edición limitada ---
+    /// Ventana de mint de edición limitada. Los kitties creados entre `start` y `end` (ambos
+    /// incluidos), hasta `supply_cap`, llevan `gene_prefix` en los alelos recesivos del gen 0 y
+    /// la temporada en `special_edition`.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct Season<BlockNumber> {
+        pub start: BlockNumber,   // Primer bloque de la temporada
+        pub end: BlockNumber,     // Último bloque de la temporada
+        pub gene_prefix: [u8; 3], // Marca que se escribe en el genoma
+        pub supply_cap: u32,      // Kitties de edición limitada como máximo
+        pub minted: u32,          // Kitties de edición limitada creados hasta ahora
+    }
+
+    // --- Mint en dos fases ---
+    /// Mint pendiente de revelar: el ADN dependerá del hash del bloque `target`, que aún no
+    /// existe al comprometerse, y de la sal secreta cuyo hash es `commitment`.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct MintCommit<BlockNumber> {
+        pub commitment: [u8; 32], // blake2_256 de la sal
+        pub target: BlockNumber,  // Bloque cuyo hash entra en el ADN
+    }
+
+    // --- Atributos visibles ---
+    /// Pelaje de un kitty. Lo decide el alelo dominante del gen 1.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+    pub enum Fur {
+        Short,
+        Long,
+        Curly,
+        Hairless,
+    }
+
+    /// Color de ojos de un kitty. Lo decide el alelo dominante del gen 2.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+    pub enum EyeColor {
+        Amber,
+        Blue,
+        Green,
+        Brown,
+        Hazel,
+        OddEyed, // Un ojo de cada color
+    }
+
+    /// Dibujo del pelaje de un kitty. Lo decide el alelo dominante del gen 3.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+    pub enum Pattern {
+        Solid,
+        Tabby,
+        Spotted,
+        Calico,
+    }
+
+    /// Rasgos de un kitty tal y como se expresan en su genoma. No se guardan: se calculan con
+    /// `Pallet::attributes`, que es la referencia para los front-ends.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+    pub struct KittyAttributes {
+        pub gender: Gender,      // Gen 0
+        pub fur: Fur,            // Gen 1
+        pub eye_color: EyeColor, // Gen 2
+        pub pattern: Pattern,    // Gen 3
+    }
+
+    /// Un rasgo concreto (tipo y valor), clave del índice `KittiesByTrait`.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+    pub enum KittyTrait {
+        Gender(Gender),
+        Fur(Fur),
+        EyeColor(EyeColor),
+        Pattern(Pattern),
+    }
+
+    impl KittyAttributes {
+        /// Tipos de rasgo distintos (y entradas de `KittiesByTrait` por kitty).
+        pub const TRAIT_KINDS: usize = 4;
+
+        /// Los rasgos de estos atributos, uno por tipo.
+        pub fn traits(&self) -> [KittyTrait; Self::TRAIT_KINDS] {
+            [
+                KittyTrait::Gender(self.gender),
+                KittyTrait::Fur(self.fur),
+                KittyTrait::EyeColor(self.eye_color),
+                KittyTrait::Pattern(self.pattern),
+            ]
         }
+    }
 
-        /// Permite comprar un kitty si está en venta y el comprador ofrece suficiente balance.
-        pub fn buy_kitty(
-            origin: OriginFor<T>,
-            kitty_id: [u8; 32],
-            max_price: BalanceOf<T>,
-        ) -> DispatchResult {
-            let who = ensure_signed(origin)?; // Verifica que el comprador sea una cuenta válida.
-            Self::do_buy_kitty(who, kitty_id, max_price)?; // Ejecuta la lógica de compra (valida precio, transfiere fondos, cambia dueño).
-            Ok(())
+    /// Un kitty tal como lo devuelve `runtime_api::KittiesApi`: sus datos, su nombre y su estado,
+    /// con tipos que no dependen de `T`.
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+    pub struct KittyInfo<AccountId, Balance, BlockNumber> {
+        pub id: [u8; 32],                 // Id del kitty
+        pub dna: Vec<u8>,                 // Genoma
+        pub owner: AccountId,             // Dueño actual
+        pub price: Option<Balance>,       // Precio en token nativo, si está a la venta
+        pub cooldown_until: BlockNumber,  // Primer bloque en que puede volver a criar
+        pub generation: u32,              // Generación
+        pub parents: Option<([u8; 32], [u8; 32])>, // Progenitores, si nació por cría
+        pub rarity: u16,                  // Rareza (0..=1000)
+        pub special_edition: Option<u16>, // Temporada de edición limitada, si la tiene
+        pub name: Option<Vec<u8>>,        // Nombre, si tiene
+        pub status: KittyStatus,          // Subsistema que lo ocupa
+    }
+
+    pub type KittyInfoOf<T> =
+        KittyInfo<<T as frame_system::Config>::AccountId, BalanceOf<T>, BlockNumberFor<T>>;
+
+    /// Entrada del historial de un kitty en el índice off-chain. Se guarda codificada junto al
+    /// bloque en que ocurrió: `(bloque, registro)`.
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+    pub enum KittyHistoryRecord<AccountId, Balance> {
+        Minted { owner: AccountId },                  // Se creó para `owner`
+        Transferred { from: AccountId, to: AccountId }, // Cambió de dueño por cualquier vía
+        Sold { seller: AccountId, price: Balance },   // Se vendió en token nativo (el comprador es el `to` del traspaso)
+    }
+
+    pub type KittyHistoryRecordOf<T> = KittyHistoryRecord<<T as frame_system::Config>::AccountId, BalanceOf<T>>;
+
+    /// Posición en una lista paginada. Es opaca: los clientes solo la devuelven tal cual para
+    /// pedir la página siguiente, así que el almacenamiento puede cambiar de forma sin romperlos.
+    /// Guarda el último id devuelto, no un índice, para que quitar kitties de la lista entre dos
+    /// páginas no haga saltarse ninguno.
+    #[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+    pub struct PageCursor(pub(crate) [u8; 32]);
+
+    /// Una página de una lista y el cursor de la siguiente (`None` si es la última).
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, RuntimeDebug)]
+    pub struct KittyPage<Item> {
+        pub items: Vec<Item>,          // Elementos de esta página
+        pub next: Option<PageCursor>,  // Cursor de la página siguiente
+    }
+
+    /// Lo que un renderizador necesita para dibujar un kitty, calculado de su genoma con
+    /// `Pallet::image_seed`. No es la imagen: cualquier renderizador que siga la misma semilla y
+    /// paleta produce el mismo dibujo. No se guarda.
+    #[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+    pub struct KittyImageSeed {
+        pub seed: [u8; 32],          // Semilla de los detalles del dibujo (posición de manchas, etc.)
+        pub attributes: KittyAttributes, // Pelaje, ojos, dibujo y sexo
+        pub body_color: [u8; 3],     // RGB del pelaje (o de la piel, si no tiene pelo)
+        pub accent_color: [u8; 3],   // RGB del dibujo del pelaje
+        pub eye_colors: [[u8; 3]; 2], // RGB de cada ojo (distintos si tiene un ojo de cada color)
+        pub background: [u8; 3],     // RGB del fondo
+    }
+
+    // Nombre de un kitty, acotado por `MaxNameLength`.
+    pub type KittyName<T> = BoundedVec<u8, <T as Config>::MaxNameLength>;
+
+    // Clave y valor de un atributo libre de un kitty (ver `nonfungibles_v2`).
+    pub type AttributeKey<T> = BoundedVec<u8, <T as Config>::MaxAttributeKeyLength>;
+    pub type AttributeValue<T> = BoundedVec<u8, <T as Config>::MaxAttributeValueLength>;
+
+    // --- Pase de temporada para mints ---
+    /// Mints prepagados de una cuenta, válidos hasta `expires_at`. Los mints hechos con el pase no
+    /// pagan tasas de creación.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct MintPass<BlockNumber> {
+        pub remaining: u32,          // Mints que quedan en el pase
+        pub expires_at: BlockNumber, // Último bloque en que se puede usar
+    }
+
+    // --- Préstamo de cría ---
+    /// Derecho de un solo uso que el dueño de un kitty da a otra cuenta para usarlo como
+    /// progenitor en `breed_kitty`, sin transferir la propiedad.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct BreedingLoan<AccountId, Balance, BlockNumber> {
+        pub borrower: AccountId,     // Cuenta que puede usar el kitty para criar
+        pub fee: Balance,            // Lo que paga al dueño en el momento de criar
+        pub expires_at: BlockNumber, // Último bloque en que se puede usar
+    }
+
+    pub type BreedingLoanOf<T> = BreedingLoan<
+        <T as frame_system::Config>::AccountId,
+        BalanceOf<T>,
+        BlockNumberFor<T>,
+    >;
+
+    // --- Seguro de kitties ---
+    /// Póliza de seguro de un kitty: quién la paga y hasta qué bloque está cubierta.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct InsurancePolicy<AccountId, BlockNumber> {
+        pub holder: AccountId,       // Dueño asegurado
+        pub paid_until: BlockNumber, // Último bloque cubierto por las primas pagadas
+    }
+
+    /// Estado de un reclamo al seguro.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub enum ClaimStatus {
+        Pending,  // Esperando al árbitro
+        Approved, // Pagado
+        Rejected, // Denegado
+    }
+
+    /// Reclamo al seguro por la pérdida de un kitty (quemado o confiscado por gobernanza).
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct InsuranceClaim<AccountId, BlockNumber> {
+        pub claimant: AccountId,   // Quien reclama (el asegurado)
+        pub filed_at: BlockNumber, // Bloque en que se presentó
+        pub status: ClaimStatus,   // Estado actual
+    }
+
+    /// Pérdida de un kitty asegurado (quemado o confiscado por gobernanza) pendiente de reclamo.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct InsuredLoss<AccountId, BlockNumber> {
+        pub holder: AccountId,       // Asegurado que perdió el kitty
+        pub paid_until: BlockNumber, // Último bloque cubierto por su póliza
+        pub lost_at: BlockNumber,    // Bloque de la pérdida
+    }
+
+    // --- Rifas ---
+    /// Venta de un kitty por sorteo entre quienes compren boletos.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct Raffle<AccountId, Balance, BlockNumber> {
+        pub seller: AccountId,        // Dueño que rifa el kitty
+        pub ticket_price: Balance,    // Precio de cada boleto
+        pub max_tickets: u32,         // Boletos a la venta
+        pub close_block: BlockNumber, // Bloque a partir del cual se puede cerrar la rifa
+    }
+
+    pub type RaffleOf<T> =
+        Raffle<<T as frame_system::Config>::AccountId, BalanceOf<T>, BlockNumberFor<T>>;
+
+    // --- Reputación de vendedores ---
+    /// Historial de una cuenta como vendedora. `score` se guarda tal como estaba en
+    /// `last_update`; el valor vigente se obtiene con `Pallet::reputation_of`, que aplica el
+    /// decaimiento.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug, Default)]
+    pub struct Reputation<BlockNumber> {
+        pub completed_sales: u32,    // Ventas completadas
+        pub cancelled_listings: u32, // Anuncios retirados antes de vender
+        pub disputes_won: u32,       // Disputas resueltas a su favor
+        pub disputes_lost: u32,      // Disputas resueltas en su contra
+        pub score: i32,              // Puntuación en `last_update`
+        pub last_update: BlockNumber, // Bloque de la última actualización
+    }
+
+    /// Hechos que afectan la reputación de un vendedor.
+    #[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+    pub enum ReputationEvent {
+        Sale,             // Venta completada
+        ListingCancelled, // Anuncio retirado sin vender
+        DisputeWon,       // Disputa resuelta a su favor
+        DisputeLost,      // Disputa resuelta en su contra
+    }
+
+    impl ReputationEvent {
+        /// Puntos que suma (o resta) cada hecho.
+        pub fn points(self) -> i32 {
+            match self {
+                ReputationEvent::Sale => 10,
+                ReputationEvent::ListingCancelled => -2,
+                ReputationEvent::DisputeWon => 5,
+                ReputationEvent::DisputeLost => -25,
+            }
+        }
+    }
+
+    // --- Cría entre cadenas ---
+    /// Envío de recibos de cría a la parachain hermana que aportó la pareja remota.
+    pub trait BreedingReceipts {
+        /// Notifica a `para_id` que su kitty `partner_id` tuvo la cría local `child_id`.
+        fn send_receipt(para_id: u32, partner_id: [u8; 32], child_id: [u8; 32]) -> DispatchResult;
+    }
+
+    /// Implementación vacía para runtimes sin XCM: no envía nada.
+    impl BreedingReceipts for () {
+        fn send_receipt(_: u32, _: [u8; 32], _: [u8; 32]) -> DispatchResult {
+            Ok(())
+        }
+    }
+
+    // --- Avisos al runtime ---
+    /// Avisos de los cambios de un kitty, para que el runtime enganche estadísticas, logros o
+    /// la lógica de otros pallets. Se llaman después del cambio y no pueden hacerlo fallar.
+    pub trait OnKittyMinted<AccountId> {
+        /// `kitty_id` acaba de crearse para `owner`.
+        fn on_kitty_minted(owner: &AccountId, kitty_id: [u8; 32]);
+    }
+
+    pub trait OnKittyTransferred<AccountId> {
+        /// `kitty_id` acaba de pasar de `from` a `to`.
+        fn on_kitty_transferred(from: &AccountId, to: &AccountId, kitty_id: [u8; 32]);
+    }
+
+    pub trait OnKittyBurned<AccountId> {
+        /// `kitty_id`, de `owner`, acaba de destruirse.
+        fn on_kitty_burned(owner: &AccountId, kitty_id: [u8; 32]);
+    }
+
+    /// Implementaciones vacías para runtimes que no necesitan los avisos.
+    impl<AccountId> OnKittyMinted<AccountId> for () {
+        fn on_kitty_minted(_: &AccountId, _: [u8; 32]) {}
+    }
+
+    impl<AccountId> OnKittyTransferred<AccountId> for () {
+        fn on_kitty_transferred(_: &AccountId, _: &AccountId, _: [u8; 32]) {}
+    }
+
+    impl<AccountId> OnKittyBurned<AccountId> for () {
+        fn on_kitty_burned(_: &AccountId, _: [u8; 32]) {}
+    }
+
+    // --- Interfaz para otros pallets ---
+    /// Lo que otros pallets del runtime (juegos, préstamos...) necesitan de los kitties, sin
+    /// depender de los tipos ni del almacenamiento de este pallet.
+    pub trait KittiesProvider<AccountId, Balance> {
+        /// Crea un kitty con ADN aleatorio para `who`, sin tasas de creación, y devuelve su id.
+        fn mint_to(who: &AccountId) -> Result<[u8; 32], DispatchError>;
+        /// Transfiere `kitty_id` de `from` a `to` con las mismas reglas que `transfer`.
+        fn transfer(from: &AccountId, to: &AccountId, kitty_id: [u8; 32]) -> DispatchResult;
+        /// Dueño de `kitty_id`, si existe.
+        fn owner_of(kitty_id: [u8; 32]) -> Option<AccountId>;
+        /// Precio de venta de `kitty_id` en token nativo, si está a la venta.
+        fn price_of(kitty_id: [u8; 32]) -> Option<Balance>;
+    }
+
+    // --- Kitties en otras cadenas ---
+    /// Envío de kitties a parachains hermanas. El kitty se queda en reserva en esta cadena
+    /// (`Pallet::xcm_reserve_account`) y la parachain de destino acuña un derivado.
+    pub trait KittyTransport {
+        /// Pide a `para_id` que acuñe el derivado de `kitty_id`, con genoma `dna`, para la
+        /// cuenta `beneficiary` de esa cadena.
+        fn send_kitty(para_id: u32, kitty_id: [u8; 32], dna: &[u8], beneficiary: [u8; 32]) -> DispatchResult;
+    }
+
+    /// Implementación para runtimes sin XCM: no se puede enviar ningún kitty.
+    impl KittyTransport for () {
+        fn send_kitty(_: u32, _: [u8; 32], _: &[u8], _: [u8; 32]) -> DispatchResult {
+            Err(frame::deps::sp_runtime::TokenError::Unsupported.into())
+        }
+    }
+
+    // --- Puente con Ethereum ---
+    /// Canal de salida del puente con Ethereum. El kitty se queda en custodia en esta cadena
+    /// (`Pallet::eth_bridge_account`) y el contrato ERC-721 del otro lado acuña el token.
+    pub trait EthereumOutbound {
+        /// Encola `payload`, un mensaje ABI de `eth_bridge`, para su entrega en Ethereum.
+        fn send_message(payload: &[u8]) -> DispatchResult;
+    }
+
+    /// Implementación para runtimes sin puente: no se puede exportar ningún kitty.
+    impl EthereumOutbound for () {
+        fn send_message(_: &[u8]) -> DispatchResult {
+            Err(frame::deps::sp_runtime::TokenError::Unsupported.into())
+        }
+    }
+
+    // --- Aleatoriedad ---
+    /// Fuente de aleatoriedad por defecto: el hash del bloque anterior mezclado con el `subject`.
+    /// Es el esquema que usaba el pallet antes de `Config::Randomness`. Cualquiera puede
+    /// predecirla, así que solo vale para desarrollo y pruebas; en producción se usa BABE/VRF.
+    pub struct BlockHashRandomness<T>(PhantomData<T>);
+
+    impl<T: frame_system::Config> Randomness<T::Hash, BlockNumberFor<T>> for BlockHashRandomness<T> {
+        fn random(subject: &[u8]) -> (T::Hash, BlockNumberFor<T>) {
+            let parent_hash = frame_system::Pallet::<T>::parent_hash();
+            let block_number = frame_system::Pallet::<T>::block_number();
+            let seed = <T::Hashing as frame::traits::Hash>::hash_of(&(subject, parent_hash, block_number));
+            // El resultado se conoce desde que se conoce el bloque anterior.
+            (seed, block_number.saturating_sub(One::one()))
+        }
+    }
+
+    // --- Tasaciones ---
+    /// A qué se refiere una tasación: a un kitty concreto o a todo un nivel de rareza.
+    #[derive(Encode, Decode, DecodeWithMemTracking, TypeInfo, MaxEncodedLen, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+    pub enum AppraisalTarget {
+        Kitty([u8; 32]), // Valoración de un kitty
+        Tier(u8),        // Valoración de referencia de un nivel de rareza
+    }
+
+    /// Última valoración publicada por el oráculo y el bloque en que se publicó.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct Appraisal<Balance, BlockNumber> {
+        pub value: Balance,          // Valor estimado
+        pub updated_at: BlockNumber, // Bloque de publicación
+    }
+
+    pub type AppraisalOf<T> = Appraisal<BalanceOf<T>, BlockNumberFor<T>>;
+
+    // --- Ventas con escrow ---
+    /// Estado de una venta con escrow.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub enum EscrowStatus<BlockNumber> {
+        Open,                               // Esperando a que se libere el pago
+        Disputed { deadline: BlockNumber }, // Congelada hasta que el árbitro decida
+    }
+
+    /// Venta cuyo pago queda retenido hasta `release_at`. Mientras tanto el kitty sigue con el
+    /// vendedor, bloqueado, y cualquiera de las partes puede abrir una disputa.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen, Clone, PartialEq, RuntimeDebug)]
+    pub struct EscrowedSale<AccountId, Balance, BlockNumber> {
+        pub kitty_id: [u8; 32],                 // Kitty vendido
+        pub seller: AccountId,                  // Vendedor
+        pub buyer: AccountId,                   // Comprador
+        pub price: Balance,                     // Importe retenido
+        pub release_at: BlockNumber,            // Bloque desde el que se puede liquidar
+        pub status: EscrowStatus<BlockNumber>,  // Abierta o en disputa
+    }
+
+    pub type EscrowedSaleOf<T> =
+        EscrowedSale<<T as frame_system::Config>::AccountId, BalanceOf<T>, BlockNumberFor<T>>;
+
+    // Entrada de un snapshot exportado: (dueño, ADN, precio opcional).
+    pub type KittySnapshotOf<T> =
+        (<T as frame_system::Config>::AccountId, [u8; 32], Option<BalanceOf<T>>);
+
+    // --- Almacenamientos del pallet ---
+    #[pallet::storage]
+    pub(super) type CountForKitties<T: Config> = StorageValue<Value = u32, QueryKind = ValueQuery>;
+    // Guarda el número total de kitties creados.
+    // QueryKind = ValueQuery indica que si no hay valor, devuelve 0 por defecto.
+
+    #[pallet::storage]
+    pub(super) type MaxTotalKitties<T: Config> = StorageValue<Value = u32, QueryKind = OptionQuery>;
+    // Máximo de kitties que pueden existir a la vez; sin valor no hay más límite que `u32`.
+
+    #[pallet::storage]
+    pub(super) type CurrentMintPhase<T: Config> = StorageValue<Value = MintPhase, QueryKind = ValueQuery>;
+    // Fase de mint en curso; por defecto `Public`.
+
+    #[pallet::storage]
+    pub(super) type Paused<T: Config> = StorageValue<Value = bool, QueryKind = ValueQuery>;
+    // Pausa de emergencia: mientras es `true` se rechaza toda llamada firmada.
+
+    #[pallet::storage]
+    pub(super) type PausedCalls<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = CallClass, Value = ()>;
+    // Clases de llamada en pausa, independientes de la pausa global.
+
+    #[pallet::storage]
+    pub(super) type Blacklist<T: Config> =
+        StorageMap<Hasher = Blake2_128Concat, Key = T::AccountId, Value = ()>;
+    // Cuentas que no pueden anunciar, comprar, pujar ni recibir kitties.
+
+    #[pallet::storage]
+    pub(super) type MintAllowlist<T: Config> =
+        StorageMap<Hasher = Blake2_128Concat, Key = T::AccountId, Value = ()>;
+    // Cuentas que pueden crear kitties durante la fase `Whitelist`.
+
+    #[pallet::storage]
+    pub(super) type Kitties<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = Kitty<T>>;
+    // Mapa principal que guarda todos los kitties creados, usando su ADN (kitty_id) como clave.
+    // El ADN ya es la salida de un hash, así que basta `Twox64Concat`: claves más cortas (menos
+    // PoV) y la clave original sigue recuperable para iterar.
+
+    #[pallet::storage]
+    pub(super) type KittiesOwned<T: Config> = StorageMap<
+        Hasher = Blake2_128Concat,
+        Key = T::AccountId,
+        Value = BoundedVec<[u8; 32], T::MaxKittiesOwned>,
+        QueryKind = ValueQuery,
+    >;
+    // Mapa que almacena los IDs de los kitties propiedad de cada usuario.
+    // Se limita a `MaxKittiesOwned` (BoundedVec) para evitar abusos o overflows; el límite
+    // efectivo de cada cuenta lo da `max_kitties_for()`.
+    // Las cuentas las elige el usuario, por eso se usa un hasher criptográfico (`Blake2_128Concat`).
+
+    #[pallet::storage]
+    pub(super) type KittyNames<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = KittyName<T>>;
+    // Nombre actual de cada kitty (si tiene).
+
+    #[pallet::storage]
+    pub(super) type KittyByName<T: Config> =
+        StorageMap<Hasher = Blake2_128Concat, Key = KittyName<T>, Value = [u8; 32]>;
+    // Índice inverso nombre -> kitty, para que cada nombre solo lo pueda usar un kitty.
+
+    #[pallet::storage]
+    pub(super) type RenamesInEra<T: Config> = StorageMap<
+        Hasher = Blake2_128Concat,
+        Key = T::AccountId,
+        Value = (BlockNumberFor<T>, u32),
+        QueryKind = ValueQuery,
+    >;
+    // Renombres hechos por cada cuenta: (era, cantidad). Se reinicia al cambiar de era.
+
+    #[pallet::storage]
+    pub(super) type MintsInPeriod<T: Config> = StorageDoubleMap<
+        Hasher1 = Blake2_128Concat,
+        Key1 = T::AccountId,
+        Hasher2 = Twox64Concat,
+        Key2 = BlockNumberFor<T>,
+        Value = u32,
+        QueryKind = ValueQuery,
+    >;
+    // Kitties creados por cada cuenta en cada periodo de cuota. Solo se guarda el último periodo.
+
+    #[pallet::storage]
+    pub(super) type Vouchers<T: Config> =
+        StorageMap<Hasher = Identity, Key = [u8; 32], Value = T::AccountId>;
+    // Vales regalo pendientes: hash del código de canje -> cuenta que lo compró.
+    // La clave ya es un hash, por eso basta `Identity`.
+
+    #[pallet::storage]
+    pub(super) type MintPasses<T: Config> = StorageMap<
+        Hasher = Blake2_128Concat,
+        Key = T::AccountId,
+        Value = MintPass<BlockNumberFor<T>>,
+    >;
+    // Pase de temporada activo de cada cuenta (si tiene).
+
+    #[pallet::storage]
+    pub(super) type BreedingLoans<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = BreedingLoanOf<T>>;
+    // Préstamo de cría vigente de cada kitty (como mucho uno por kitty).
+
+    #[pallet::storage]
+    pub(super) type InsurancePolicies<T: Config> = StorageMap<
+        Hasher = Twox64Concat,
+        Key = [u8; 32],
+        Value = InsurancePolicy<T::AccountId, BlockNumberFor<T>>,
+    >;
+    // Póliza vigente de cada kitty asegurado.
+
+    #[pallet::storage]
+    pub(super) type InsuranceClaims<T: Config> = StorageMap<
+        Hasher = Twox64Concat,
+        Key = [u8; 32],
+        Value = InsuranceClaim<T::AccountId, BlockNumberFor<T>>,
+    >;
+    // Reclamos presentados por kitty; se conservan tras resolverse como registro.
+
+    #[pallet::storage]
+    pub(super) type InsuredLosses<T: Config> = StorageMap<
+        Hasher = Twox64Concat,
+        Key = [u8; 32],
+        Value = InsuredLoss<T::AccountId, BlockNumberFor<T>>,
+    >;
+    // Kitties con póliza que gobernanza quemó o confiscó; la pérdida se consume con `file_claim`.
+
+    #[pallet::storage]
+    pub(super) type Raffles<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = RaffleOf<T>>;
+    // Rifas abiertas, por kitty rifado.
+
+    #[pallet::storage]
+    pub(super) type RaffleTickets<T: Config> = StorageMap<
+        Hasher = Twox64Concat,
+        Key = [u8; 32],
+        Value = BoundedVec<T::AccountId, T::MaxRaffleTickets>,
+        QueryKind = ValueQuery,
+    >;
+    // Boletos vendidos de cada rifa: una entrada por boleto con la cuenta que lo compró.
+
+    #[pallet::storage]
+    pub(super) type Reputations<T: Config> = StorageMap<
+        Hasher = Blake2_128Concat,
+        Key = T::AccountId,
+        Value = Reputation<BlockNumberFor<T>>,
+        QueryKind = ValueQuery,
+    >;
+    // Reputación de cada cuenta que vendió o anunció kitties.
+
+    #[pallet::storage]
+    pub(super) type NextSaleId<T: Config> = StorageValue<Value = u32, QueryKind = ValueQuery>;
+    // Identificador que recibirá la próxima venta con escrow.
+
+    #[pallet::storage]
+    pub(super) type EscrowedSales<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = u32, Value = EscrowedSaleOf<T>>;
+    // Ventas con escrow pendientes de liquidar.
+
+    #[pallet::storage]
+    pub(super) type KittyEscrowSale<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = u32>;
+    // Índice kitty -> venta con escrow pendiente (bloquea el kitty).
+
+    #[pallet::storage]
+    pub(super) type Appraisals<T: Config> =
+        StorageMap<Hasher = Blake2_128Concat, Key = AppraisalTarget, Value = AppraisalOf<T>>;
+    // Última tasación publicada por kitty o por nivel de rareza.
+
+    #[pallet::storage]
+    pub(super) type RemoteBreedingOffers<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = u32>;
+    // Kitties ofrecidos para criar con una pareja de la parachain indicada.
+
+    #[pallet::storage]
+    pub(super) type CapacityStakes<T: Config> =
+        StorageMap<Hasher = Blake2_128Concat, Key = T::AccountId, Value = BalanceOf<T>, QueryKind = ValueQuery>;
+    // Stake de cada cuenta para ampliar su límite de kitties.
+
+    #[pallet::storage]
+    pub(super) type CollectorPasses<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = ()>;
+    // Kitties que funcionan como pase de coleccionista.
+
+    #[pallet::storage]
+    pub(super) type NextPendingBirthId<T: Config> = StorageValue<Value = u32, QueryKind = ValueQuery>;
+    // Identificador que recibirá la próxima cría en gestación.
+
+    #[pallet::storage]
+    pub(super) type PendingBirths<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = u32, Value = PendingBirth<T>>;
+    // Crías en gestación pendientes de `claim_birth`.
+
+    #[pallet::storage]
+    pub(super) type StudListings<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = BalanceOf<T>>;
+    // Kitties anunciados como sementales y la tarifa que cobra su dueño por cada cría.
+
+    #[pallet::storage]
+    pub(super) type RerollCount<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = u32, QueryKind = ValueQuery>;
+    // Veces que se ha regenerado el ADN de cada kitty (fija el precio del siguiente).
+
+    #[pallet::storage]
+    pub(super) type RarityTierCounts<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = u8, Value = u32, QueryKind = ValueQuery>;
+    // Cuántos kitties hay en cada nivel de rareza (ver `rarity_tier`).
+
+    #[pallet::storage]
+    pub(super) type KittiesByTrait<T: Config> = StorageDoubleMap<
+        Hasher1 = Blake2_128Concat,
+        Key1 = KittyTrait,
+        Hasher2 = Twox64Concat,
+        Key2 = [u8; 32],
+        Value = (),
+        QueryKind = OptionQuery,
+    >;
+    // Índice de kitties por rasgo: `iter_key_prefix(rasgo)` da todos los que lo tienen.
+
+    #[pallet::storage]
+    pub(super) type NextSeasonId<T: Config> = StorageValue<Value = u16, QueryKind = ValueQuery>;
+    // Identificador que recibirá la siguiente temporada.
+
+    #[pallet::storage]
+    pub(super) type Seasons<T: Config> = StorageMap<
+        Hasher = Twox64Concat,
+        Key = u16,
+        Value = Season<BlockNumberFor<T>>,
+        QueryKind = OptionQuery,
+    >;
+    // Todas las temporadas programadas, con cuántos kitties se crearon en cada una.
+
+    #[pallet::storage]
+    pub(super) type CurrentSeason<T: Config> = StorageValue<Value = u16, QueryKind = OptionQuery>;
+    // Última temporada programada; no se puede programar otra hasta que termine.
+
+    #[pallet::storage]
+    pub(super) type MintCommits<T: Config> = StorageMap<
+        Hasher = Blake2_128Concat,
+        Key = T::AccountId,
+        Value = MintCommit<BlockNumberFor<T>>,
+        QueryKind = OptionQuery,
+    >;
+    // Mint comprometido y sin revelar de cada cuenta (como mucho uno).
+
+    #[pallet::storage]
+    pub(super) type KittyDeposits<T: Config> = StorageMap<
+        Hasher = Twox64Concat,
+        Key = [u8; 32],
+        Value = (T::AccountId, BalanceOf<T>),
+        QueryKind = OptionQuery,
+    >;
+    // Quién pagó el depósito de cada kitty y cuánto. Sigue con esa cuenta aunque el kitty cambie
+    // de dueño: se le devuelve a ella al destruirlo.
+
+    #[pallet::storage]
+    pub(super) type KittyMinters<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = T::AccountId>;
+    // Cuenta que creó o crió cada kitty; cobra las regalías de sus reventas.
+
+    #[pallet::storage]
+    pub(super) type ListingDeposits<T: Config> = StorageMap<
+        Hasher = Twox64Concat,
+        Key = [u8; 32],
+        Value = (T::AccountId, BalanceOf<T>),
+        QueryKind = OptionQuery,
+    >;
+    // Depósito de cada kitty a la venta: quién lo pagó y cuánto.
+
+    #[pallet::storage]
+    pub(super) type AssetListings<T: Config> = StorageMap<
+        Hasher = Twox64Concat,
+        Key = [u8; 32],
+        Value = (AssetIdOf<T>, AssetBalanceOf<T>),
+        QueryKind = OptionQuery,
+    >;
+    // Kitties a la venta en un activo distinto del nativo: activo y precio. Un kitty está a la
+    // venta en el token nativo (`Kitty::price`) o aquí, nunca en los dos.
+
+    #[pallet::storage]
+    pub(super) type PendingTransfers<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = PendingTransferOf<T>>;
+    // Transferencias ofrecidas pendientes de aceptar. El kitty no se bloquea mientras tanto.
+
+    #[pallet::storage]
+    pub(super) type KittyApprovals<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = KittyApprovalOf<T>>;
+    // Cuenta autorizada sobre cada kitty (una por kitty, como en ERC-721) y su papel. Se borra
+    // cuando el kitty cambia de dueño o, ya caducada, con `clear_expired_approval`.
+
+    #[pallet::storage]
+    pub(super) type OperatorApprovals<T: Config> = StorageDoubleMap<
+        Hasher1 = Blake2_128Concat,
+        Key1 = T::AccountId,
+        Hasher2 = Blake2_128Concat,
+        Key2 = T::AccountId,
+        Value = Option<BlockNumberFor<T>>,
+    >;
+    // Operadores (segunda clave) que gestionan todos los kitties de un dueño (primera clave):
+    // transferirlos, ponerles precio y subastarlos. El valor es el bloque en que caduca, si lo hay.
+
+    #[pallet::storage]
+    pub(super) type OperatorDeposits<T: Config> = StorageDoubleMap<
+        Hasher1 = Blake2_128Concat,
+        Key1 = T::AccountId,
+        Hasher2 = Blake2_128Concat,
+        Key2 = T::AccountId,
+        Value = BalanceOf<T>,
+    >;
+    // Depósito retenido al dueño por cada operador de `OperatorApprovals`. Las delegaciones de
+    // antes de que existiera el depósito no tienen entrada.
+
+    #[pallet::storage]
+    pub(super) type EscrowVault<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = T::AccountId>;
+    // Dueño real de cada kitty guardado en la bóveda (`Pallet::escrow_vault_account`).
+
+    #[pallet::storage]
+    pub(super) type KittiesAbroad<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = u32>;
+    // Parachain que tiene el derivado de cada kitty en reserva (`Pallet::xcm_reserve_account`).
+
+    #[pallet::storage]
+    pub(super) type ForeignKitties<T: Config> = StorageDoubleMap<
+        Hasher1 = Twox64Concat,
+        Key1 = u32,
+        Hasher2 = Twox64Concat,
+        Key2 = [u8; 32],
+        Value = [u8; 32],
+    >;
+    // Kitty local que envuelve cada NFT llegado de otra cadena, por (parachain, id de origen).
+
+    #[pallet::storage]
+    pub(super) type KittyProvenance<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = (u32, [u8; 32])>;
+    // Parachain e id de origen de cada kitty envuelto (inverso de `ForeignKitties`).
+
+    #[pallet::storage]
+    pub(super) type WrappedKitties<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = KittyTokenIdOf<T>>;
+    // Activo de `KittyTokens` cuya única unidad representa cada kitty envuelto
+    // (`Pallet::token_wrapper_account`).
+
+    #[pallet::storage]
+    pub(super) type KittiesOnEthereum<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = [u8; 20]>;
+    // Dirección de Ethereum a la que se exportó cada kitty en custodia (`Pallet::eth_bridge_account`).
+
+    #[pallet::storage]
+    pub(super) type EthOutboundNonce<T> = StorageValue<Value = u64, QueryKind = ValueQuery>;
+    // Nonce del último mensaje enviado a Ethereum.
+
+    #[pallet::storage]
+    pub(super) type EthInboundNonce<T> = StorageValue<Value = u64, QueryKind = ValueQuery>;
+    // Nonce del último mensaje de Ethereum finalizado; los mensajes llegan en orden.
+
+    #[pallet::storage]
+    pub(super) type ParkedImports<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = T::AccountId>;
+    // Destinatario de cada kitty que volvió de Ethereum y sigue en custodia porque no pudo
+    // recibirlo; lo reclama con `claim_parked_import`.
+
+    #[pallet::storage]
+    pub(super) type CoOwners<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = CoOwnershipOf<T>>;
+    // Kitties en copropiedad; el dueño no puede moverlos ni venderlos por su cuenta.
+
+    #[pallet::storage]
+    pub(super) type NextCoOwnerActionId<T: Config> = StorageValue<Value = u32, QueryKind = ValueQuery>;
+    // Identificador que recibirá la siguiente acción de copropietarios.
+
+    #[pallet::storage]
+    pub(super) type CoOwnerActions<T: Config> = StorageDoubleMap<
+        Hasher1 = Twox64Concat,
+        Key1 = [u8; 32],
+        Hasher2 = Twox64Concat,
+        Key2 = u32,
+        Value = PendingCoOwnerActionOf<T>,
+    >;
+    // Acciones propuestas por kitty, pendientes de reunir aprobaciones.
+
+    #[pallet::storage]
+    pub(super) type RentalListings<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = RentalListingOf<T>>;
+    // Kitties que se pueden alquilar y a qué precio.
+
+    #[pallet::storage]
+    pub(super) type Rentals<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = RentalOf<T>>;
+    // Alquiler en curso de cada kitty. `on_initialize` lo borra en `ends_at`.
+
+    #[pallet::storage]
+    pub(super) type RentalsEndingAt<T: Config> = StorageMap<
+        Hasher = Twox64Concat,
+        Key = BlockNumberFor<T>,
+        Value = BoundedVec<[u8; 32], T::MaxRentalsPerBlock>,
+        QueryKind = ValueQuery,
+    >;
+    // Alquileres que terminan en cada bloque, para cerrarlos en `on_initialize`.
+
+    #[pallet::storage]
+    pub(super) type RentalEndRequests<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = T::AccountId>;
+    // Parte (dueño o inquilino) que ya pidió terminar antes de tiempo el alquiler de cada kitty.
+
+    #[pallet::storage]
+    pub(super) type KittyStatuses<T: Config> = StorageMap<
+        Hasher = Twox64Concat,
+        Key = [u8; 32],
+        Value = KittyStatus,
+        QueryKind = ValueQuery,
+    >;
+    // Estado de cada kitty. Los kitties en `Idle` no se guardan.
+
+    #[pallet::storage]
+    pub(super) type ItemAttributes<T: Config> = StorageDoubleMap<
+        Hasher1 = Twox64Concat,
+        Key1 = [u8; 32],
+        Hasher2 = Blake2_128Concat,
+        Key2 = AttributeKey<T>,
+        Value = AttributeValue<T>,
+    >;
+    // Atributos clave/valor de cada kitty, escritos por los traits `nonfungibles_v2`.
+
+    #[pallet::storage]
+    pub(super) type Gifts<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = PendingTransferOf<T>>;
+    // Regalos sin reclamar; el kitty queda bloqueado con el remitente hasta que se reclama o caduca.
+
+    #[pallet::storage]
+    pub(super) type GiftsExpiringAt<T: Config> = StorageMap<
+        Hasher = Twox64Concat,
+        Key = BlockNumberFor<T>,
+        Value = BoundedVec<[u8; 32], T::MaxGiftsPerBlock>,
+        QueryKind = ValueQuery,
+    >;
+    // Regalos que caducan en cada bloque, para devolverlos en `on_initialize`.
+
+    #[pallet::storage]
+    pub(super) type ReservedBuyers<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = T::AccountId>;
+    // Único comprador permitido de un kitty en venta privada.
+
+    #[pallet::storage]
+    pub(super) type NextBundleId<T: Config> = StorageValue<Value = u32, QueryKind = ValueQuery>;
+    // Identificador que recibirá el siguiente lote.
+
+    #[pallet::storage]
+    pub(super) type Bundles<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = u32, Value = BundleOf<T>>;
+    // Lotes a la venta.
+
+    #[pallet::storage]
+    pub(super) type KittyBundle<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = u32>;
+    // Lote en el que está cada kitty; bloquea sus ventas y transferencias sueltas.
+
+    #[pallet::storage]
+    pub(super) type NextSwapId<T: Config> = StorageValue<Value = u32, QueryKind = ValueQuery>;
+    // Identificador que recibirá el siguiente intercambio.
+
+    #[pallet::storage]
+    pub(super) type Swaps<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = u32, Value = KittySwapOf<T>>;
+    // Intercambios pendientes de aceptar.
+
+    #[pallet::storage]
+    pub(super) type NextOfferId<T: Config> = StorageValue<Value = u32, QueryKind = ValueQuery>;
+    // Identificador que recibirá la siguiente oferta.
+
+    #[pallet::storage]
+    pub(super) type Offers<T: Config> = StorageDoubleMap<
+        Hasher1 = Blake2_128Concat,
+        Key1 = [u8; 32],
+        Hasher2 = Twox64Concat,
+        Key2 = u32,
+        Value = OfferOf<T>,
+    >;
+    // Ofertas abiertas por kitty.
+
+    #[pallet::storage]
+    pub(super) type NextAuctionId<T: Config> = StorageValue<Value = u32, QueryKind = ValueQuery>;
+    // Identificador que recibirá la siguiente subasta.
+
+    #[pallet::storage]
+    pub(super) type Auctions<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = u32, Value = AuctionOf<T>>;
+    // Subastas sin liquidar.
+
+    #[pallet::storage]
+    pub(super) type KittyAuction<T: Config> =
+        StorageMap<Hasher = Twox64Concat, Key = [u8; 32], Value = u32>;
+    // Subasta en la que está cada kitty; bloquea sus ventas y transferencias.
+
+    #[pallet::storage]
+    pub(super) type AuctionsEndingAt<T: Config> = StorageMap<
+        Hasher = Twox64Concat,
+        Key = BlockNumberFor<T>,
+        Value = BoundedVec<u32, T::MaxAuctionsPerBlock>,
+        QueryKind = ValueQuery,
+    >;
+    // Subastas que terminan en cada bloque, para liquidarlas en `on_initialize`.
+
+    #[pallet::storage]
+    pub(super) type MarketStats<T: Config> =
+        StorageValue<Value = MarketStatsOf<T>, QueryKind = ValueQuery>;
+    // Volumen, ventas y anuncios activos del mercado.
+
+    #[pallet::storage]
+    pub(super) type ListingsByPrice<T: Config> = StorageDoubleMap<
+        Hasher1 = Identity,
+        Key1 = [u8; 16],
+        Hasher2 = Blake2_128Concat,
+        Key2 = [u8; 32],
+        Value = BalanceOf<T>,
+    >;
+    // Anuncios activos y su precio. La primera clave es el precio sin hash (`Pallet::listing_key`),
+    // así que el mapa se recorre del anuncio más barato al más caro.
+
+    #[pallet::storage]
+    pub(super) type FloorPrice<T: Config> = StorageValue<Value = BalanceOf<T>, QueryKind = OptionQuery>;
+    // Precio del anuncio activo más barato.
+
+    #[pallet::storage]
+    pub(super) type PriceHistory<T: Config> = StorageMap<
+        Hasher = Twox64Concat,
+        Key = [u8; 32],
+        Value = BoundedVec<BalanceOf<T>, T::PriceHistoryLength>,
+        QueryKind = ValueQuery,
+    >;
+    // Últimos precios de venta de cada kitty, del más antiguo al más reciente.
+
+    // --- Configuración de génesis ---
+    /// Permite arrancar la cadena con kitties importados de otra cadena o de un despliegue anterior.
+    ///
+    /// Los kitties pueden venir como lista legible (JSON en el chain spec) o como un blob SCALE
+    /// (`snapshot`) que codifica un `Vec<KittySnapshotOf<T>>`. Ambos se validan igual que un mint
+    /// normal: ADN único y capacidad del dueño.
+    #[pallet::genesis_config]
+    #[derive(DefaultNoBound)]
+    pub struct GenesisConfig<T: Config> {
+        pub kitties: Vec<KittySnapshotOf<T>>, // Kitties en formato legible (owner, dna, price)
+        pub snapshot: Vec<u8>,                // Blob SCALE opcional con más kitties
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
+        fn build(&self) {
+            // Primero la lista legible y luego el blob, en el orden en que fueron exportados.
+            let from_blob = if self.snapshot.is_empty() {
+                Vec::new()
+            } else {
+                Vec::<KittySnapshotOf<T>>::decode(&mut &self.snapshot[..])
+                    .expect("el snapshot de kitties no es SCALE válido")
+            };
+            for entry in self.kitties.iter().cloned().chain(from_blob) {
+                Pallet::<T>::import_snapshot_kitty(entry).expect("kitty inválido en el snapshot de génesis");
+            }
+        }
+    }
+
+    // --- Hooks ---
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: BlockNumberFor<T>) -> Weight {
+            // Liquida las subastas que terminan en este bloque (como mucho `MaxAuctionsPerBlock`),
+            // devuelve los regalos que caducan en él (como mucho `MaxGiftsPerBlock`) y cierra los
+            // alquileres que vencen (como mucho `MaxRentalsPerBlock`).
+            Self::settle_ending_auctions(now)
+                .saturating_add(Self::reclaim_expired_gifts(now))
+                .saturating_add(Self::end_expired_rentals(now))
+        }
+
+        fn integrity_test() {
+            // Las extrínsecas no comprueban las pausas por su cuenta: sin `PauseFilter` en
+            // `BaseCallFilter`, `Paused` y `PausedCalls` no detendrían ninguna llamada firmada.
+            Paused::<T>::put(true);
+            let call: <T as Config>::RuntimeCall = Call::<T>::burn_kitty { kitty_id: [0; 32] }.into();
+            assert!(
+                !<T as frame_system::Config>::BaseCallFilter::contains(call.into_ref()),
+                "`BaseCallFilter` tiene que incluir `PauseFilter`"
+            );
+        }
+    }
+
+    // --- Eventos del pallet ---
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        Created {                       // Emitido cuando se crea un nuevo kitty
+            owner: T::AccountId,
+            kitty_id: [u8; 32],
+            generation: u32
+        },
+        Transferred {                   // Emitido cuando se transfiere un kitty
+            from: T::AccountId,
+            to: T::AccountId,
+            kitty_id: [u8; 32],
+            remark: Option<TransferRemark>
+        },
+        ForceTransferred {              // Emitido cuando gobernanza mueve un kitty sin ser su dueño
+            from: T::AccountId,
+            to: T::AccountId,
+            kitty_id: [u8; 32]
+        },
+        ForceBurned {                   // Emitido cuando gobernanza destruye un kitty
+            owner: T::AccountId,
+            kitty_id: [u8; 32]
+        },
+        Approval {                      // Emitido cuando un dueño autoriza a otra cuenta a mover un kitty
+            owner: T::AccountId,
+            spender: T::AccountId,
+            kitty_id: [u8; 32],
+            role: ApprovalRole,
+            expires_at: Option<BlockNumberFor<T>>
+        },
+        ApprovalRevoked {               // Emitido cuando un dueño retira la autorización de un kitty
+            owner: T::AccountId,
+            kitty_id: [u8; 32]
+        },
+        ApprovalForAll {                // Emitido cuando un dueño nombra o retira a un operador
+            owner: T::AccountId,
+            operator: T::AccountId,
+            approved: bool,
+            expires_at: Option<BlockNumberFor<T>>
+        },
+        ExpiredApprovalCleared {        // Emitido cuando se borra la autorización caducada de un kitty
+            kitty_id: [u8; 32]
+        },
+        ExpiredOperatorCleared {        // Emitido cuando se borra una delegación de operador caducada
+            owner: T::AccountId,
+            operator: T::AccountId
+        },
+        TransferOffered {               // Emitido cuando un dueño ofrece un kitty a otra cuenta
+            from: T::AccountId,
+            to: T::AccountId,
+            kitty_id: [u8; 32],
+            expires_at: BlockNumberFor<T>
+        },
+        TransferOfferCancelled {        // Emitido cuando se retira una transferencia ofrecida
+            kitty_id: [u8; 32]
+        },
+        DepositedToEscrow {             // Emitido cuando un dueño guarda un kitty en la bóveda
+            owner: T::AccountId,
+            kitty_id: [u8; 32]
+        },
+        WithdrawnFromEscrow {           // Emitido cuando un dueño saca un kitty de la bóveda
+            owner: T::AccountId,
+            kitty_id: [u8; 32]
+        },
+        KittySentAbroad {               // Emitido cuando un kitty queda en reserva y se envía a otra parachain
+            kitty_id: [u8; 32],
+            from: T::AccountId,
+            para_id: u32,
+            beneficiary: [u8; 32]
+        },
+        KittyReturned {                 // Emitido cuando vuelve un kitty enviado a otra parachain
+            kitty_id: [u8; 32],
+            para_id: u32,
+            to: T::AccountId
+        },
+        ForeignKittyWrapped {           // Emitido cuando llega un NFT de otra cadena y se acuña su envoltorio
+            kitty_id: [u8; 32],
+            para_id: u32,
+            foreign_id: [u8; 32],
+            to: T::AccountId
+        },
+        KittyExported {                 // Emitido cuando un kitty queda en custodia y sale hacia Ethereum
+            kitty_id: [u8; 32],
+            from: T::AccountId,
+            eth_address: [u8; 20],
+            nonce: u64,
+            commitment: [u8; 32]
+        },
+        KittyImported {                 // Emitido cuando vuelve de Ethereum un kitty exportado
+            kitty_id: [u8; 32],
+            to: T::AccountId,
+            nonce: u64
+        },
+        KittyImportRejected {           // Emitido cuando un mensaje de Ethereum no se puede aplicar; su nonce se consume igual
+            kitty_id: [u8; 32],
+            nonce: u64,
+            error: DispatchError
+        },
+        KittyImportParked {             // Emitido cuando el destinatario no puede recibir un kitty de Ethereum; espera en custodia
+            kitty_id: [u8; 32],
+            to: T::AccountId,
+            nonce: u64,
+            error: DispatchError
+        },
+        ParkedImportClaimed {           // Emitido cuando el destinatario reclama un kitty de Ethereum que esperaba en custodia
+            kitty_id: [u8; 32],
+            to: T::AccountId
+        },
+        KittyWrappedIntoToken {         // Emitido cuando un kitty se envuelve en la unidad de un activo
+            kitty_id: [u8; 32],
+            asset_id: KittyTokenIdOf<T>,
+            owner: T::AccountId
+        },
+        KittyUnwrapped {                // Emitido cuando se quema el token y el kitty vuelve a una cuenta
+            kitty_id: [u8; 32],
+            asset_id: KittyTokenIdOf<T>,
+            to: T::AccountId
+        },
+        CoOwnershipEnabled {            // Emitido cuando un dueño reparte un kitty entre varias cuentas
+            kitty_id: [u8; 32],
+            owners: Vec<T::AccountId>,
+            threshold: u32
+        },
+        CoOwnerActionProposed {         // Emitido cuando un copropietario propone una acción
+            kitty_id: [u8; 32],
+            action_id: u32,
+            action: CoOwnerAction<T::AccountId>,
+            proposer: T::AccountId
+        },
+        CoOwnerActionApproved {         // Emitido cuando un copropietario aprueba una acción
+            kitty_id: [u8; 32],
+            action_id: u32,
+            who: T::AccountId
+        },
+        CoOwnerActionExecuted {         // Emitido cuando se ejecuta una acción aprobada
+            kitty_id: [u8; 32],
+            action_id: u32
+        },
+        RentalListed {                  // Emitido cuando un dueño ofrece su kitty en alquiler
+            kitty_id: [u8; 32],
+            price_per_block: BalanceOf<T>,
+            max_duration: BlockNumberFor<T>,
+            collateral: BalanceOf<T>
+        },
+        RentalUnlisted {                // Emitido cuando un dueño deja de ofrecer su kitty en alquiler
+            kitty_id: [u8; 32]
+        },
+        KittyRented {                   // Emitido cuando alguien alquila un kitty
+            kitty_id: [u8; 32],
+            renter: T::AccountId,
+            ends_at: BlockNumberFor<T>,
+            paid: BalanceOf<T>,
+            collateral: BalanceOf<T>
+        },
+        RentalDefaulted {               // Emitido cuando el inquilino no paga y se cobra de su garantía
+            kitty_id: [u8; 32],
+            renter: T::AccountId,
+            slashed: BalanceOf<T>
+        },
+        RentalEndRequested {            // Emitido cuando una de las partes pide terminar un alquiler antes de tiempo
+            kitty_id: [u8; 32],
+            by: T::AccountId
+        },
+        RentalEnded {                   // Emitido cuando termina un alquiler y el dueño recupera el kitty
+            kitty_id: [u8; 32],
+            renter: T::AccountId
+        },
+        KittyLocked {                   // Emitido cuando un dueño bloquea su kitty
+            kitty_id: [u8; 32]
+        },
+        KittyUnlocked {                 // Emitido cuando un dueño desbloquea su kitty
+            kitty_id: [u8; 32]
+        },
+        AttributeSet {                  // Emitido cuando se escribe un atributo de un kitty
+            kitty_id: [u8; 32],
+            key: AttributeKey<T>,
+            value: AttributeValue<T>
+        },
+        AttributeCleared {              // Emitido cuando se borra un atributo de un kitty
+            kitty_id: [u8; 32],
+            key: AttributeKey<T>
+        },
+        GiftSent {                      // Emitido cuando un dueño regala un kitty
+            from: T::AccountId,
+            to: T::AccountId,
+            kitty_id: [u8; 32],
+            expires_at: BlockNumberFor<T>
+        },
+        GiftClaimed {                   // Emitido cuando el destinatario reclama su regalo
+            kitty_id: [u8; 32],
+            to: T::AccountId
+        },
+        GiftReclaimed {                 // Emitido cuando un regalo caduca y vuelve al remitente
+            kitty_id: [u8; 32],
+            from: T::AccountId
+        },
+        PriceSet {                      // Emitido cuando un dueño pone o quita un precio
+            owner: T::AccountId,
+            kitty_id: [u8; 32],
+            new_price: Option<BalanceOf<T>>,
+            whitelisted_buyer: Option<T::AccountId>
+        },
+        Sold {                          // Emitido cuando se vende un kitty
+            buyer: T::AccountId,
+            kitty_id: [u8; 32],
+            price: BalanceOf<T>
+        },
+        FloorSwept {                    // Emitido cuando alguien compra de una vez los anuncios más baratos
+            buyer: T::AccountId,
+            kitty_ids: Vec<[u8; 32]>,
+            total: BalanceOf<T>
+        },
+        BundleCreated {                 // Emitido cuando se pone a la venta un lote de kitties
+            bundle_id: u32,
+            seller: T::AccountId,
+            kitty_ids: Vec<[u8; 32]>,
+            price: BalanceOf<T>
+        },
+        BundleCancelled {               // Emitido cuando el vendedor deshace un lote
+            bundle_id: u32
+        },
+        BundleSold {                    // Emitido cuando se compra un lote entero
+            bundle_id: u32,
+            buyer: T::AccountId,
+            price: BalanceOf<T>
+        },
+        SwapProposed {                  // Emitido cuando se propone un intercambio de kitties
+            swap_id: u32,
+            proposer: T::AccountId,
+            offered: [u8; 32],
+            wanted: [u8; 32],
+            extra_payment: Option<BalanceOf<T>>,
+            expires_at: BlockNumberFor<T>
+        },
+        SwapAccepted {                  // Emitido cuando se completa un intercambio
+            swap_id: u32,
+            accepted_by: T::AccountId
+        },
+        SwapCancelled {                 // Emitido cuando se retira un intercambio
+            swap_id: u32
+        },
+        OfferMade {                     // Emitido cuando alguien oferta por un kitty
+            offer_id: u32,
+            kitty_id: [u8; 32],
+            bidder: T::AccountId,
+            amount: BalanceOf<T>,
+            expires_at: BlockNumberFor<T>
+        },
+        OfferAccepted {                 // Emitido cuando el dueño acepta una oferta
+            offer_id: u32,
+            kitty_id: [u8; 32],
+            seller: T::AccountId,
+            bidder: T::AccountId,
+            amount: BalanceOf<T>
+        },
+        OfferCancelled {                // Emitido cuando se retira una oferta y se libera el importe
+            offer_id: u32,
+            kitty_id: [u8; 32]
+        },
+        AuctionStarted {                // Emitido cuando se abre una subasta
+            auction_id: u32,
+            kitty_id: [u8; 32],
+            seller: T::AccountId,
+            min_bid: BalanceOf<T>,
+            buy_now: Option<BalanceOf<T>>,
+            end: BlockNumberFor<T>
+        },
+        BidPlaced {                     // Emitido cuando alguien supera la puja más alta
+            auction_id: u32,
+            bidder: T::AccountId,
+            amount: BalanceOf<T>
+        },
+        AuctionExtended {               // Emitido cuando una puja tardía alarga la subasta
+            auction_id: u32,
+            end: BlockNumberFor<T>
+        },
+        BoughtOut {                     // Emitido cuando alguien paga el precio de compra inmediata
+            auction_id: u32,
+            buyer: T::AccountId,
+            price: BalanceOf<T>
+        },
+        AuctionSettled {                // Emitido al liquidar una subasta (sin ganador si no se vendió)
+            auction_id: u32,
+            winner: Option<T::AccountId>,
+            price: Option<BalanceOf<T>>
+        },
+        AssetPriceSet {                 // Emitido cuando se pone un kitty a la venta en un activo
+            owner: T::AccountId,
+            kitty_id: [u8; 32],
+            asset_id: AssetIdOf<T>,
+            amount: AssetBalanceOf<T>
+        },
+        SoldForAsset {                  // Emitido cuando se vende un kitty por un activo
+            buyer: T::AccountId,
+            kitty_id: [u8; 32],
+            asset_id: AssetIdOf<T>,
+            price: AssetBalanceOf<T>,
+            commission: AssetBalanceOf<T>, // Parte del precio que fue a la comisión del mercado
+            royalty: AssetBalanceOf<T>     // Parte del precio que fue al creador del kitty
+        },
+        NameSet {                       // Emitido cuando un dueño pone nombre a su kitty
+            kitty_id: [u8; 32],
+            name: KittyName<T>,
+            fee: BalanceOf<T>
+        },
+        VoucherPurchased {              // Emitido cuando alguien compra un vale regalo
+            buyer: T::AccountId,
+            code_hash: [u8; 32]
+        },
+        VoucherRedeemed {               // Emitido cuando se canjea un vale por un kitty
+            code_hash: [u8; 32],
+            who: T::AccountId,
+            kitty_id: [u8; 32]
+        },
+        MintPassPurchased {             // Emitido cuando una cuenta compra un pase de temporada
+            who: T::AccountId,
+            mints: u32,
+            expires_at: BlockNumberFor<T>
+        },
+        MintPassUsed {                  // Emitido cuando un mint consume un uso del pase
+            who: T::AccountId,
+            remaining: u32
+        },
+        BreedingLoanGranted {           // Emitido cuando un dueño presta su kitty para criar
+            kitty_id: [u8; 32],
+            borrower: T::AccountId,
+            fee: BalanceOf<T>,
+            expires_at: BlockNumberFor<T>
+        },
+        BreedingLoanRevoked {           // Emitido cuando el dueño retira el préstamo
+            kitty_id: [u8; 32]
+        },
+        BreedingLoanUsed {              // Emitido cuando el prestatario usa su derecho de cría
+            kitty_id: [u8; 32],
+            borrower: T::AccountId,
+            fee: BalanceOf<T>
+        },
+        PremiumPaid {                   // Emitido cuando se pagan primas del seguro
+            kitty_id: [u8; 32],
+            holder: T::AccountId,
+            amount: BalanceOf<T>,
+            paid_until: BlockNumberFor<T>
+        },
+        ClaimFiled {                    // Emitido cuando un asegurado presenta un reclamo
+            kitty_id: [u8; 32],
+            claimant: T::AccountId
+        },
+        ClaimResolved {                 // Emitido cuando el árbitro resuelve un reclamo
+            kitty_id: [u8; 32],
+            claimant: T::AccountId,
+            approved: bool,
+            payout: BalanceOf<T>
+        },
+        RaffleStarted {                 // Emitido cuando un dueño pone su kitty en rifa
+            kitty_id: [u8; 32],
+            seller: T::AccountId,
+            ticket_price: BalanceOf<T>,
+            max_tickets: u32,
+            close_block: BlockNumberFor<T>
+        },
+        TicketBought {                  // Emitido por cada boleto vendido
+            kitty_id: [u8; 32],
+            buyer: T::AccountId,
+            tickets_sold: u32
+        },
+        RaffleWon {                     // Emitido cuando se sortea el ganador de una rifa
+            kitty_id: [u8; 32],
+            winner: T::AccountId,
+            proceeds: BalanceOf<T>,
+            fee: BalanceOf<T>
+        },
+        RaffleRefunded {                // Emitido cuando una rifa se cierra sin ganador
+            kitty_id: [u8; 32],
+            tickets_refunded: u32
+        },
+        EscrowedSaleOpened {            // Emitido cuando se compra un kitty con escrow
+            sale_id: u32,
+            kitty_id: [u8; 32],
+            seller: T::AccountId,
+            buyer: T::AccountId,
+            price: BalanceOf<T>
+        },
+        DisputeRaised {                 // Emitido cuando una parte abre una disputa
+            sale_id: u32,
+            by: T::AccountId,
+            deadline: BlockNumberFor<T>
+        },
+        DisputeRuled {                  // Emitido cuando el árbitro resuelve una disputa
+            sale_id: u32,
+            buyer_wins: bool
+        },
+        EscrowedSaleSettled {           // Emitido al liquidar una venta con escrow
+            sale_id: u32,
+            completed: bool             // true: kitty al comprador y pago al vendedor; false: reembolso
+        },
+        BreedingStarted {               // Emitido cuando empieza una gestación
+            owner: T::AccountId,
+            pending_id: u32,
+            parent_1: [u8; 32],
+            parent_2: [u8; 32],
+            due_at: BlockNumberFor<T>
+        },
+        Bred {                          // Emitido cuando nace un kitty por cría
+            owner: T::AccountId,
+            parent_1: [u8; 32],
+            parent_2: [u8; 32],
+            child_id: [u8; 32]
+        },
+        Burned {                        // Emitido cuando se destruye un kitty (también al fusionarlo)
+            owner: T::AccountId,
+            kitty_id: [u8; 32]
+        },
+        KittiesFused {                  // Emitido cuando dos kitties se fusionan en uno
+            owner: T::AccountId,
+            kitty_a: [u8; 32],
+            kitty_b: [u8; 32],
+            fused: [u8; 32]
+        },
+        SeasonScheduled {               // Emitido al programar una temporada de edición limitada
+            season_id: u16,
+            start: BlockNumberFor<T>,
+            end: BlockNumberFor<T>,
+            supply_cap: u32
+        },
+        SpecialEditionMinted {          // Emitido al crear un kitty de edición limitada
+            kitty_id: [u8; 32],
+            season_id: u16,
+            number: u32                 // Posición del kitty dentro de la edición (desde 1)
+        },
+        MintCommitted {                 // Emitido al comprometer un mint en dos fases
+            who: T::AccountId,
+            target: BlockNumberFor<T>   // Bloque cuyo hash decidirá el ADN
+        },
+        Rerolled {                      // Emitido cuando se regenera el ADN de un kitty
+            kitty_id: [u8; 32],
+            old_dna: KittyDna<T>,
+            new_dna: KittyDna<T>,
+            fee: BalanceOf<T>
+        },
+        CommissionCharged {             // Emitido cuando una venta paga la comisión del mercado
+            kitty_id: [u8; 32],
+            seller: T::AccountId,
+            commission: BalanceOf<T>
+        },
+        RoyaltyPaid {                   // Emitido cuando una reventa paga la regalía al creador
+            kitty_id: [u8; 32],
+            minter: T::AccountId,
+            royalty: BalanceOf<T>
+        },
+        BatchMinted {                   // Emitido tras un reparto; cada kitty emite además `Created`
+            recipients: u32
+        },
+        MaxTotalKittiesSet {            // Emitido cuando gobernanza cambia el tope de suministro
+            cap: Option<u32>
+        },
+        MintPhaseChanged {              // Emitido cuando gobernanza cambia la fase de mint
+            phase: MintPhase
+        },
+        PauseChanged {                  // Emitido cuando gobernanza pausa o reanuda el pallet
+            paused: bool
+        },
+        CallClassPauseChanged {         // Emitido cuando gobernanza pausa o reanuda una clase de llamadas
+            class: CallClass,
+            paused: bool
+        },
+        BlacklistUpdated {              // Emitido cuando gobernanza añade o quita una cuenta de la lista negra
+            who: T::AccountId,
+            blacklisted: bool
+        },
+        MintAllowlistUpdated {          // Emitido cuando se añade o quita una cuenta de la lista
+            who: T::AccountId,
+            allowed: bool
+        },
+        MintFeePaid {                   // Emitido cuando se cobra la tasa de mint
+            who: T::AccountId,
+            fee: BalanceOf<T>
+        },
+        MintPricePaid {                 // Emitido cuando se cobra un mint al precio de la curva
+            who: T::AccountId,
+            price: BalanceOf<T>,
+            reserve: BalanceOf<T>,
+            treasury: BalanceOf<T>
+        },
+        BreedingFeePaid {               // Emitido cuando se cobra la tarifa de cría del protocolo
+            who: T::AccountId,
+            fee: BalanceOf<T>
+        },
+        StudListed {                    // Emitido al anunciar o retirar un semental
+            kitty_id: [u8; 32],
+            price: Option<BalanceOf<T>>
+        },
+        StudUsed {                      // Emitido cuando alguien cría con un semental anunciado
+            stud: [u8; 32],
+            breeder: T::AccountId,
+            fee: BalanceOf<T>
+        },
+        Mutated {                       // Emitido cuando una cría nace con genes mutados
+            kitty_id: [u8; 32],
+            genes: Vec<u8>              // Índices de los genes mutados
+        },
+        RemoteBreedingOffered {         // Emitido cuando un dueño abre su kitty a cría remota
+            kitty_id: [u8; 32],
+            para_id: u32
+        },
+        RemoteBreedingCancelled {       // Emitido cuando el dueño retira la oferta
+            kitty_id: [u8; 32]
+        },
+        RemoteKittyBred {               // Emitido al nacer una cría con pareja remota
+            kitty_id: [u8; 32],
+            para_id: u32,
+            partner_id: [u8; 32],
+            child_id: [u8; 32]
+        },
+        CapacityStaked {                // Emitido cuando una cuenta aumenta su stake de capacidad
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+            max_kitties: u32
+        },
+        CapacityUnstaked {              // Emitido cuando una cuenta retira stake de capacidad
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+            max_kitties: u32
+        },
+        CollectorPassSet {              // Emitido al marcar o desmarcar un pase de coleccionista
+            kitty_id: [u8; 32],
+            is_pass: bool
+        },
+        AppraisalPosted {               // Emitido cuando el oráculo publica una tasación
+            target: AppraisalTarget,
+            value: BalanceOf<T>
+        },
+    }
+
+    // --- Errores posibles del pallet ---
+    #[pallet::error]
+    pub enum Error<T> {
+        TooManyKitties,   // Se excedió el límite total de kitties permitidos
+        SupplyCapReached, // Ya existen `MaxTotalKitties` kitties
+        NotOnMintAllowlist, // En la fase `Whitelist` la cuenta no puede crear kitties
+        DuplicateKitty,   // Ya existe un kitty con ese ADN
+        TooManyOwned,     // El dueño ya posee el máximo de kitties de su nivel
+        TransferToSelf,   // No se puede transferir un kitty a uno mismo
+        NoKitty,          // El kitty no existe en el mapa
+        NotOwner,         // La cuenta que intenta operar no es el dueño del kitty
+        NotForSale,       // Se intenta comprar un kitty que no está en venta
+        MaxPriceTooLow,   // El precio máximo ofrecido por el comprador es menor al precio de venta
+        EmptyName,        // El nombre no puede estar vacío
+        NameTaken,        // Otro kitty ya usa ese nombre
+        VoucherExists,    // Ya hay un vale pendiente con ese código
+        NoVoucher,        // No hay ningún vale pendiente para ese código
+        MintPassActive,   // La cuenta ya tiene un pase de temporada con mints disponibles
+        NoBreedingLoan,   // No hay préstamo de cría para ese kitty y esa cuenta
+        BreedingLoanExpired, // El préstamo de cría ya venció
+        LoanToSelf,       // No se puede prestar un kitty a su propio dueño
+        ExpiryInPast,     // El vencimiento indicado ya pasó
+        ZeroPeriods,      // Hay que pagar al menos un periodo de prima
+        NoPolicy,         // No hay pérdida asegurada de ese kitty a nombre de quien reclama
+        PolicyLapsed,     // La póliza no cubría el bloque de la pérdida
+        ClaimExists,      // Ya hay un reclamo presentado para ese kitty
+        NoClaim,          // No hay reclamo para ese kitty
+        ClaimNotPending,  // El reclamo ya fue resuelto
+        KittyInRaffle,    // El kitty está en una rifa abierta
+        NoRaffle,         // No hay rifa abierta para ese kitty
+        InvalidRaffle,    // Parámetros de rifa inválidos (boletos o cierre)
+        TicketPriceTooLow, // El boleto no alcanza el depósito existencial
+        RaffleClosed,     // La rifa ya no vende boletos
+        RaffleSoldOut,    // Ya se vendieron todos los boletos
+        RaffleStillOpen,  // Todavía no se puede cerrar la rifa
+        KittyInEscrow,    // El kitty tiene una venta con escrow pendiente
+        KittyInBundle,    // El kitty forma parte de un lote a la venta
+        NoPendingTransfer, // El kitty no tiene una transferencia ofrecida
+        NotRecipient,     // Solo el destinatario puede aceptar la transferencia
+        TransferOfferExpired, // La transferencia ofrecida ya caducó
+        PalletPaused,     // El pallet está en pausa de emergencia
+        CallClassPaused,  // Las llamadas de esta clase están en pausa
+        AccountBlacklisted, // La cuenta está en la lista negra del mercado
+        NotMarketParticipant, // `Config::MarketParticipants` no admite a la cuenta
+        NotApproved,      // Quien llama no es el dueño ni está autorizado para este kitty
+        InvalidApprovalExpiry, // La autorización caducaría en el bloque actual o antes
+        NoApproval,       // El kitty no tiene una autorización que revocar
+        ApprovalStillActive, // La autorización todavía no ha caducado
+        NotInEscrowVault, // El kitty no está en la bóveda o no es de quien llama
+        NotAbroad,        // El kitty no está en esa parachain
+        InvalidForeignDna, // El ADN recibido no tiene la longitud del genoma o no es el del kitty
+        AlreadyWrapped,   // Ese NFT de otra cadena ya tiene su kitty envuelto aquí
+        KittyWrapped,     // El kitty ya está envuelto en un token
+        NotWrapped,       // El kitty no está envuelto en un token
+        TokenAssetInUse,  // Ya existe un activo con ese id
+        InvalidEthAddress, // No se puede exportar a la dirección cero
+        InvalidBridgeMessage, // El mensaje de Ethereum no tiene el formato esperado
+        UnexpectedBridgeNonce, // El mensaje de Ethereum no es el siguiente del canal
+        NotOnEthereum,    // El kitty no está exportado a Ethereum
+        NotParked,        // No hay un kitty de Ethereum esperando a esta cuenta
+        KittyCoOwned,     // El kitty está en copropiedad; hace falta una acción aprobada
+        NotCoOwned,       // El kitty no está en copropiedad
+        NotCoOwner,       // Quien llama no es copropietario del kitty
+        InvalidThreshold, // El umbral tiene que estar entre 1 y el número de copropietarios
+        TooManyCoOwners,  // Se supera `MaxCoOwners`
+        NoCoOwnerAction,  // No existe esa acción pendiente
+        AlreadyApproved,  // El copropietario ya aprobó esa acción
+        NotEnoughApprovals, // La acción aún no reúne el umbral
+        NotForRent,       // El kitty no se ofrece en alquiler
+        InvalidRentalDuration, // La duración es cero o supera la máxima del anuncio
+        KittyRented,      // El kitty está alquilado
+        NotRented,        // El kitty no tiene un alquiler en curso
+        NotRentalParty,   // Quien llama no es ni el dueño ni el inquilino
+        TooManyRentalsEnding, // Ya terminan `MaxRentalsPerBlock` alquileres en ese bloque
+        KittyLocked,      // El kitty está bloqueado por su dueño
+        NotLocked,        // El kitty no está bloqueado
+        KittyInVault,     // El kitty está guardado en la bóveda de escrow
+        UnknownCollection, // La colección no es `KittyCollection`
+        AttributeKeyTooLong,   // La clave supera `MaxAttributeKeyLength`
+        AttributeValueTooLong, // El valor supera `MaxAttributeValueLength`
+        NoAttribute,      // El kitty no tiene ese atributo
+        NoGift,           // El kitty no es un regalo pendiente
+        KittyGifted,      // El kitty es un regalo pendiente de reclamar
+        TooManyGiftsExpiring, // Ya caducan `MaxGiftsPerBlock` regalos en ese bloque
+        InvalidSweep,     // Hay que comprar entre 1 y `MaxSweep` kitties
+        NotEnoughListings, // No hay tantos anuncios al precio máximo indicado
+        EmptyBundle,      // Un lote necesita al menos un kitty
+        NoBundle,         // No existe ese lote
+        NotWhitelistedBuyer, // La venta está reservada para otro comprador
+        NoSwap,           // No existe ese intercambio
+        SwapExpired,      // El intercambio ya caducó
+        NoOffer,          // No existe esa oferta
+        OfferExpired,     // La oferta ya caducó (o caducaría antes de crearse)
+        OfferTooLow,      // La oferta no llega al depósito existencial
+        NoAuction,        // No existe esa subasta
+        KittyInAuction,   // El kitty está en una subasta en curso
+        InvalidAuctionDuration, // Duración nula o mayor que `MaxAuctionDuration`
+        BidTooLow,        // La puja no llega al mínimo o no supera la más alta
+        BuyNowBelowMinBid, // El precio de compra inmediata es menor que la puja mínima
+        AuctionEnded,     // La subasta ya no admite pujas
+        AuctionNotEnded,  // La subasta todavía no se puede liquidar
+        TooManyAuctionsEnding, // Ya terminan `MaxAuctionsPerBlock` subastas en ese bloque
+        NoSale,           // No existe esa venta con escrow
+        NotSaleParty,     // Solo el comprador o el vendedor pueden hacer esto
+        SaleDisputed,     // La venta está en disputa
+        SaleNotDisputed,  // La venta no está en disputa
+        DisputeWindowClosed, // El pago ya se puede liberar; es tarde para disputar
+        SaleNotDue,       // Todavía no se puede liquidar la venta
+        SameParent,       // Un kitty no puede criar consigo mismo
+        NoBreedingRights, // Ni se posee el progenitor ni se tiene un préstamo de cría sobre él
+        BreedingCooldownActive, // Un progenitor todavía se está recuperando de su última cría
+        SameGender,       // Los progenitores deben ser de sexo opuesto
+        MaxGenerationReached, // La cría superaría `MaxGeneration`
+        TooCloselyRelated, // Los progenitores están demasiado emparentados
+        SameKitty,        // No se puede fusionar un kitty consigo mismo
+        InvalidSeason,    // La temporada termina antes de empezar, ya pasó o no tiene cupo
+        SeasonScheduled,  // Ya hay una temporada programada o en curso
+        InstantMintDisabled, // El runtime solo permite el mint en dos fases
+        CommitPending,    // La cuenta ya tiene un mint comprometido que aún se puede revelar
+        NoMintCommit,     // La cuenta no tiene un mint comprometido
+        RevealTooEarly,   // El bloque que decide el ADN todavía no ha terminado
+        CommitExpired,    // El hash del bloque objetivo ya no está disponible
+        BadReveal,        // La sal no coincide con el compromiso
+        InsufficientMintFunds, // La cuenta no puede pagar la tasa de mint
+        MintQuotaExceeded, // La cuenta ya creó `MintQuota` kitties en este periodo
+        UnknownAsset,     // El activo no existe
+        WrongAsset,       // El kitty está a la venta en otro activo
+        NotStud,          // El kitty no está anunciado como semental
+        NoPendingBirth,   // No existe esa gestación
+        GestationNotComplete, // La gestación todavía no ha terminado
+        NoRemoteOffer,    // El kitty no está ofrecido para cría remota
+        WrongParachain,   // La oferta de cría es para otra parachain
+        InvalidPartnerDna, // El ADN de la pareja remota no tiene la longitud del genoma
+        InsufficientStake, // Se intenta retirar más stake del que hay
+        StakeBacksKitties, // El stake restante no cubriría los kitties que ya se poseen
+    }
+
+    // --- Extrinsics (funciones públicas que pueden llamarse desde fuera del runtime) ---
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+
+        /// Crea un nuevo kitty con ADN aleatorio y lo asigna al usuario que ejecuta la transacción.
+        /// Solo si `InstantMint` está activo; si no, hay que usar `commit_create`.
+        pub fn create_kitty(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Comprueba que la llamada proviene de una cuenta firmada (no root).
+            ensure!(T::InstantMint::get(), Error::<T>::InstantMintDisabled); // El ADN instantáneo se puede prever.
+            Self::ensure_mint_phase_allows(&who)?; // En la fase `Whitelist`, solo la lista.
+            Self::use_mint_quota(&who)?; // Respeta la cuota de mints por periodo.
+            Self::pay_for_mint(&who)?; // Consume un mint del pase si lo tiene; si no, cobra la curva o `MintFee`.
+            let dna = Self::gen_dna(&who); // Genera un ADN aleatorio ligado a quien crea el kitty.
+            Self::mint(who, dna)?; // Crea el kitty y lo asigna al dueño llamando a la función mint() (implementada en impls.rs)
+            Ok(())
+        }
+
+        /// Crea un kitty para cada destinatario (un reparto tipo génesis después del lanzamiento).
+        /// No cobra tasas ni respeta fases o cuotas, pero sí el tope de suministro y la capacidad
+        /// de cada cuenta; si un mint falla, no se crea ninguno.
+        #[pallet::weight(T::WeightInfo::force_mint_batch(recipients.len() as u32))]
+        pub fn force_mint_batch(
+            origin: OriginFor<T>,
+            recipients: BoundedVec<T::AccountId, T::MaxAirdropRecipients>,
+        ) -> DispatchResult {
+            T::AirdropOrigin::ensure_origin(origin)?; // Solo el origen configurado (gobernanza).
+            Self::do_force_mint_batch(recipients.into_inner())?; // Un mint por destinatario.
+            Ok(())
+        }
+
+        /// Crea un kitty con `seed` como identificador y semilla del genoma, para tener ADN
+        /// reproducible en redes de pruebas.
+        pub fn create_kitty_with_seed(origin: OriginFor<T>, seed: [u8; 32]) -> DispatchResult {
+            let who = T::SeededMintOrigin::ensure_origin(origin)?; // Solo el origen configurado.
+            Self::mint(who, seed)?; // Mismo camino que `create_kitty`, sin pasar por `gen_dna`.
+            Ok(())
+        }
+
+        /// Primera fase del mint: se compromete `commitment = blake2_256(salt)`. El ADN dependerá
+        /// de la sal y del hash de un bloque futuro, así que nadie puede simularlo de antemano.
+        pub fn commit_create(origin: OriginFor<T>, commitment: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Quien se compromete recibirá el kitty.
+            Self::do_commit_create(who, commitment)?; // Guarda el compromiso y su bloque objetivo.
+            Ok(())
+        }
+
+        /// Segunda fase del mint: revela la sal y crea el kitty.
+        pub fn reveal_create(origin: OriginFor<T>, salt: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Solo quien se comprometió.
+            Self::ensure_mint_phase_allows(&who)?; // La misma fase que `create_kitty`.
+            Self::use_mint_quota(&who)?; // La misma cuota que `create_kitty`.
+            Self::pay_for_mint(&who)?; // Igual que `create_kitty`: pase, curva o `MintFee`.
+            Self::do_reveal_create(who, salt)?; // Comprueba la sal y crea el kitty.
+            Ok(())
+        }
+
+        /// Cruza dos progenitores propios o prestados para criar. La cría nace tras
+        /// `GestationPeriod` bloques, al reclamarla con `claim_birth`.
+        pub fn breed_kitty(
+            origin: OriginFor<T>,
+            parent_1: [u8; 32],
+            parent_2: [u8; 32],
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Quien cría se queda con la cría.
+            Self::do_breed(who, parent_1, parent_2)?; // Valida los progenitores y empieza la gestación.
+            Ok(())
+        }
+
+        /// Destruye un kitty propio y todo lo que apunta a él (nombre, anuncios, préstamos...).
+        pub fn burn_kitty(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Solo el dueño puede destruirlo.
+            Self::burn(&who, kitty_id)?; // Lo borra junto con sus índices y emite `Burned`.
+            Ok(())
+        }
+
+        /// Fusiona dos kitties propios: ambos se destruyen y nace uno nuevo con sus genomas
+        /// fusionados y la generación mayor.
+        pub fn fuse_kitties(
+            origin: OriginFor<T>,
+            kitty_a: [u8; 32],
+            kitty_b: [u8; 32],
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Solo el dueño de ambos puede fusionarlos.
+            Self::do_fuse(who, kitty_a, kitty_b)?; // Destruye los dos y crea el fusionado.
+            Ok(())
+        }
+
+        /// Regenera al azar el ADN de un kitty propio. Cada vez cuesta el doble que la anterior.
+        pub fn reroll_dna(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Solo el dueño puede regenerar su kitty.
+            Self::do_reroll_dna(who, kitty_id)?; // Cobra la tasa y cambia el genoma.
+            Ok(())
+        }
+
+        /// Programa una temporada de edición limitada (ver `Season`).
+        pub fn schedule_season(
+            origin: OriginFor<T>,
+            start: BlockNumberFor<T>,
+            end: BlockNumberFor<T>,
+            gene_prefix: [u8; 3],
+            supply_cap: u32,
+        ) -> DispatchResult {
+            T::SeasonOrigin::ensure_origin(origin)?; // Solo el origen configurado (gobernanza).
+            Self::do_schedule_season(start, end, gene_prefix, supply_cap)?; // Valida y la guarda.
+            Ok(())
+        }
+
+        /// Fija el máximo de kitties que pueden existir, o lo quita con `None`. Un tope por debajo
+        /// del suministro actual no destruye nada: solo impide crear más.
+        pub fn set_max_total_kitties(origin: OriginFor<T>, cap: Option<u32>) -> DispatchResult {
+            T::SupplyOrigin::ensure_origin(origin)?; // Solo el origen configurado (gobernanza).
+            Self::do_set_max_total_kitties(cap)?; // Lo comprueba cada mint.
+            Ok(())
+        }
+
+        /// Cambia la fase de mint (p. ej. de `Whitelist` a `Public` al abrir el lanzamiento).
+        pub fn set_mint_phase(origin: OriginFor<T>, phase: MintPhase) -> DispatchResult {
+            T::MintPhaseOrigin::ensure_origin(origin)?; // Solo el origen configurado (gobernanza).
+            Self::do_set_mint_phase(phase)?; // La comprueba cada `create_kitty`.
+            Ok(())
+        }
+
+        /// Añade (`allowed = true`) o quita una cuenta de la lista de la fase `Whitelist`.
+        pub fn set_mint_allowlist(origin: OriginFor<T>, who: T::AccountId, allowed: bool) -> DispatchResult {
+            T::MintPhaseOrigin::ensure_origin(origin)?; // Solo el origen configurado (gobernanza).
+            Self::do_set_mint_allowlist(who, allowed)?; // Actualiza la lista.
+            Ok(())
+        }
+
+        /// Anuncia `kitty_id` como semental con una tarifa por cría, o lo retira con `None`.
+        pub fn set_breeding_price(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            price: Option<BalanceOf<T>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Solo el dueño puede anunciar su kitty.
+            Self::do_set_breeding_price(who, kitty_id, price)?; // Guarda o borra el anuncio.
+            Ok(())
+        }
+
+        /// Cruza un kitty propio con un semental anunciado, pagando la tarifa a su dueño.
+        pub fn breed_with(
+            origin: OriginFor<T>,
+            my_kitty: [u8; 32],
+            stud_kitty: [u8; 32],
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Quien cría paga y se queda con la cría.
+            Self::do_breed_with(who, my_kitty, stud_kitty)?; // Paga la tarifa y empieza la gestación.
+            Ok(())
+        }
+
+        /// Reclama una cría cuya gestación ha terminado.
+        pub fn claim_birth(origin: OriginFor<T>, pending_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Solo quien crió puede reclamarla.
+            Self::do_claim_birth(who, pending_id)?; // Decide el genoma y crea el kitty.
+            Ok(())
+        }
+
+        /// Transfiere un kitty a otra cuenta.
+        pub fn transfer(
+            origin: OriginFor<T>,
+            to: T::AccountId,
+            kitty_id: [u8; 32],
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Verifica que la transacción esté firmada.
+            Self::do_transfer(who, to, kitty_id)?; // Ejecuta la lógica de transferencia (valida, actualiza almacenamiento, emite evento).
+            Ok(())
+        }
+
+        /// Mueve un kitty de `from` a `to` sin la firma del dueño. `from` tiene que seguir siendo
+        /// el dueño y el kitty no puede estar bloqueado (subasta, escrow, rifa, lote o regalo).
+        pub fn force_transfer(
+            origin: OriginFor<T>,
+            from: T::AccountId,
+            to: T::AccountId,
+            kitty_id: [u8; 32],
+        ) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?; // Solo el origen configurado (gobernanza).
+            Self::do_force_transfer(from, to, kitty_id)?; // Mismos índices que una transferencia normal.
+            Ok(())
+        }
+
+        /// Destruye un kitty sin la firma de su dueño y limpia todos sus índices. Un kitty bloqueado
+        /// (subasta, escrow, rifa, lote o regalo) tiene que liberarse antes.
+        pub fn force_burn(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            T::ForceOrigin::ensure_origin(origin)?; // Solo el origen configurado (gobernanza).
+            Self::do_force_burn(kitty_id)?; // Lo borra y emite `ForceBurned`.
+            Ok(())
+        }
+
+        /// Pausa (`paused = true`) o reanuda el pallet. En pausa se rechazan todas las llamadas
+        /// firmadas y los mints y transferencias de otros pallets; las de gobernanza, los cierres
+        /// de ventas y las disputas, y las subastas y regalos que vencen siguen funcionando.
+        pub fn set_paused(origin: OriginFor<T>, paused: bool) -> DispatchResult {
+            T::PauseOrigin::ensure_origin(origin)?; // Solo el origen configurado (gobernanza).
+            Self::do_set_paused(paused)?; // `PauseFilter` lo aplica a cada llamada firmada.
+            Ok(())
+        }
+
+        /// Pausa o reanuda solo las llamadas de `class`, p. ej. el mercado (`Trading`) sin tocar
+        /// las transferencias.
+        pub fn set_call_class_paused(origin: OriginFor<T>, class: CallClass, paused: bool) -> DispatchResult {
+            T::PauseOrigin::ensure_origin(origin)?; // Solo el origen configurado (gobernanza).
+            Self::do_set_call_class_paused(class, paused)?; // Lo comprueba cada llamada de la clase.
+            Ok(())
+        }
+
+        /// Añade (`blacklisted = true`) o quita una cuenta de la lista negra del mercado. Sus
+        /// kitties actuales no se tocan; solo deja de poder anunciar, comprar, pujar o recibir.
+        pub fn set_blacklisted(origin: OriginFor<T>, who: T::AccountId, blacklisted: bool) -> DispatchResult {
+            T::BlacklistOrigin::ensure_origin(origin)?; // Solo el origen configurado (gobernanza).
+            Self::do_set_blacklisted(who, blacklisted)?; // Actualiza la lista.
+            Ok(())
+        }
+
+        /// Transfiere un kitty a otra cuenta con una nota que se publica en el evento
+        /// `Transferred`, para que exchanges y custodios puedan conciliar sus movimientos.
+        pub fn transfer_with_remark(
+            origin: OriginFor<T>,
+            to: T::AccountId,
+            kitty_id: [u8; 32],
+            remark: TransferRemark,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty.
+            Self::do_transfer_with_remark(who, to, kitty_id, Some(remark))?; // Transfiere y publica la nota.
+            Ok(())
+        }
+
+        /// Autoriza a `spender` sobre `kitty_id` hasta el bloque `expires_at`, si se indica. Con
+        /// `ApprovalRole::Transfer` puede moverlo con `transfer_from` (p. ej. un contrato de
+        /// mercado o un intermediario); con `ApprovalRole::SalesAgent`, ponerle precio y
+        /// subastarlo en nombre del dueño. Sustituye a la autorización anterior.
+        pub fn approve(
+            origin: OriginFor<T>,
+            spender: T::AccountId,
+            kitty_id: [u8; 32],
+            role: ApprovalRole,
+            expires_at: Option<BlockNumberFor<T>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty.
+            Self::do_approve(who, spender, kitty_id, role, expires_at)?; // Guarda la autorización.
+            Ok(())
+        }
+
+        /// Retira la autorización de `approve` sobre un kitty propio.
+        pub fn revoke_approval(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty.
+            Self::do_revoke_approval(who, kitty_id)?; // Borra la autorización.
+            Ok(())
+        }
+
+        /// Nombra (`approved = true`) o retira a `operator` como gestor de todos los kitties de
+        /// quien llama: puede transferirlos, ponerles precio y subastarlos. Con `expires_at` la
+        /// delegación deja de valer en ese bloque. Nombrarlo retiene `OperatorDeposit`, que se
+        /// devuelve al retirarlo.
+        pub fn set_approval_for_all(
+            origin: OriginFor<T>,
+            operator: T::AccountId,
+            approved: bool,
+            expires_at: Option<BlockNumberFor<T>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño que delega.
+            Self::do_set_approval_for_all(who, operator, approved, expires_at)?; // Guarda o borra la delegación.
+            Ok(())
+        }
+
+        /// Transfiere `kitty_id` de `from` a `to` en nombre del dueño, que tiene que ser quien
+        /// llama, haberlo autorizado con `approve` o haberlo nombrado operador.
+        pub fn transfer_from(
+            origin: OriginFor<T>,
+            from: T::AccountId,
+            to: T::AccountId,
+            kitty_id: [u8; 32],
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño o cuenta autorizada.
+            Self::do_transfer_from(who, from, to, kitty_id)?; // Comprueba la autorización y transfiere.
+            Ok(())
+        }
+
+        /// Borra la autorización caducada de `kitty_id`. Cualquiera puede llamarla.
+        pub fn clear_expired_approval(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            ensure_signed(origin)?; // Cualquier cuenta firmada.
+            Self::do_clear_expired_approval(kitty_id)?; // Solo si ya no vale.
+            Ok(())
+        }
+
+        /// Borra la delegación caducada de `owner` en `operator` y le devuelve su depósito.
+        /// Cualquiera puede llamarla.
+        pub fn clear_expired_operator(
+            origin: OriginFor<T>,
+            owner: T::AccountId,
+            operator: T::AccountId,
+        ) -> DispatchResult {
+            ensure_signed(origin)?; // Cualquier cuenta firmada.
+            Self::do_clear_expired_operator(owner, operator)?; // Solo si ya no vale.
+            Ok(())
+        }
+
+        /// Ofrece un kitty propio a `to`, que tiene que aceptarlo con `accept_transfer`. Evita
+        /// perder kitties enviados a una cuenta equivocada o que no los puede gestionar.
+        pub fn offer_transfer(origin: OriginFor<T>, to: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty, uno de sus operadores o una cuenta aprobada.
+            Self::do_offer_transfer(who, to, kitty_id)?; // Guarda la oferta (sustituye a la anterior).
+            Ok(())
+        }
+
+        /// Acepta un kitty ofrecido a quien llama.
+        pub fn accept_transfer(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Destinatario de la oferta.
+            Self::do_accept_transfer(who, kitty_id)?; // Completa la transferencia.
+            Ok(())
+        }
+
+        /// Retira una transferencia ofrecida: el dueño en cualquier momento, cualquiera tras caducar.
+        pub fn cancel_transfer(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Quien ofreció el kitty, o cualquiera tras caducar.
+            Self::do_cancel_transfer(who, kitty_id)?; // Borra la oferta.
+            Ok(())
+        }
+
+        /// Guarda un kitty propio en la bóveda del pallet. Mientras esté ahí nadie puede moverlo,
+        /// venderlo ni quemarlo; solo su dueño puede sacarlo con `withdraw_from_escrow`.
+        pub fn deposit_to_escrow(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty.
+            Self::do_deposit_to_escrow(who, kitty_id)?; // Pasa el kitty a la bóveda.
+            Ok(())
+        }
+
+        /// Saca de la bóveda un kitty guardado por quien llama.
+        pub fn withdraw_from_escrow(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño real del kitty guardado.
+            Self::do_withdraw_from_escrow(who, kitty_id)?; // Devuelve el kitty.
+            Ok(())
+        }
+
+        /// Envía un kitty propio a la parachain hermana `para_id`, para su cuenta
+        /// `beneficiary`. El kitty queda en reserva aquí hasta que vuelva.
+        pub fn send_kitty_to_parachain(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            para_id: u32,
+            beneficiary: [u8; 32],
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty.
+            Self::do_send_kitty_to_parachain(who, kitty_id, para_id, beneficiary)?; // Reserva y envía.
+            Ok(())
+        }
+
+        /// Exporta un kitty propio a la dirección de Ethereum `eth_address`. El kitty queda en
+        /// custodia aquí y el contrato ERC-721 acuña el token al recibir el mensaje.
+        pub fn export_kitty(origin: OriginFor<T>, kitty_id: [u8; 32], eth_address: [u8; 20]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty.
+            Self::do_export_kitty(who, kitty_id, eth_address)?; // Custodia y envía el mensaje.
+            Ok(())
+        }
+
+        /// Finaliza un mensaje de Ethereum (`eth_bridge::ImportMessage` en ABI) cuya prueba ya
+        /// verificó el puente: el kitty sale de la custodia hacia su destinatario.
+        pub fn import_kitty(origin: OriginFor<T>, message: [u8; eth_bridge::MESSAGE_LEN]) -> DispatchResult {
+            T::EthereumBridgeOrigin::ensure_origin(origin)?; // Solo el verificador del puente.
+            Self::do_import_kitty(&message)?; // Devuelve el kitty.
+            Ok(())
+        }
+
+        /// Reclama un kitty que volvió de Ethereum a nombre de quien llama y se quedó en custodia
+        /// porque entonces no podía recibirlo.
+        pub fn claim_parked_import(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Destinatario del mensaje de Ethereum.
+            Self::do_claim_parked_import(who, kitty_id)?; // Saca el kitty de la custodia.
+            Ok(())
+        }
+
+        /// Envuelve un kitty propio en la única unidad del activo nuevo `asset_id`, que recibe
+        /// quien llama. El token circula como cualquier activo (DEX, puentes...) y quien lo
+        /// tenga puede canjearlo por el kitty con `unwrap_kitty`.
+        pub fn wrap_kitty(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            asset_id: KittyTokenIdOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty.
+            Self::do_wrap_kitty(who, kitty_id, asset_id)?; // Custodia el kitty y acuña el token.
+            Ok(())
+        }
+
+        /// Quema el token de un kitty envuelto y entrega el kitty a quien llama.
+        pub fn unwrap_kitty(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Poseedor del token.
+            Self::do_unwrap_kitty(who, kitty_id)?; // Quema el token y devuelve el kitty.
+            Ok(())
+        }
+
+        /// Llamada por XCM cuando llega un kitty de otra parachain para `beneficiary`. Si es el
+        /// derivado de uno enviado desde aquí, el original sale de la reserva; si es un NFT
+        /// ajeno, se acuña un kitty que lo envuelve con el ADN `dna`.
+        pub fn receive_kitty(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            dna: KittyDna<T>,
+            beneficiary: T::AccountId,
+        ) -> DispatchResult {
+            let para_id = T::KittyReturnOrigin::ensure_origin(origin)?; // Parachain emisora.
+            Self::do_receive_kitty(para_id, kitty_id, dna, beneficiary)?; // Devuelve o envuelve el kitty.
+            Ok(())
+        }
+
+        /// Reparte un kitty propio entre quien llama y `co_owners`. Desde entonces, transferirlo
+        /// o devolverlo a un solo dueño (para venderlo, por ejemplo) necesita `threshold`
+        /// aprobaciones (`propose_action`, `approve_action`, `execute_action`).
+        pub fn enable_co_ownership(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            co_owners: BoundedVec<T::AccountId, T::MaxCoOwners>,
+            threshold: u32,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty.
+            Self::do_enable_co_ownership(who, kitty_id, co_owners.into_inner(), threshold)?; // Bloquea el kitty.
+            Ok(())
+        }
+
+        /// Propone una acción sobre un kitty en copropiedad; cuenta como aprobación de quien la
+        /// propone.
+        pub fn propose_action(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            action: CoOwnerAction<T::AccountId>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Copropietario.
+            Self::do_propose_action(who, kitty_id, action)?; // Guarda la propuesta.
+            Ok(())
+        }
+
+        /// Aprueba una acción pendiente.
+        pub fn approve_action(origin: OriginFor<T>, kitty_id: [u8; 32], action_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Copropietario.
+            Self::do_approve_action(who, kitty_id, action_id)?; // Suma su aprobación.
+            Ok(())
+        }
+
+        /// Ejecuta una acción que ya reúne el umbral de aprobaciones.
+        pub fn execute_action(origin: OriginFor<T>, kitty_id: [u8; 32], action_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Cualquier copropietario.
+            Self::do_execute_action(who, kitty_id, action_id)?; // Termina la copropiedad y aplica la acción.
+            Ok(())
+        }
+
+        /// Ofrece un kitty propio en alquiler a `price_per_block` durante como mucho
+        /// `max_duration` bloques. El dueño lo conserva; quien lo alquila pasa a ser su usuario.
+        /// Con `collateral` distinto de cero el alquiler se paga al terminar, y el inquilino deja
+        /// esa garantía retenida mientras tanto.
+        pub fn list_for_rent(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            price_per_block: BalanceOf<T>,
+            max_duration: BlockNumberFor<T>,
+            collateral: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty, uno de sus operadores o su agente.
+            Self::do_list_for_rent(who, kitty_id, price_per_block, max_duration, collateral)?; // Guarda el anuncio.
+            Ok(())
+        }
+
+        /// Deja de ofrecer un kitty propio en alquiler. Un alquiler en curso sigue hasta su fin.
+        pub fn unlist_for_rent(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty, uno de sus operadores o su agente.
+            Self::do_unlist_for_rent(who, kitty_id)?; // Borra el anuncio.
+            Ok(())
+        }
+
+        /// Alquila un kitty durante `duration` bloques, pagando por adelantado o, si el anuncio
+        /// pide garantía, reteniéndola hasta pagar al terminar.
+        pub fn rent(origin: OriginFor<T>, kitty_id: [u8; 32], duration: BlockNumberFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Quien alquila y paga.
+            Self::do_rent(who, kitty_id, duration)?; // Paga al dueño (o retiene la garantía) y registra el alquiler.
+            Ok(())
+        }
+
+        /// Pide terminar antes de tiempo el alquiler de un kitty. Termina cuando lo piden las
+        /// dos partes, dueño e inquilino; no se devuelve nada de lo pagado.
+        pub fn end_rental(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño o inquilino.
+            Self::do_end_rental(who, kitty_id)?; // Registra su conformidad o termina el alquiler.
+            Ok(())
+        }
+
+        /// Bloquea un kitty propio: no se puede transferir, vender, subastar, alquilar ni criar
+        /// con él hasta desbloquearlo. Si estaba a la venta, deja de estarlo.
+        pub fn lock_kitty(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty.
+            Self::do_lock_kitty(who, kitty_id)?; // Retira el kitty de la venta y lo bloquea.
+            Ok(())
+        }
+
+        /// Desbloquea un kitty propio.
+        pub fn unlock_kitty(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty.
+            Self::do_unlock_kitty(who, kitty_id)?; // Quita el bloqueo.
+            Ok(())
+        }
+
+        /// Regala un kitty propio a `to`. Si no lo reclama en `GiftExpiry` bloques, el kitty
+        /// vuelve a quedar libre con el remitente.
+        pub fn send_gift(origin: OriginFor<T>, to: T::AccountId, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty, uno de sus operadores o una cuenta aprobada.
+            Self::do_send_gift(who, to, kitty_id)?; // Bloquea el kitty y programa su caducidad.
+            Ok(())
+        }
+
+        /// Reclama un kitty regalado a quien llama.
+        pub fn claim_gift(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Destinatario del regalo.
+            Self::do_claim_gift(who, kitty_id)?; // Transfiere el kitty.
+            Ok(())
+        }
+
+        /// Transfiere varios kitties propios a `to` en una sola transacción. Si alguno no se
+        /// puede transferir, no se transfiere ninguno.
+        #[pallet::weight(T::WeightInfo::transfer_many(kitty_ids.len() as u32))]
+        pub fn transfer_many(
+            origin: OriginFor<T>,
+            to: T::AccountId,
+            kitty_ids: BoundedVec<[u8; 32], T::MaxBatchTransfer>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño de todos los kitties.
+            Self::do_transfer_many(who, to, kitty_ids.into_inner())?; // Una `do_transfer` por kitty.
+            Ok(())
+        }
+
+        /// Transfiere a `to` todos los kitties de quien llama (p. ej. al cambiar de cuenta). Falla
+        /// entera si alguno está bloqueado en una rifa, subasta, lote o venta con escrow.
+        #[pallet::weight(T::WeightInfo::transfer_all(T::MaxKittiesOwned::get()))]
+        pub fn transfer_all(origin: OriginFor<T>, to: T::AccountId) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño actual de los kitties.
+            Self::do_transfer_all(who, to)?; // Mueve todo `KittiesOwned`.
+            Ok(())
+        }
+
+        /// Permite poner un kitty en venta o quitarlo (establecer precio o None). Con
+        /// `whitelisted_buyer` solo esa cuenta puede comprarlo (venta privada acordada fuera).
+        pub fn set_price(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            new_price: Option<BalanceOf<T>>,
+            whitelisted_buyer: Option<T::AccountId>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Comprueba que sea una cuenta firmada.
+            Self::do_set_price(who, kitty_id, new_price, whitelisted_buyer)?; // Llama a la lógica de negocio para actualizar el precio.
+            Ok(())
+        }
+
+        /// Permite comprar un kitty si está en venta y el comprador ofrece suficiente balance.
+        pub fn buy_kitty(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            max_price: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Verifica que el comprador sea una cuenta válida.
+            Self::do_buy_kitty(who, kitty_id, max_price)?; // Ejecuta la lógica de compra (valida precio, transfiere fondos, cambia dueño).
+            Ok(())
+        }
+
+        /// Compra los `count` kitties más baratos en venta, a `max_price` o menos cada uno.
+        #[pallet::weight(T::WeightInfo::sweep_floor((*count).min(T::MaxSweep::get())))]
+        pub fn sweep_floor(origin: OriginFor<T>, count: u32, max_price: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Comprador de todos los kitties.
+            Self::do_sweep_floor(who, count, max_price)?; // Compra los anuncios más baratos.
+            Ok(())
+        }
+
+        /// Pone a la venta varios kitties propios juntos por un único precio.
+        #[pallet::weight(T::WeightInfo::create_bundle(kitty_ids.len() as u32))]
+        pub fn create_bundle(
+            origin: OriginFor<T>,
+            kitty_ids: BoundedVec<[u8; 32], T::MaxBundleSize>,
+            price: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño de todos los kitties del lote, uno de sus operadores o su agente.
+            Self::do_create_bundle(who, kitty_ids, price)?; // Bloquea los kitties y guarda el lote.
+            Ok(())
+        }
+
+        /// Retira un lote propio de la venta.
+        pub fn cancel_bundle(origin: OriginFor<T>, bundle_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?; // El vendedor o uno de sus operadores.
+            Self::do_cancel_bundle(who, bundle_id)?; // Libera los kitties.
+            Ok(())
+        }
+
+        /// Compra un lote entero pagando como mucho `max_price`.
+        #[pallet::weight(T::WeightInfo::buy_bundle(T::MaxBundleSize::get()))]
+        pub fn buy_bundle(
+            origin: OriginFor<T>,
+            bundle_id: u32,
+            max_price: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // El comprador recibe todos los kitties.
+            Self::do_buy_bundle(who, bundle_id, max_price)?; // Paga y transfiere el lote de una vez.
+            Ok(())
+        }
+
+        /// Propone cambiar `my_kitty` por `their_kitty`, con un pago extra opcional.
+        pub fn propose_swap(
+            origin: OriginFor<T>,
+            my_kitty: [u8; 32],
+            their_kitty: [u8; 32],
+            extra_payment: Option<BalanceOf<T>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño de `my_kitty`.
+            Self::do_propose_swap(who, my_kitty, their_kitty, extra_payment)?; // Guarda la propuesta.
+            Ok(())
+        }
+
+        /// Acepta un intercambio sobre un kitty propio.
+        pub fn accept_swap(origin: OriginFor<T>, swap_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty pedido.
+            Self::do_accept_swap(who, swap_id)?; // Cambia los kitties y el pago extra.
+            Ok(())
+        }
+
+        /// Retira un intercambio propio, o cualquiera ya caducado.
+        pub fn cancel_swap(origin: OriginFor<T>, swap_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Proponente, o cualquiera tras caducar.
+            Self::do_cancel_swap(who, swap_id)?; // Borra la propuesta.
+            Ok(())
+        }
+
+        /// Oferta `amount` por cualquier kitty, válida hasta el bloque `expiry`.
+        pub fn make_offer(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            amount: BalanceOf<T>,
+            expiry: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // El ofertante aporta el importe.
+            Self::do_make_offer(who, kitty_id, amount, expiry)?; // Guarda la oferta y su importe.
+            Ok(())
+        }
+
+        /// Acepta una oferta sobre un kitty propio.
+        pub fn accept_offer(origin: OriginFor<T>, kitty_id: [u8; 32], offer_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty, uno de sus operadores o su agente.
+            Self::do_accept_offer(who, kitty_id, offer_id)?; // Vende el kitty al ofertante.
+            Ok(())
+        }
+
+        /// Retira una oferta propia, o cualquiera ya caducada.
+        pub fn cancel_offer(origin: OriginFor<T>, kitty_id: [u8; 32], offer_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Ofertante, o cualquiera tras caducar.
+            Self::do_cancel_offer(who, kitty_id, offer_id)?; // Devuelve el importe.
+            Ok(())
+        }
+
+        /// Subasta un kitty propio durante `duration` bloques. Una puja de al menos `buy_now`
+        /// compra el kitty en el acto.
+        pub fn start_auction(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            min_bid: BalanceOf<T>,
+            duration: BlockNumberFor<T>,
+            buy_now: Option<BalanceOf<T>>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty, uno de sus operadores o su agente.
+            Self::do_start_auction(who, kitty_id, min_bid, duration, buy_now)?; // Bloquea el kitty y abre la subasta.
+            Ok(())
+        }
+
+        /// Puja en una subasta; el importe queda retenido hasta ser superado o liquidado.
+        pub fn bid(origin: OriginFor<T>, auction_id: u32, amount: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Pujador.
+            Self::do_bid(who, auction_id, amount)?; // Retiene la puja y libera la anterior.
+            Ok(())
+        }
+
+        /// Liquida una subasta terminada; cualquiera puede llamarla. Normalmente no hace falta:
+        /// `on_initialize` liquida cada subasta en su bloque final.
+        #[pallet::weight(T::WeightInfo::settle_auction())]
+        pub fn settle_auction(origin: OriginFor<T>, auction_id: u32) -> DispatchResult {
+            ensure_signed(origin)?; // Cualquier cuenta firmada.
+            Self::do_settle_auction(auction_id)?; // Entrega el kitty y paga al vendedor.
+            Ok(())
+        }
+
+        /// Pone un kitty propio a la venta por `amount` del activo `asset_id`. Sustituye al precio
+        /// en token nativo, si lo tenía.
+        pub fn set_price_in_asset(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            asset_id: AssetIdOf<T>,
+            amount: AssetBalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño, uno de sus operadores o su agente de ventas.
+            Self::do_set_price_in_asset(who, kitty_id, asset_id, amount)?; // Guarda el anuncio.
+            Ok(())
+        }
+
+        /// Compra un kitty a la venta en `asset_id`, pagando como mucho `max_amount`.
+        pub fn buy_kitty_with_asset(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            asset_id: AssetIdOf<T>,
+            max_amount: AssetBalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // El comprador paga en el activo.
+            Self::do_buy_kitty_with_asset(who, kitty_id, asset_id, max_amount)?; // Paga y cambia dueño.
+            Ok(())
+        }
+
+        /// Pone (o cambia) el nombre de un kitty pagando la tasa de nombres al bote del pallet.
+        pub fn set_name(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            name: KittyName<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Solo cuentas firmadas pueden nombrar kitties.
+            Self::do_set_name(who, kitty_id, name)?; // Valida, cobra la tasa y reserva el nombre.
+            Ok(())
+        }
+
+        /// Compra un vale regalo canjeable por un mint. Solo se guarda el hash del código; el
+        /// código en sí se entrega fuera de la cadena a quien se quiera regalar.
+        pub fn buy_voucher(origin: OriginFor<T>, code_hash: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // El comprador debe firmar (y pagar) el vale.
+            Self::do_buy_voucher(who, code_hash)?; // Cobra el precio y registra el vale.
+            Ok(())
+        }
+
+        /// Canjea un vale regalo presentando su código: crea un kitty nuevo para quien canjea.
+        pub fn redeem_voucher(origin: OriginFor<T>, code: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // El kitty se crea para quien firma el canje.
+            Self::do_redeem_voucher(who, code)?; // Verifica el código, consume el vale y hace el mint.
+            Ok(())
+        }
+
+        /// Compra un pase de temporada con `MintPassMints` mints prepagados.
+        pub fn buy_mint_pass(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?; // El pase queda asociado a la cuenta que firma.
+            Self::do_buy_mint_pass(who)?; // Cobra el pase y lo registra con su vencimiento.
+            Ok(())
+        }
+
+        /// Presta un kitty para una sola cría: `borrower` podrá usarlo como progenitor hasta
+        /// `expires_at`, pagando `fee` al dueño en ese momento.
+        pub fn grant_breeding_loan(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            borrower: T::AccountId,
+            fee: BalanceOf<T>,
+            expires_at: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Solo el dueño firmante puede prestar su kitty.
+            Self::do_grant_breeding_loan(who, kitty_id, borrower, fee, expires_at)?; // Registra el préstamo.
+            Ok(())
+        }
+
+        /// Retira el préstamo de cría de un kitty antes de que se use.
+        pub fn revoke_breeding_loan(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Solo el dueño puede retirar el préstamo.
+            Self::do_revoke_breeding_loan(who, kitty_id)?; // Borra el préstamo si existe.
+            Ok(())
+        }
+
+        /// Contrata o extiende el seguro de un kitty pagando `periods` primas al fondo del seguro.
+        pub fn pay_premium(origin: OriginFor<T>, kitty_id: [u8; 32], periods: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Solo el dueño firmante puede asegurar su kitty.
+            Self::do_pay_premium(who, kitty_id, periods)?; // Cobra las primas y extiende la cobertura.
+            Ok(())
+        }
+
+        /// Presenta un reclamo al seguro por un kitty asegurado que gobernanza quemó o confiscó.
+        pub fn file_claim(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // El reclamo lo presenta el asegurado.
+            Self::do_file_claim(who, kitty_id)?; // Verifica la pérdida y registra el reclamo.
+            Ok(())
+        }
+
+        /// El árbitro aprueba (y se paga la indemnización) o rechaza un reclamo pendiente.
+        pub fn resolve_claim(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            approve: bool,
+        ) -> DispatchResult {
+            T::ArbiterOrigin::ensure_origin(origin)?; // Solo el origen árbitro configurado.
+            Self::do_resolve_claim(kitty_id, approve)?; // Paga o deniega y guarda el resultado.
+            Ok(())
+        }
+
+        /// Pone un kitty en rifa: se venden hasta `max_tickets` boletos hasta `close_block`.
+        pub fn start_raffle(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            ticket_price: BalanceOf<T>,
+            max_tickets: u32,
+            close_block: BlockNumberFor<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Dueño del kitty, uno de sus operadores o su agente.
+            Self::do_start_raffle(who, kitty_id, ticket_price, max_tickets, close_block)?; // Valida y abre la rifa.
+            Ok(())
+        }
+
+        /// Compra un boleto de la rifa de un kitty.
+        pub fn buy_ticket(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // El boleto queda a nombre de quien firma y paga.
+            Self::do_buy_ticket(who, kitty_id)?; // Cobra el boleto y lo registra.
+            Ok(())
+        }
+
+        /// Cierra una rifa vencida (o agotada): sortea el ganador o devuelve el dinero.
+        /// Cualquiera puede llamarla.
+        pub fn close_raffle(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            ensure_signed(origin)?; // Cualquier cuenta firmada puede cerrar la rifa.
+            Self::do_close_raffle(kitty_id)?; // Sortea y reparte, o reembolsa.
+            Ok(())
+        }
+
+        /// Compra un kitty en venta dejando el pago retenido durante `EscrowPeriod` bloques.
+        pub fn buy_kitty_escrowed(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            max_price: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // El comprador firma y paga al escrow.
+            Self::do_buy_kitty_escrowed(who, kitty_id, max_price)?; // Retiene el pago y bloquea el kitty.
+            Ok(())
+        }
+
+        /// El comprador o el vendedor congelan la liquidación de una venta con escrow.
+        pub fn raise_dispute(origin: OriginFor<T>, sale_id: u32) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Solo una de las partes puede disputar.
+            Self::do_raise_dispute(who, sale_id)?; // Marca la venta en disputa con su plazo.
+            Ok(())
+        }
+
+        /// El árbitro resuelve una disputa a favor del comprador (reembolso) o del vendedor.
+        pub fn rule_dispute(origin: OriginFor<T>, sale_id: u32, buyer_wins: bool) -> DispatchResult {
+            T::ArbiterOrigin::ensure_origin(origin)?; // Solo el origen árbitro configurado.
+            Self::do_rule_dispute(sale_id, buyer_wins)?; // Liquida según el fallo.
+            Ok(())
+        }
+
+        /// Liquida una venta con escrow vencida: libera el pago al vendedor o, si la disputa
+        /// venció sin fallo, reembolsa al comprador. Cualquiera puede llamarla.
+        pub fn settle_escrowed_sale(origin: OriginFor<T>, sale_id: u32) -> DispatchResult {
+            ensure_signed(origin)?; // Cualquier cuenta firmada puede liquidar.
+            Self::do_settle_escrowed_sale(sale_id)?; // Aplica el resultado por defecto.
+            Ok(())
+        }
+
+        /// Ofrece `kitty_id` para criar con un kitty de la parachain hermana `para_id`.
+        pub fn offer_remote_breeding(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            para_id: u32,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Solo el dueño puede ofrecer su kitty.
+            Self::do_offer_remote_breeding(who, kitty_id, para_id)?; // Guarda la oferta.
+            Ok(())
+        }
+
+        /// Retira la oferta de cría remota de `kitty_id`.
+        pub fn cancel_remote_breeding(origin: OriginFor<T>, kitty_id: [u8; 32]) -> DispatchResult {
+            let who = ensure_signed(origin)?; // Solo el dueño puede retirarla.
+            Self::do_cancel_remote_breeding(who, kitty_id)?; // Borra la oferta si existe.
+            Ok(())
+        }
+
+        /// Llamada por XCM desde la parachain ofertada con el ADN atestiguado de la pareja:
+        /// la cría nace aquí, para el dueño del kitty local, y se envía un recibo de vuelta.
+        pub fn breed_with_remote(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            partner_id: [u8; 32],
+            partner_dna: KittyDna<T>,
+        ) -> DispatchResult {
+            let para_id = T::RemoteBreedingOrigin::ensure_origin(origin)?; // Parachain emisora.
+            Self::do_breed_with_remote(para_id, kitty_id, partner_id, partner_dna)?; // Cría y recibo.
+            Ok(())
+        }
+
+        /// Aporta `amount` de stake para poder tener más kitties.
+        pub fn stake_for_capacity(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?; // La cuenta que aporta el stake.
+            Self::do_stake_for_capacity(who, amount)?; // Mueve los fondos y sube el límite.
+            Ok(())
+        }
+
+        /// Retira `amount` de stake, siempre que el nuevo límite cubra los kitties que se poseen.
+        pub fn unstake_capacity(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?; // La cuenta que recupera su stake.
+            Self::do_unstake_capacity(who, amount)?; // Devuelve los fondos si es posible.
+            Ok(())
+        }
+
+        /// Marca o desmarca `kitty_id` como pase de coleccionista.
+        pub fn set_collector_pass(
+            origin: OriginFor<T>,
+            kitty_id: [u8; 32],
+            is_pass: bool,
+        ) -> DispatchResult {
+            T::CollectorPassOrigin::ensure_origin(origin)?; // Solo el origen configurado.
+            Self::do_set_collector_pass(kitty_id, is_pass)?; // Actualiza la marca.
+            Ok(())
+        }
+
+        /// El oráculo publica la tasación de un kitty o de un nivel de rareza.
+        pub fn post_appraisal(
+            origin: OriginFor<T>,
+            target: AppraisalTarget,
+            value: BalanceOf<T>,
+        ) -> DispatchResult {
+            T::OracleOrigin::ensure_origin(origin)?; // Solo el origen oráculo configurado.
+            Self::do_post_appraisal(target, value)?; // Guarda el valor con su bloque.
+            Ok(())
+        }
+    }
+
+    // --- Lecturas para carteras ---
+    // View functions de FRAME: las carteras las llaman por la API de runtime
+    // `RuntimeViewFunction`. El id de cada una sale del nombre del pallet y de su firma, así que
+    // cambiar la firma rompe a los clientes; mejor añadir una función nueva.
+    #[pallet::view_functions_experimental]
+    impl<T: Config> Pallet<T> {
+        /// Dueño de `kitty_id`, si existe.
+        pub fn owner_of(kitty_id: [u8; 32]) -> Option<T::AccountId> {
+            Kitties::<T>::get(kitty_id).map(|kitty| kitty.owner)
+        }
+
+        /// Precio de venta de `kitty_id` en token nativo, si está a la venta.
+        pub fn price_of(kitty_id: [u8; 32]) -> Option<BalanceOf<T>> {
+            Kitties::<T>::get(kitty_id).and_then(|kitty| kitty.price)
+        }
+
+        /// Kitties que existen.
+        pub fn total_supply() -> u32 {
+            CountForKitties::<T>::get()
+        }
+
+        /// Kitties de `account`.
+        pub fn owned_count(account: T::AccountId) -> u32 {
+            KittiesOwned::<T>::decode_len(&account).unwrap_or(0) as u32
         }
     }
 }
@@ -0,0 +1,669 @@
+// Migraciones de almacenamiento del pallet.
+//
+// Cada versión vive en su propio módulo (`v1`, `v2`, ...) y se envuelve en `VersionedMigration`,
+// que comprueba la versión on-chain antes de ejecutar y la actualiza al terminar. El runtime solo
+// tiene que añadir la migración a la lista de `Executive`.
+
+use super::*;
+use frame::arithmetic::Saturating;
+use frame::arithmetic::Zero;
+use frame::deps::frame_support::migrations::MigrationId;
+use frame::deps::frame_support::migrations::SteppedMigration;
+use frame::deps::frame_support::migrations::SteppedMigrationError;
+use frame::deps::frame_support::migrations::VersionedMigration;
+use frame::deps::frame_support::storage::unhashed;
+use frame::deps::frame_support::storage::StoragePrefixedMap;
+use frame::deps::frame_support::storage_alias;
+use frame::deps::frame_support::weights::WeightMeter;
+use frame::deps::sp_io;
+use frame::traits::UncheckedOnRuntimeUpgrade;
+
+/// Identificador de las migraciones multi-bloque de este pallet.
+pub const PALLET_MIGRATIONS_ID: &[u8; 14] = b"pallet-kitties";
+
+/// Formato de almacenamiento anterior a la versión 1.
+pub mod v0 {
+    use super::*;
+
+    /// `Kitty` antes de la versión 2: el ADN era siempre el identificador de 32 bytes.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct OldKitty<T: Config> {
+        pub dna: [u8; 32],
+        pub owner: T::AccountId,
+        pub price: Option<BalanceOf<T>>,
+    }
+
+    /// `Kitties` tal como quedaba con el hasher por defecto de `dev_mode` (`Blake2_128Concat`).
+    #[storage_alias]
+    pub type Kitties<T: Config> = StorageMap<Pallet<T>, Blake2_128Concat, [u8; 32], OldKitty<T>>;
+}
+
+/// v0 -> v1: `Kitties` pasa de `Blake2_128Concat` a `Twox64Concat`.
+///
+/// `KittiesOwned` ya usaba `Blake2_128Concat`, así que sus claves no cambian.
+pub mod v1 {
+    use super::*;
+    use v0::OldKitty;
+
+    /// `Kitties` en v1: nuevo hasher, pero los valores siguen en el formato viejo.
+    #[storage_alias]
+    pub type Kitties<T: Config> = StorageMap<Pallet<T>, Twox64Concat, [u8; 32], OldKitty<T>>;
+
+    pub struct InnerMigrateV0ToV1<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV0ToV1<T> {
+        fn on_runtime_upgrade() -> Weight {
+            // Las claves viejas y nuevas comparten prefijo, así que primero se vacía el mapa viejo
+            // y después se reinsertan las entradas con el nuevo hasher.
+            let old = v0::Kitties::<T>::drain().collect::<Vec<_>>();
+            let migrated = old.len() as u64;
+            for (kitty_id, kitty) in old {
+                v1::Kitties::<T>::insert(kitty_id, kitty);
+            }
+            T::DbWeight::get().reads_writes(migrated, migrated.saturating_mul(2))
+        }
+    }
+
+    /// Migración lista para el runtime: solo se ejecuta si la versión on-chain es 0.
+    pub type MigrateV0ToV1<T> = VersionedMigration<
+        0,
+        1,
+        InnerMigrateV0ToV1<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// Versión multi-bloque (`SteppedMigration`) de v0 -> v1, para cadenas con cientos de miles de
+/// kitties donde vaciar y reinsertar todo `Kitties` no cabe en un solo bloque.
+///
+/// Se usa con `pallet-migrations` en lugar de `v1::MigrateV0ToV1`, nunca junto a ella.
+pub mod v1_stepped {
+    use super::*;
+
+    /// Longitud de una clave cruda de `Kitties` con el hasher viejo:
+    /// prefijo (32) + `Blake2_128Concat` (16 + 32).
+    pub const OLD_KEY_LEN: u32 = 32 + 16 + 32;
+
+    /// Cursor: la clave cruda de la última entrada visitada.
+    pub type Cursor = BoundedVec<u8, ConstU32<OLD_KEY_LEN>>;
+
+    pub struct LazyMigrateV0ToV1<T>(PhantomData<T>);
+
+    impl<T: Config> LazyMigrateV0ToV1<T> {
+        /// Peso de migrar una entrada: leer y borrar la vieja, escribir la nueva.
+        pub fn step_weight() -> Weight {
+            T::DbWeight::get().reads_writes(2, 2)
+        }
+    }
+
+    impl<T: Config> SteppedMigration for LazyMigrateV0ToV1<T> {
+        type Cursor = Cursor;
+        type Identifier = MigrationId<14>;
+
+        fn id() -> Self::Identifier {
+            MigrationId { pallet_id: *PALLET_MIGRATIONS_ID, version_from: 0, version_to: 1 }
+        }
+
+        fn step(
+            cursor: Option<Self::Cursor>,
+            meter: &mut WeightMeter,
+        ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+            // Si la cadena ya está en v1 no hay nada que hacer.
+            if cursor.is_none() && Pallet::<T>::on_chain_storage_version() >= 1 {
+                return Ok(None);
+            }
+
+            let required = Self::step_weight();
+            if meter.remaining().any_lt(required) {
+                return Err(SteppedMigrationError::InsufficientWeight { required });
+            }
+
+            // Claves viejas y nuevas comparten prefijo; se recorre el espacio de claves crudo y
+            // se distinguen por longitud, así las ya migradas simplemente se saltan.
+            let prefix = v0::Kitties::<T>::final_prefix();
+            let mut last_key = cursor.map(|c| c.into_inner()).unwrap_or_else(|| prefix.to_vec());
+
+            while meter.try_consume(required).is_ok() {
+                let Some(key) = sp_io::storage::next_key(&last_key).filter(|k| k.starts_with(&prefix))
+                else {
+                    // Fin del mapa: se marca la nueva versión.
+                    StorageVersion::new(1).put::<Pallet<T>>();
+                    return Ok(None);
+                };
+
+                if key.len() == OLD_KEY_LEN as usize {
+                    // El `kitty_id` va en claro al final de la clave (`Blake2_128Concat`).
+                    let kitty_id: [u8; 32] = key[key.len() - 32..]
+                        .try_into()
+                        .map_err(|_| SteppedMigrationError::Failed)?;
+                    if let Some(kitty) = unhashed::take::<v0::OldKitty<T>>(&key) {
+                        v1::Kitties::<T>::insert(kitty_id, kitty);
+                    }
+                }
+                last_key = key;
+            }
+
+            let cursor = last_key.try_into().map_err(|_| SteppedMigrationError::InvalidCursor)?;
+            Ok(Some(cursor))
+        }
+    }
+}
+
+/// v1 -> v2: el ADN de `Kitty` pasa de `[u8; 32]` a `BoundedVec<u8, DnaLength>`.
+///
+/// El genoma nuevo se deriva del ADN viejo con `Pallet::expand_dna`, igual que en un mint.
+pub mod v2 {
+    use super::*;
+
+    /// `Kitty` en la versión 2: genoma configurable, todavía sin enfriamiento de cría.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct OldKitty<T: Config> {
+        pub dna: KittyDna<T>,
+        pub owner: T::AccountId,
+        pub price: Option<BalanceOf<T>>,
+    }
+
+    /// `Kitties` con los valores en el formato de la versión 2.
+    #[storage_alias]
+    pub type Kitties<T: Config> = StorageMap<Pallet<T>, Twox64Concat, [u8; 32], OldKitty<T>>;
+
+    pub struct InnerMigrateV1ToV2<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV1ToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut migrated = 0u64;
+            Kitties::<T>::translate::<v0::OldKitty<T>, _>(|_, old| {
+                migrated.saturating_inc();
+                Some(OldKitty {
+                    dna: Pallet::<T>::expand_dna(&old.dna),
+                    owner: old.owner,
+                    price: old.price,
+                })
+            });
+            T::DbWeight::get().reads_writes(migrated, migrated)
+        }
+    }
+
+    /// Migración lista para el runtime: solo se ejecuta si la versión on-chain es 1.
+    pub type MigrateV1ToV2<T> = VersionedMigration<
+        1,
+        2,
+        InnerMigrateV1ToV2<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v2 -> v3: `Kitty` gana `cooldown_until`. Los kitties existentes pueden criar de inmediato.
+pub mod v3 {
+    use super::*;
+
+    /// `Kitty` en la versión 3: con enfriamiento de cría, todavía sin generación.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct OldKitty<T: Config> {
+        pub dna: KittyDna<T>,
+        pub owner: T::AccountId,
+        pub price: Option<BalanceOf<T>>,
+        pub cooldown_until: BlockNumberFor<T>,
+    }
+
+    /// `Kitties` con los valores en el formato de la versión 3.
+    #[storage_alias]
+    pub type Kitties<T: Config> = StorageMap<Pallet<T>, Twox64Concat, [u8; 32], OldKitty<T>>;
+
+    pub struct InnerMigrateV2ToV3<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV2ToV3<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut migrated = 0u64;
+            Kitties::<T>::translate::<v2::OldKitty<T>, _>(|_, old| {
+                migrated.saturating_inc();
+                Some(OldKitty {
+                    dna: old.dna,
+                    owner: old.owner,
+                    price: old.price,
+                    cooldown_until: Zero::zero(),
+                })
+            });
+            T::DbWeight::get().reads_writes(migrated, migrated)
+        }
+    }
+
+    /// Migración lista para el runtime: solo se ejecuta si la versión on-chain es 2.
+    pub type MigrateV2ToV3<T> = VersionedMigration<
+        2,
+        3,
+        InnerMigrateV2ToV3<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v3 -> v4: `Kitty` gana `generation`. Antes no había cría, así que todos son de generación 0.
+pub mod v4 {
+    use super::*;
+
+    /// `Kitty` en la versión 4: con generación, todavía sin progenitores.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct OldKitty<T: Config> {
+        pub dna: KittyDna<T>,
+        pub owner: T::AccountId,
+        pub price: Option<BalanceOf<T>>,
+        pub cooldown_until: BlockNumberFor<T>,
+        pub generation: u32,
+    }
+
+    /// `Kitties` con los valores en el formato de la versión 4.
+    #[storage_alias]
+    pub type Kitties<T: Config> = StorageMap<Pallet<T>, Twox64Concat, [u8; 32], OldKitty<T>>;
+
+    pub struct InnerMigrateV3ToV4<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV3ToV4<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut migrated = 0u64;
+            Kitties::<T>::translate::<v3::OldKitty<T>, _>(|_, old| {
+                migrated.saturating_inc();
+                Some(OldKitty {
+                    dna: old.dna,
+                    owner: old.owner,
+                    price: old.price,
+                    cooldown_until: old.cooldown_until,
+                    generation: 0,
+                })
+            });
+            T::DbWeight::get().reads_writes(migrated, migrated)
+        }
+    }
+
+    /// Migración lista para el runtime: solo se ejecuta si la versión on-chain es 3.
+    pub type MigrateV3ToV4<T> = VersionedMigration<
+        3,
+        4,
+        InnerMigrateV3ToV4<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v4 -> v5: `Kitty` gana `parents`. Los kitties de generación 0 no tienen progenitores; los
+/// criados antes de esta versión pierden el dato, que no se guardaba.
+pub mod v5 {
+    use super::*;
+
+    /// `Kitty` en la versión 5: con progenitores, todavía sin rareza.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct OldKitty<T: Config> {
+        pub dna: KittyDna<T>,
+        pub owner: T::AccountId,
+        pub price: Option<BalanceOf<T>>,
+        pub cooldown_until: BlockNumberFor<T>,
+        pub generation: u32,
+        pub parents: Option<([u8; 32], [u8; 32])>,
+    }
+
+    /// `Kitties` con los valores en el formato de la versión 5.
+    #[storage_alias]
+    pub type Kitties<T: Config> = StorageMap<Pallet<T>, Twox64Concat, [u8; 32], OldKitty<T>>;
+
+    pub struct InnerMigrateV4ToV5<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV4ToV5<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut migrated = 0u64;
+            Kitties::<T>::translate::<v4::OldKitty<T>, _>(|_, old| {
+                migrated.saturating_inc();
+                Some(OldKitty {
+                    dna: old.dna,
+                    owner: old.owner,
+                    price: old.price,
+                    cooldown_until: old.cooldown_until,
+                    generation: old.generation,
+                    parents: None,
+                })
+            });
+            T::DbWeight::get().reads_writes(migrated, migrated)
+        }
+    }
+
+    /// Migración lista para el runtime: solo se ejecuta si la versión on-chain es 4.
+    pub type MigrateV4ToV5<T> = VersionedMigration<
+        4,
+        5,
+        InnerMigrateV4ToV5<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v5 -> v6: `Kitty` gana `rarity`, calculada a partir de su genoma, y se rellena el recuento
+/// de kitties por nivel de rareza (`RarityTierCounts`).
+pub mod v6 {
+    use super::*;
+
+    /// `Kitty` en la versión 6: con rareza, todavía sin edición limitada.
+    #[derive(Encode, Decode, TypeInfo, MaxEncodedLen)]
+    #[scale_info(skip_type_params(T))]
+    pub struct OldKitty<T: Config> {
+        pub dna: KittyDna<T>,
+        pub owner: T::AccountId,
+        pub price: Option<BalanceOf<T>>,
+        pub cooldown_until: BlockNumberFor<T>,
+        pub generation: u32,
+        pub parents: Option<([u8; 32], [u8; 32])>,
+        pub rarity: u16,
+    }
+
+    /// `Kitties` con los valores en el formato de la versión 6.
+    #[storage_alias]
+    pub type Kitties<T: Config> = StorageMap<Pallet<T>, Twox64Concat, [u8; 32], OldKitty<T>>;
+
+    pub struct InnerMigrateV5ToV6<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV5ToV6<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut migrated = 0u64;
+            let mut tiers: Vec<u8> = Vec::new();
+            Kitties::<T>::translate::<v5::OldKitty<T>, _>(|_, old| {
+                migrated.saturating_inc();
+                let rarity = Pallet::<T>::rarity_score(&old.dna);
+                tiers.push(Pallet::<T>::rarity_tier(rarity));
+                Some(OldKitty {
+                    dna: old.dna,
+                    owner: old.owner,
+                    price: old.price,
+                    cooldown_until: old.cooldown_until,
+                    generation: old.generation,
+                    parents: old.parents,
+                    rarity,
+                })
+            });
+            for tier in &tiers {
+                RarityTierCounts::<T>::mutate(tier, |count| count.saturating_inc());
+            }
+            T::DbWeight::get().reads_writes(migrated, migrated.saturating_add(tiers.len() as u64))
+        }
+    }
+
+    /// Migración lista para el runtime: solo se ejecuta si la versión on-chain es 5.
+    pub type MigrateV5ToV6<T> = VersionedMigration<
+        5,
+        6,
+        InnerMigrateV5ToV6<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v6 -> v7: se crea el índice `KittiesByTrait` con los kitties existentes.
+pub mod v7 {
+    use super::*;
+
+    pub struct InnerMigrateV6ToV7<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV6ToV7<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut indexed = 0u64;
+            for (kitty_id, kitty) in v6::Kitties::<T>::iter() {
+                indexed.saturating_inc();
+                Pallet::<T>::index_traits(&kitty_id, &Pallet::<T>::attributes(&kitty.dna), true);
+            }
+            let traits = KittyAttributes::TRAIT_KINDS as u64;
+            T::DbWeight::get().reads_writes(indexed, indexed.saturating_mul(traits))
+        }
+    }
+
+    /// Migración lista para el runtime: solo se ejecuta si la versión on-chain es 6.
+    pub type MigrateV6ToV7<T> = VersionedMigration<
+        6,
+        7,
+        InnerMigrateV6ToV7<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v7 -> v8: `Kitty` gana `special_edition`. Los kitties existentes no son de ninguna temporada.
+pub mod v8 {
+    use super::*;
+
+    pub struct InnerMigrateV7ToV8<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV7ToV8<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut migrated = 0u64;
+            Kitties::<T>::translate::<v6::OldKitty<T>, _>(|_, old| {
+                migrated.saturating_inc();
+                Some(Kitty {
+                    dna: old.dna,
+                    owner: old.owner,
+                    price: old.price,
+                    cooldown_until: old.cooldown_until,
+                    generation: old.generation,
+                    parents: old.parents,
+                    rarity: old.rarity,
+                    special_edition: None,
+                })
+            });
+            T::DbWeight::get().reads_writes(migrated, migrated)
+        }
+    }
+
+    /// Migración lista para el runtime: solo se ejecuta si la versión on-chain es 7.
+    pub type MigrateV7ToV8<T> = VersionedMigration<
+        7,
+        8,
+        InnerMigrateV7ToV8<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// v8 -> v9: se indexan los anuncios existentes en `ListingsByPrice`, `FloorPrice` y
+/// `MarketStats`. El volumen y las ventas anteriores no se conocen y empiezan en cero.
+pub mod v9 {
+    use super::*;
+
+    pub struct InnerMigrateV8ToV9<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV8ToV9<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut read = 0u64;
+            let mut listed = 0u64;
+            for (kitty_id, kitty) in Kitties::<T>::iter() {
+                read.saturating_inc();
+                if let Some(price) = kitty.price {
+                    listed.saturating_inc();
+                    Pallet::<T>::track_listing(kitty_id, None, Some(price));
+                }
+            }
+            T::DbWeight::get().reads_writes(read, listed.saturating_mul(3))
+        }
+    }
+
+    /// Migración lista para el runtime: solo se ejecuta si la versión on-chain es 8.
+    pub type MigrateV8ToV9<T> = VersionedMigration<
+        8,
+        9,
+        InnerMigrateV8ToV9<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+/// Versión multi-bloque de v1 -> v9 en una sola migración (`SteppedMigration`), para cadenas
+/// donde cada una de `MigrateV1ToV2` .. `MigrateV8ToV9` recorre todo `Kitties` en un bloque.
+///
+/// Parte de la versión on-chain que haya, de 1 a 8: lee cada entrada en el formato de esa versión,
+/// la convierte al `Kitty` actual y rellena solo los índices de las migraciones pendientes
+/// (`RarityTierCounts` antes de v6, `KittiesByTrait` antes de v7 y los anuncios antes de v9). La
+/// versión no cambia hasta el final, así que todos los pasos leen el mismo formato.
+///
+/// Se usa con `pallet-migrations` en lugar de `v2::MigrateV1ToV2` .. `v9::MigrateV8ToV9`, nunca
+/// junto a ellas. En una cadena en v0 va detrás de `v1_stepped::LazyMigrateV0ToV1`.
+pub mod v9_stepped {
+    use super::*;
+
+    /// Cursor: el `kitty_id` de la última entrada migrada.
+    pub type Cursor = [u8; 32];
+
+    pub struct LazyMigrateV1ToV9<T>(PhantomData<T>);
+
+    impl<T: Config> LazyMigrateV1ToV9<T> {
+        /// Peso de migrar una entrada desde v1, el peor caso: leer la clave y el kitty,
+        /// reescribirlo y actualizar el recuento de rareza, un rasgo por tipo y los índices del
+        /// anuncio.
+        pub fn step_weight() -> Weight {
+            let traits = KittyAttributes::TRAIT_KINDS as u64;
+            T::DbWeight::get().reads_writes(5, traits.saturating_add(5))
+        }
+
+        /// El `Kitty` actual que corresponde a `raw`, guardado en el formato de la versión `from`.
+        fn upgrade(from: StorageVersion, raw: &[u8]) -> Option<Kitty<T>> {
+            let input = &mut &raw[..];
+            // Hasta v5 los campos se van añadiendo; la rareza se calcula igual que en v6.
+            let old = if from < 2 {
+                let old = v0::OldKitty::<T>::decode(input).ok()?;
+                v5::OldKitty::<T> {
+                    dna: Pallet::<T>::expand_dna(&old.dna),
+                    owner: old.owner,
+                    price: old.price,
+                    cooldown_until: Zero::zero(),
+                    generation: 0,
+                    parents: None,
+                }
+            } else if from < 3 {
+                let old = v2::OldKitty::<T>::decode(input).ok()?;
+                v5::OldKitty::<T> {
+                    dna: old.dna,
+                    owner: old.owner,
+                    price: old.price,
+                    cooldown_until: Zero::zero(),
+                    generation: 0,
+                    parents: None,
+                }
+            } else if from < 4 {
+                let old = v3::OldKitty::<T>::decode(input).ok()?;
+                v5::OldKitty::<T> {
+                    dna: old.dna,
+                    owner: old.owner,
+                    price: old.price,
+                    cooldown_until: old.cooldown_until,
+                    generation: 0,
+                    parents: None,
+                }
+            } else if from < 5 {
+                let old = v4::OldKitty::<T>::decode(input).ok()?;
+                v5::OldKitty::<T> {
+                    dna: old.dna,
+                    owner: old.owner,
+                    price: old.price,
+                    cooldown_until: old.cooldown_until,
+                    generation: old.generation,
+                    parents: None,
+                }
+            } else if from < 6 {
+                v5::OldKitty::<T>::decode(input).ok()?
+            } else if from < 8 {
+                // v7 solo añadió un índice; el formato sigue siendo el de v6.
+                let old = v6::OldKitty::<T>::decode(input).ok()?;
+                return Some(Kitty {
+                    dna: old.dna,
+                    owner: old.owner,
+                    price: old.price,
+                    cooldown_until: old.cooldown_until,
+                    generation: old.generation,
+                    parents: old.parents,
+                    rarity: old.rarity,
+                    special_edition: None,
+                });
+            } else {
+                return Kitty::<T>::decode(input).ok();
+            };
+            Some(Kitty {
+                rarity: Pallet::<T>::rarity_score(&old.dna),
+                dna: old.dna,
+                owner: old.owner,
+                price: old.price,
+                cooldown_until: old.cooldown_until,
+                generation: old.generation,
+                parents: old.parents,
+                special_edition: None,
+            })
+        }
+    }
+
+    impl<T: Config> SteppedMigration for LazyMigrateV1ToV9<T> {
+        type Cursor = Cursor;
+        type Identifier = MigrationId<14>;
+
+        fn id() -> Self::Identifier {
+            MigrationId { pallet_id: *PALLET_MIGRATIONS_ID, version_from: 1, version_to: 9 }
+        }
+
+        fn step(
+            cursor: Option<Self::Cursor>,
+            meter: &mut WeightMeter,
+        ) -> Result<Option<Self::Cursor>, SteppedMigrationError> {
+            let from = Pallet::<T>::on_chain_storage_version();
+            // Si la cadena ya está en v9 no hay nada que hacer; en v0 faltaría rehashear antes.
+            if from >= 9 {
+                return Ok(None);
+            }
+            if from < 1 {
+                return Err(SteppedMigrationError::Failed);
+            }
+
+            let required = Self::step_weight();
+            if meter.remaining().any_lt(required) {
+                return Err(SteppedMigrationError::InsufficientWeight { required });
+            }
+
+            // Solo se leen las claves, que no cambian de formato; reescribir una entrada existente
+            // no altera el recorrido.
+            let mut kitty_ids = match cursor {
+                Some(last) => Kitties::<T>::iter_keys_from(Kitties::<T>::hashed_key_for(last)),
+                None => Kitties::<T>::iter_keys(),
+            };
+            let mut last = cursor;
+
+            while meter.try_consume(required).is_ok() {
+                let Some(kitty_id) = kitty_ids.next() else {
+                    // Fin del mapa: se marca la nueva versión.
+                    StorageVersion::new(9).put::<Pallet<T>>();
+                    return Ok(None);
+                };
+                last = Some(kitty_id);
+
+                // Como `translate` en las migraciones de un bloque, una entrada que no se puede
+                // decodificar se borra.
+                let key = Kitties::<T>::hashed_key_for(kitty_id);
+                let Some(kitty) = unhashed::get_raw(&key).and_then(|raw| Self::upgrade(from, &raw))
+                else {
+                    unhashed::kill(&key);
+                    continue;
+                };
+
+                if from < 6 {
+                    let tier = Pallet::<T>::rarity_tier(kitty.rarity);
+                    RarityTierCounts::<T>::mutate(tier, |count| count.saturating_inc());
+                }
+                if from < 7 {
+                    Pallet::<T>::index_traits(&kitty_id, &Pallet::<T>::attributes(&kitty.dna), true);
+                }
+                if let Some(price) = kitty.price {
+                    Pallet::<T>::track_listing(kitty_id, None, Some(price));
+                }
+                Kitties::<T>::insert(kitty_id, kitty);
+            }
+
+            Ok(last)
+        }
+    }
+}
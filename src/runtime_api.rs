@@ -0,0 +1,45 @@
+// API de runtime del pallet. El runtime la implementa delegando en `Pallet`, y los nodos y
+// pasarelas la consultan por RPC (`state_call`) sin necesidad de un indexador.
+
+use crate::KittyImageSeed;
+use crate::KittyInfo;
+use crate::KittyPage;
+use crate::PageCursor;
+use alloc::vec::Vec;
+use codec::Codec;
+
+frame::deps::sp_api::decl_runtime_apis! {
+    /// Consultas sobre kitties. Las listas se piden por páginas de como mucho
+    /// `Pallet::MAX_QUERY_PAGE` kitties.
+    pub trait KittiesApi<AccountId, Balance, BlockNumber>
+    where
+        AccountId: Codec,
+        Balance: Codec,
+        BlockNumber: Codec,
+    {
+        /// `kitty_id`, si existe.
+        fn kitty(kitty_id: [u8; 32]) -> Option<KittyInfo<AccountId, Balance, BlockNumber>>;
+
+        /// Ids de los kitties de `owner` en orden de id, como mucho `limit`, desde `cursor`
+        /// (`None` para empezar; luego el `next` de la página anterior).
+        fn owned_kitties(owner: AccountId, cursor: Option<PageCursor>, limit: u32) -> KittyPage<[u8; 32]>;
+
+        /// Como `owned_kitties`, pero con los datos de cada kitty.
+        fn kitties_of(
+            owner: AccountId,
+            cursor: Option<PageCursor>,
+            limit: u32,
+        ) -> KittyPage<KittyInfo<AccountId, Balance, BlockNumber>>;
+
+        /// Kitties a la venta en token nativo después de `cursor` (el último de la página
+        /// anterior, o `None` para empezar), como mucho `limit`.
+        fn kitties_for_sale(cursor: Option<[u8; 32]>, limit: u32) -> Vec<KittyInfo<AccountId, Balance, BlockNumber>>;
+
+        /// Metadatos de `kitty_id` en JSON de ERC-721 (ver `Pallet::token_metadata`); vacío si
+        /// el kitty no existe. Una pasarela puede servirlos tal cual como `tokenURI`.
+        fn token_metadata(kitty_id: [u8; 32]) -> Vec<u8>;
+
+        /// Semilla y paleta con las que se dibuja `kitty_id` (ver `Pallet::kitty_image_seed`), si existe.
+        fn image_seed(kitty_id: [u8; 32]) -> Option<KittyImageSeed>;
+    }
+}
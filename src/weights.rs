@@ -0,0 +1,188 @@
+// Pesos de las extrínsecas y de los cierres de `on_initialize` con benchmark (ver
+// `benchmarking.rs`).
+//
+// Generados con esos benchmarks sobre el runtime de los tests, ejecutados en nativo (`--release`,
+// 20 repeticiones por valor del componente) y ajustados por mínimos cuadrados como hace
+// `frame-omni-bencher`. Las lecturas y escrituras son las claves distintas que toca cada llamada,
+// sin las de `System` ni `TotalIssuance` que los runtimes dejan fuera. Los tiempos son de una
+// máquina de desarrollo y sin wasm, no del hardware de referencia: cada runtime tiene que
+// regenerarlos con `frame-omni-bencher` antes de producción. Un runtime usa
+// `SubstrateWeight<Runtime>` con su `DbWeight`; los tests, `()`.
+
+use core::marker::PhantomData;
+use frame::deps::frame_support::weights::constants::RocksDbWeight;
+use frame::prelude::*;
+
+/// Pesos de las extrínsecas de `pallet_kitties` y de los cierres de `on_initialize` que tienen
+/// benchmark.
+pub trait WeightInfo {
+    /// `force_mint_batch` con `n` destinatarios.
+    fn force_mint_batch(n: u32) -> Weight;
+    /// `transfer_all` de una cuenta con `n` kitties, todos a la venta.
+    fn transfer_all(n: u32) -> Weight;
+    /// `transfer_many` de `n` kitties, todos a la venta.
+    fn transfer_many(n: u32) -> Weight;
+    /// `sweep_floor` que compra `n` anuncios.
+    fn sweep_floor(n: u32) -> Weight;
+    /// `create_bundle` con `n` kitties, todos a la venta por separado.
+    fn create_bundle(n: u32) -> Weight;
+    /// `buy_bundle` de un lote de `n` kitties.
+    fn buy_bundle(n: u32) -> Weight;
+    /// Liquidar una subasta con puja, con `settle_auction` o en `on_initialize`.
+    fn settle_auction() -> Weight;
+    /// Cerrar un alquiler vencido en `on_initialize`.
+    fn close_rental() -> Weight;
+    /// Devolver un regalo caducado en `on_initialize`.
+    fn reclaim_gift() -> Weight;
+}
+
+/// Pesos para un runtime, con su `DbWeight`.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    fn force_mint_batch(n: u32) -> Weight {
+        Weight::from_parts(19_648_800, 0)
+            .saturating_add(Weight::from_parts(60_788_860, 0).saturating_mul(n.into()))
+            .saturating_add(T::DbWeight::get().reads(12))
+            .saturating_add(T::DbWeight::get().reads(4u64.saturating_mul(n.into())))
+            .saturating_add(T::DbWeight::get().writes(3))
+            .saturating_add(T::DbWeight::get().writes(8u64.saturating_mul(n.into())))
+    }
+
+    fn transfer_all(n: u32) -> Weight {
+        Weight::from_parts(192_718_000, 0)
+            .saturating_add(Weight::from_parts(258_563_333, 0).saturating_mul(n.into()))
+            .saturating_add(T::DbWeight::get().reads(15))
+            .saturating_add(T::DbWeight::get().reads(11u64.saturating_mul(n.into())))
+            .saturating_add(T::DbWeight::get().writes(6))
+            .saturating_add(T::DbWeight::get().writes(17u64.saturating_mul(n.into())))
+    }
+
+    fn transfer_many(n: u32) -> Weight {
+        Weight::from_parts(28_577_333, 0)
+            .saturating_add(Weight::from_parts(106_336_050, 0).saturating_mul(n.into()))
+            .saturating_add(T::DbWeight::get().reads(11))
+            .saturating_add(T::DbWeight::get().reads(12u64.saturating_mul(n.into())))
+            .saturating_add(T::DbWeight::get().writes(6))
+            .saturating_add(T::DbWeight::get().writes(17u64.saturating_mul(n.into())))
+    }
+
+    fn sweep_floor(n: u32) -> Weight {
+        Weight::from_parts(25_561_766, 0)
+            .saturating_add(Weight::from_parts(227_185_900, 0).saturating_mul(n.into()))
+            .saturating_add(T::DbWeight::get().reads(17))
+            .saturating_add(T::DbWeight::get().reads(17u64.saturating_mul(n.into())))
+            .saturating_add(T::DbWeight::get().writes(8))
+            .saturating_add(T::DbWeight::get().writes(18u64.saturating_mul(n.into())))
+    }
+
+    fn create_bundle(n: u32) -> Weight {
+        Weight::from_parts(28_677_666, 0)
+            .saturating_add(Weight::from_parts(39_294_350, 0).saturating_mul(n.into()))
+            .saturating_add(T::DbWeight::get().reads(9))
+            .saturating_add(T::DbWeight::get().reads(9u64.saturating_mul(n.into())))
+            .saturating_add(T::DbWeight::get().writes(5))
+            .saturating_add(T::DbWeight::get().writes(6u64.saturating_mul(n.into())))
+    }
+
+    fn buy_bundle(n: u32) -> Weight {
+        Weight::from_parts(90_666_033, 0)
+            .saturating_add(Weight::from_parts(142_082_850, 0).saturating_mul(n.into()))
+            .saturating_add(T::DbWeight::get().reads(16))
+            .saturating_add(T::DbWeight::get().reads(11u64.saturating_mul(n.into())))
+            .saturating_add(T::DbWeight::get().writes(8))
+            .saturating_add(T::DbWeight::get().writes(17u64.saturating_mul(n.into())))
+    }
+
+    fn settle_auction() -> Weight {
+        Weight::from_parts(95_899_000, 0)
+            .saturating_add(T::DbWeight::get().reads(21))
+            .saturating_add(T::DbWeight::get().writes(27))
+    }
+
+    fn close_rental() -> Weight {
+        Weight::from_parts(38_044_000, 0)
+            .saturating_add(T::DbWeight::get().reads(7))
+            .saturating_add(T::DbWeight::get().writes(6))
+    }
+
+    fn reclaim_gift() -> Weight {
+        Weight::from_parts(8_348_000, 0)
+            .saturating_add(T::DbWeight::get().reads(2))
+            .saturating_add(T::DbWeight::get().writes(2))
+    }
+}
+
+// Los mismos pesos con `RocksDbWeight`, para los tests.
+impl WeightInfo for () {
+    fn force_mint_batch(n: u32) -> Weight {
+        Weight::from_parts(19_648_800, 0)
+            .saturating_add(Weight::from_parts(60_788_860, 0).saturating_mul(n.into()))
+            .saturating_add(RocksDbWeight::get().reads(12))
+            .saturating_add(RocksDbWeight::get().reads(4u64.saturating_mul(n.into())))
+            .saturating_add(RocksDbWeight::get().writes(3))
+            .saturating_add(RocksDbWeight::get().writes(8u64.saturating_mul(n.into())))
+    }
+
+    fn transfer_all(n: u32) -> Weight {
+        Weight::from_parts(192_718_000, 0)
+            .saturating_add(Weight::from_parts(258_563_333, 0).saturating_mul(n.into()))
+            .saturating_add(RocksDbWeight::get().reads(15))
+            .saturating_add(RocksDbWeight::get().reads(11u64.saturating_mul(n.into())))
+            .saturating_add(RocksDbWeight::get().writes(6))
+            .saturating_add(RocksDbWeight::get().writes(17u64.saturating_mul(n.into())))
+    }
+
+    fn transfer_many(n: u32) -> Weight {
+        Weight::from_parts(28_577_333, 0)
+            .saturating_add(Weight::from_parts(106_336_050, 0).saturating_mul(n.into()))
+            .saturating_add(RocksDbWeight::get().reads(11))
+            .saturating_add(RocksDbWeight::get().reads(12u64.saturating_mul(n.into())))
+            .saturating_add(RocksDbWeight::get().writes(6))
+            .saturating_add(RocksDbWeight::get().writes(17u64.saturating_mul(n.into())))
+    }
+
+    fn sweep_floor(n: u32) -> Weight {
+        Weight::from_parts(25_561_766, 0)
+            .saturating_add(Weight::from_parts(227_185_900, 0).saturating_mul(n.into()))
+            .saturating_add(RocksDbWeight::get().reads(17))
+            .saturating_add(RocksDbWeight::get().reads(17u64.saturating_mul(n.into())))
+            .saturating_add(RocksDbWeight::get().writes(8))
+            .saturating_add(RocksDbWeight::get().writes(18u64.saturating_mul(n.into())))
+    }
+
+    fn create_bundle(n: u32) -> Weight {
+        Weight::from_parts(28_677_666, 0)
+            .saturating_add(Weight::from_parts(39_294_350, 0).saturating_mul(n.into()))
+            .saturating_add(RocksDbWeight::get().reads(9))
+            .saturating_add(RocksDbWeight::get().reads(9u64.saturating_mul(n.into())))
+            .saturating_add(RocksDbWeight::get().writes(5))
+            .saturating_add(RocksDbWeight::get().writes(6u64.saturating_mul(n.into())))
+    }
+
+    fn buy_bundle(n: u32) -> Weight {
+        Weight::from_parts(90_666_033, 0)
+            .saturating_add(Weight::from_parts(142_082_850, 0).saturating_mul(n.into()))
+            .saturating_add(RocksDbWeight::get().reads(16))
+            .saturating_add(RocksDbWeight::get().reads(11u64.saturating_mul(n.into())))
+            .saturating_add(RocksDbWeight::get().writes(8))
+            .saturating_add(RocksDbWeight::get().writes(17u64.saturating_mul(n.into())))
+    }
+
+    fn settle_auction() -> Weight {
+        Weight::from_parts(95_899_000, 0)
+            .saturating_add(RocksDbWeight::get().reads(21))
+            .saturating_add(RocksDbWeight::get().writes(27))
+    }
+
+    fn close_rental() -> Weight {
+        Weight::from_parts(38_044_000, 0)
+            .saturating_add(RocksDbWeight::get().reads(7))
+            .saturating_add(RocksDbWeight::get().writes(6))
+    }
+
+    fn reclaim_gift() -> Weight {
+        Weight::from_parts(8_348_000, 0)
+            .saturating_add(RocksDbWeight::get().reads(2))
+            .saturating_add(RocksDbWeight::get().writes(2))
+    }
+}
@@ -0,0 +1,179 @@
+// Adaptador de activos XCM para los kitties.
+//
+// `KittiesTransactor` es el `TransactAsset` que el ejecutor XCM del runtime usa para los ítems de
+// `KittyCollection`, y los mueve con la interfaz `nonfungibles_v2` del pallet. Un kitty es el
+// activo no fungible `(KittiesLocation, Array32(kitty_id))`.
+//
+// Esta cadena es la reserva de todos los kitties. Retirar un kitty lo pasa a
+// `Pallet::xcm_reserve_account`; depositarlo en una parachain hermana, por reserva
+// (`DepositReserveAsset`) o por teletransporte (`InitiateTeleport`), lo deja allí y apunta en
+// `KittiesAbroad` qué parachain tiene el derivado. Solo esa parachain puede retirarlo o
+// teletransportarlo de vuelta, y depositarlo en una cuenta lo saca de la reserva. Un kitty en la
+// reserva sin entrada en `KittiesAbroad` está a medio camino, en el holding de un mensaje.
+// Es el mismo registro que usan `send_kitty_to_parachain` y `receive_kitty`.
+// Solo se compila con la feature `xcm`.
+
+use super::*;
+use frame::traits::tokens::nonfungibles_v2;
+use xcm::latest::Asset;
+use xcm::latest::AssetId;
+use xcm::latest::AssetInstance;
+use xcm::latest::Error as XcmError;
+use xcm::latest::Fungibility;
+use xcm::latest::Junction;
+use xcm::latest::Location;
+use xcm::latest::Result as XcmResult;
+use xcm::latest::XcmContext;
+use xcm_executor::traits::ConvertLocation;
+use xcm_executor::traits::TransactAsset;
+use xcm_executor::AssetsInHolding;
+
+/// `TransactAsset` de los kitties. `KittiesLocation` es la ubicación de la colección vista desde
+/// esta cadena y `AccountIdConverter` traduce ubicaciones a cuentas locales.
+pub struct KittiesTransactor<T, KittiesLocation, AccountIdConverter>(
+    PhantomData<(T, KittiesLocation, AccountIdConverter)>,
+);
+
+impl<T, KittiesLocation, AccountIdConverter> KittiesTransactor<T, KittiesLocation, AccountIdConverter>
+where
+    T: Config,
+    KittiesLocation: Get<Location>,
+    AccountIdConverter: ConvertLocation<T::AccountId>,
+{
+    /// Id del kitty que representa `what`. Cualquier otro activo no es de este adaptador.
+    fn kitty_id(what: &Asset) -> Result<[u8; 32], XcmError> {
+        match what {
+            Asset { id: AssetId(location), fun: Fungibility::NonFungible(AssetInstance::Array32(kitty_id)) }
+                if *location == KittiesLocation::get() =>
+                Ok(*kitty_id),
+            _ => Err(XcmError::AssetNotFound),
+        }
+    }
+
+    /// Parachain hermana que es `location`, si lo es.
+    fn sibling(location: &Location) -> Option<u32> {
+        match location.unpack() {
+            (1, [Junction::Parachain(para_id)]) => Some(*para_id),
+            _ => None,
+        }
+    }
+
+    /// Cuenta local de `location`.
+    fn account(location: &Location) -> Result<T::AccountId, XcmError> {
+        AccountIdConverter::convert_location(location)
+            .ok_or(XcmError::FailedToTransactAsset("AccountIdConversionFailed"))
+    }
+
+    /// Dueño de `kitty_id` según `nonfungibles_v2`.
+    fn owner(kitty_id: &[u8; 32]) -> Option<T::AccountId> {
+        <Pallet<T> as nonfungibles_v2::Inspect<T::AccountId>>::owner(&T::KittyCollection::get(), kitty_id)
+    }
+
+    /// Pasa `kitty_id` de `from` a `to` con `nonfungibles_v2`.
+    fn transfer(kitty_id: &[u8; 32], from: &T::AccountId, to: &T::AccountId) -> XcmResult {
+        if Self::owner(kitty_id).as_ref() != Some(from) {
+            return Err(XcmError::FailedToTransactAsset("NotOwner"));
+        }
+        <Pallet<T> as nonfungibles_v2::Transfer<T::AccountId>>::transfer(&T::KittyCollection::get(), kitty_id, to)
+            .map_err(|error| XcmError::FailedToTransactAsset(error.into()))
+    }
+
+    /// Comprueba que `kitty_id` está en el holding: en la reserva y sin derivado en otra cadena.
+    fn ensure_in_holding(kitty_id: &[u8; 32]) -> XcmResult {
+        let in_reserve = Self::owner(kitty_id) == Some(Pallet::<T>::xcm_reserve_account());
+        if !in_reserve || KittiesAbroad::<T>::contains_key(kitty_id) {
+            return Err(XcmError::FailedToTransactAsset("KittyNotInHolding"));
+        }
+        Ok(())
+    }
+
+    /// Comprueba que el derivado de `kitty_id` está en `location`.
+    fn ensure_abroad_in(kitty_id: &[u8; 32], location: &Location) -> XcmResult {
+        let para_id = Self::sibling(location);
+        if para_id.is_none() || KittiesAbroad::<T>::get(kitty_id) != para_id {
+            return Err(XcmError::FailedToTransactAsset("NotAbroad"));
+        }
+        Ok(())
+    }
+}
+
+impl<T, KittiesLocation, AccountIdConverter> TransactAsset for KittiesTransactor<T, KittiesLocation, AccountIdConverter>
+where
+    T: Config,
+    KittiesLocation: Get<Location>,
+    AccountIdConverter: ConvertLocation<T::AccountId>,
+{
+    /// Solo entra por teletransporte un kitty cuyo derivado tiene `origin`.
+    fn can_check_in(origin: &Location, what: &Asset, _context: &XcmContext) -> XcmResult {
+        let kitty_id = Self::kitty_id(what)?;
+        Self::ensure_abroad_in(&kitty_id, origin)
+    }
+
+    /// El kitty vuelve al holding; sigue en la reserva hasta que se deposite.
+    fn check_in(_origin: &Location, what: &Asset, _context: &XcmContext) {
+        if let Ok(kitty_id) = Self::kitty_id(what) {
+            KittiesAbroad::<T>::remove(kitty_id);
+        }
+    }
+
+    /// Solo sale por teletransporte, hacia una parachain hermana, un kitty ya retirado.
+    fn can_check_out(dest: &Location, what: &Asset, _context: &XcmContext) -> XcmResult {
+        let kitty_id = Self::kitty_id(what)?;
+        Self::sibling(dest).ok_or(XcmError::FailedToTransactAsset("NotASibling"))?;
+        Self::ensure_in_holding(&kitty_id)
+    }
+
+    /// El kitty se queda en la reserva con su derivado en `dest`.
+    fn check_out(dest: &Location, what: &Asset, _context: &XcmContext) {
+        if let (Ok(kitty_id), Some(para_id)) = (Self::kitty_id(what), Self::sibling(dest)) {
+            KittiesAbroad::<T>::insert(kitty_id, para_id);
+        }
+    }
+
+    /// En una parachain hermana, el kitty queda en reserva para ella; en una cuenta, sale de la
+    /// reserva para esa cuenta.
+    fn deposit_asset(what: &Asset, who: &Location, _context: Option<&XcmContext>) -> XcmResult {
+        let kitty_id = Self::kitty_id(what)?;
+        Self::ensure_in_holding(&kitty_id)?;
+        match Self::sibling(who) {
+            Some(para_id) => {
+                KittiesAbroad::<T>::insert(kitty_id, para_id);
+                Ok(())
+            },
+            None => Self::transfer(&kitty_id, &Pallet::<T>::xcm_reserve_account(), &Self::account(who)?),
+        }
+    }
+
+    /// Una parachain hermana solo retira el kitty cuyo derivado tiene; una cuenta retira los
+    /// suyos, que pasan a la reserva.
+    fn withdraw_asset(
+        what: &Asset,
+        who: &Location,
+        _maybe_context: Option<&XcmContext>,
+    ) -> Result<AssetsInHolding, XcmError> {
+        let kitty_id = Self::kitty_id(what)?;
+        if Self::sibling(who).is_some() {
+            Self::ensure_abroad_in(&kitty_id, who)?;
+            KittiesAbroad::<T>::remove(kitty_id);
+        } else {
+            Self::transfer(&kitty_id, &Self::account(who)?, &Pallet::<T>::xcm_reserve_account())?;
+        }
+        Ok(what.clone().into())
+    }
+
+    /// Entre cuentas locales es una transferencia normal. Hacia o desde una parachain hermana, el
+    /// ejecutor vuelve a `withdraw_asset` y `deposit_asset`.
+    fn internal_transfer_asset(
+        asset: &Asset,
+        from: &Location,
+        to: &Location,
+        _context: &XcmContext,
+    ) -> Result<AssetsInHolding, XcmError> {
+        let kitty_id = Self::kitty_id(asset)?;
+        if Self::sibling(from).is_some() || Self::sibling(to).is_some() {
+            return Err(XcmError::Unimplemented);
+        }
+        Self::transfer(&kitty_id, &Self::account(from)?, &Self::account(to)?)?;
+        Ok(asset.clone().into())
+    }
+}